@@ -0,0 +1,266 @@
+//! Small sorted in-memory store using the helper modules for its layout.
+//!
+//! Users are stored in a table keyed by ID with a secondary index by email
+//! domain. Values are also encoded with strkey for simplicity. The tests exercise lookups, ranges, pagination, and a migration
+//! of the key layout.
+use std::{collections::BTreeMap, ops::Bound};
+
+use serde::{Deserialize, Serialize};
+use strkey::{
+    query::Query,
+    range::ByteRange,
+    tables::{Table, TableRegistry},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct User {
+    id: u32,
+    domain: String,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct DomainIndexKey {
+    domain: String,
+    id: u32,
+}
+
+const USERS: Table<u32, User> = Table::new("user");
+const USERS_BY_DOMAIN: Table<DomainIndexKey, ()> = Table::new("user_by_domain");
+
+#[derive(Default)]
+struct Store {
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Store {
+    fn insert_user(&mut self, user: &User) -> Result<(), strkey::Error> {
+        let value = strkey::to_vec(user)?;
+        let index_key = DomainIndexKey {
+            domain: user.domain.clone(),
+            id: user.id,
+        };
+
+        self.entries.insert(USERS.encode_key(&user.id)?, value);
+        self.entries
+            .insert(USERS_BY_DOMAIN.encode_key(&index_key)?, Vec::new());
+
+        Ok(())
+    }
+
+    fn get_user(&self, id: u32) -> Result<Option<User>, strkey::Error> {
+        match self.entries.get(&USERS.encode_key(&id)?) {
+            Some(value) => Ok(Some(strkey::from_slice(value)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn users_in_domain(&self, domain: &str) -> Result<Vec<u32>, strkey::Error> {
+        let range = Query::on::<(String, DomainIndexKey)>()
+            .eq(USERS_BY_DOMAIN.name())
+            .eq(domain)
+            .encode()?;
+
+        self.entries
+            .range(range)
+            .map(|(key, _value)| Ok(USERS_BY_DOMAIN.decode_key(key)?.id))
+            .collect()
+    }
+
+    fn user_ids_between(&self, start: u32, end: u32) -> Result<Vec<u32>, strkey::Error> {
+        let range = Query::on::<(String, u32)>()
+            .eq(USERS.name())
+            .between(start, end)
+            .encode()?;
+
+        self.entries
+            .range(range)
+            .map(|(key, _value)| USERS.decode_key(key))
+            .collect()
+    }
+
+    /// Returns a page of user IDs and the cursor of the next page.
+    fn user_page(
+        &self,
+        cursor: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<(Vec<u32>, Option<Vec<u8>>), strkey::Error> {
+        let (start, end): ByteRange = USERS.prefix_range()?;
+        let start = match cursor {
+            Some(cursor) => Bound::Excluded(cursor.to_vec()),
+            None => start,
+        };
+
+        let keys = self
+            .entries
+            .range((start, end))
+            .take(limit)
+            .map(|(key, _value)| key.clone())
+            .collect::<Vec<_>>();
+        let ids = keys
+            .iter()
+            .map(|key| USERS.decode_key(key))
+            .collect::<Result<Vec<_>, _>>()?;
+        let cursor = if keys.len() == limit {
+            keys.last().cloned()
+        } else {
+            None
+        };
+
+        Ok((ids, cursor))
+    }
+
+    /// Rewrites the keys of a table from an old key type to a new key type.
+    fn migrate<Old, New, F>(
+        &mut self,
+        old_table: &Table<Old, User>,
+        new_table: &Table<New, User>,
+        convert: F,
+    ) -> Result<usize, strkey::Error>
+    where
+        Old: for<'de> Deserialize<'de>,
+        New: Serialize,
+        F: Fn(Old) -> New,
+    {
+        let range = old_table.prefix_range()?;
+        let old_keys = self
+            .entries
+            .range(range)
+            .map(|(key, _value)| key.clone())
+            .collect::<Vec<_>>();
+
+        for old_key in &old_keys {
+            let value = self.entries.remove(old_key).unwrap();
+            let new_key = new_table.encode_key(&convert(old_table.decode_key(old_key)?))?;
+            self.entries.insert(new_key, value);
+        }
+
+        Ok(old_keys.len())
+    }
+}
+
+fn sample_store() -> Result<Store, strkey::Error> {
+    let mut store = Store::default();
+
+    for (id, domain) in [
+        (3, "b.example"),
+        (1, "a.example"),
+        (2, "b.example"),
+        (10, "a.example"),
+    ] {
+        store.insert_user(&User {
+            id,
+            domain: domain.to_string(),
+            name: format!("user{}", id),
+        })?;
+    }
+
+    Ok(store)
+}
+
+fn main() -> Result<(), strkey::Error> {
+    let store = sample_store()?;
+    let mut registry = TableRegistry::new();
+    registry.register(&USERS)?;
+    registry.register(&USERS_BY_DOMAIN)?;
+
+    for key in store.entries.keys() {
+        println!(
+            "{:16} {}",
+            registry.identify(key).unwrap_or("?"),
+            String::from_utf8_lossy(key)
+        );
+    }
+
+    println!("user 2: {:?}", store.get_user(2)?);
+    println!("b.example: {:?}", store.users_in_domain("b.example")?);
+    println!("IDs 2 to 10: {:?}", store.user_ids_between(2, 10)?);
+
+    let (page, cursor) = store.user_page(None, 2)?;
+    println!("first page: {:?}", page);
+    println!(
+        "second page: {:?}",
+        store.user_page(cursor.as_deref(), 2)?.0
+    );
+
+    const USERS_V2: Table<u64, User> = Table::new("user_v2");
+    let mut store = store;
+    let count = store.migrate(&USERS, &USERS_V2, u64::from)?;
+    println!("migrated {} users", count);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use strkey::{FieldType, Schema};
+
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let store = sample_store().unwrap();
+
+        assert_eq!(store.get_user(2).unwrap().unwrap().domain, "b.example");
+        assert_eq!(store.get_user(4).unwrap(), None);
+    }
+
+    #[test]
+    fn test_secondary_index() {
+        let store = sample_store().unwrap();
+
+        assert_eq!(store.users_in_domain("a.example").unwrap(), vec![1, 10]);
+        assert_eq!(store.users_in_domain("b.example").unwrap(), vec![2, 3]);
+        assert!(store.users_in_domain("a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_range() {
+        let store = sample_store().unwrap();
+
+        assert_eq!(store.user_ids_between(2, 10).unwrap(), vec![2, 3, 10]);
+        assert_eq!(store.user_ids_between(4, 9).unwrap(), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_pagination() {
+        let store = sample_store().unwrap();
+        let mut cursor = None;
+        let mut pages = Vec::new();
+
+        loop {
+            let (ids, next) = store.user_page(cursor.as_deref(), 3).unwrap();
+            pages.push(ids);
+
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        assert_eq!(pages, vec![vec![1, 2, 3], vec![10]]);
+    }
+
+    #[test]
+    fn test_migration() {
+        const USERS_V1: Table<u16, User> = Table::new("user_v1");
+        const USERS_V2: Table<u32, User> = Table::new("user_v2");
+
+        let old_schema = Schema::new(vec![FieldType::Str, FieldType::U16]);
+        let new_schema = Schema::new(vec![FieldType::Str, FieldType::U32]);
+        assert!(!new_schema.compatible_with(&old_schema).is_empty());
+
+        let mut store = Store::default();
+        store
+            .entries
+            .insert(USERS_V1.encode_key(&7).unwrap(), Vec::new());
+
+        let count = store.migrate(&USERS_V1, &USERS_V2, u32::from).unwrap();
+
+        assert_eq!(count, 1);
+        assert_eq!(
+            store.entries.keys().collect::<Vec<_>>(),
+            vec![&b"user_v2:00000007".to_vec()]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Canonical keys
+//!
+//! Decoding is lenient in some ways, such as accepting uppercase hexadecimal
+//! digits, so different keys can decode to the same value. These functions
+//! re-encode a key to find its canonical form, which is useful for
+//! deduplicating keys from external sources.
+//!
+//! Example:
+//!
+//! ```rust
+//! # fn main() -> Result<(), strkey::Error> {
+//! assert_eq!(strkey::canonicalize::<(&str, u32)>(b"account:000004d2")?, b"account:000004d2");
+//! assert!(strkey::canonicalize::<(&str, u32)>(b"account:000004D2").is_err());
+//!
+//! assert_eq!(
+//!     strkey::canonical::normalize::<(&str, u32)>(b"account:000004D2")?,
+//!     b"account:000004d2"
+//! );
+//! # Ok(())
+//! # }
+//! ```
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// Decodes and re-encodes a key, verifying that it was already canonical.
+///
+/// Returns the key if it is canonical. Returns [`Error::Data`] with the
+/// offset of the first differing byte if it is not.
+pub fn canonicalize<'a, T>(key: &'a [u8]) -> Result<Vec<u8>, Error>
+where
+    T: Deserialize<'a> + Serialize,
+{
+    let canonical = normalize::<T>(key)?;
+
+    if let Some(offset) = mismatch(key, &canonical) {
+        return Err(Error::Data(format!(
+            "key is not canonical at byte {}",
+            offset
+        )));
+    }
+
+    Ok(canonical)
+}
+
+/// Decodes and re-encodes a key, returning its canonical form.
+pub fn normalize<'a, T>(key: &'a [u8]) -> Result<Vec<u8>, Error>
+where
+    T: Deserialize<'a> + Serialize,
+{
+    let value = crate::from_slice::<T>(key)?;
+
+    crate::to_vec(&value)
+}
+
+/// Returns whether the key is valid and canonical for the type.
+pub fn is_canonical<'a, T>(key: &'a [u8]) -> bool
+where
+    T: Deserialize<'a> + Serialize,
+{
+    canonicalize::<T>(key).is_ok()
+}
+
+fn mismatch(key: &[u8], canonical: &[u8]) -> Option<usize> {
+    key.iter()
+        .zip(canonical)
+        .position(|(a, b)| a != b)
+        .or_else(|| (key.len() != canonical.len()).then(|| key.len().min(canonical.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonicalize() {
+        assert_eq!(
+            canonicalize::<(String, i8, f32)>(b"a:7f:3f800000").unwrap(),
+            b"a:7f:3f800000"
+        );
+        assert!(canonicalize::<(String, i8)>(b"a:7F").is_err());
+        assert!(canonicalize::<(String, i8)>(b"a:7").is_err());
+        assert!(is_canonical::<u16>(b"00ff"));
+        assert!(!is_canonical::<u16>(b"00FF"));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize::<(String, i8)>(b"a:7F").unwrap(), b"a:7f");
+        assert!(normalize::<u8>(b"xx").is_err());
+    }
+
+    #[test]
+    fn test_mismatch() {
+        assert_eq!(mismatch(b"abc", b"abc"), None);
+        assert_eq!(mismatch(b"abc", b"abd"), Some(2));
+        assert_eq!(mismatch(b"ab", b"abc"), Some(2));
+    }
+}
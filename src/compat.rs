@@ -0,0 +1,102 @@
+//! Compatibility with the bytekey encoding
+//!
+//! The [bytekey](https://crates.io/crates/bytekey-fix) encoding is a binary
+//! format that shares the same integer and floating point transformations as
+//! strkey. The functions in this module allow keys to be converted between the
+//! two encodings so existing databases can be migrated incrementally.
+//!
+//! Requires the `bytekey` feature.
+//!
+//! Example:
+//!
+//! ```rust
+//! # fn main() -> Result<(), strkey::Error> {
+//! let bytekey_key = strkey::compat::to_bytekey(&("account", 1234u32))?;
+//! let strkey_key = strkey::compat::bytekey_to_strkey::<(String, u32)>(&bytekey_key)?;
+//!
+//! assert_eq!(&strkey_key, b"account:000004d2");
+//! # Ok(())
+//! # }
+//! ```
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// Serializes the given value to a vector using the bytekey encoding.
+pub fn to_bytekey<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+    T: Serialize,
+{
+    Ok(bytekey::serialize(value).map_err(bytekey::Error::from)?)
+}
+
+/// Deserializes the value from a byte array slice in the bytekey encoding.
+pub fn from_bytekey<T>(value: &[u8]) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    Ok(bytekey::deserialize(value).map_err(bytekey::Error::from)?)
+}
+
+/// Converts a bytekey encoded key to strkey encoding.
+///
+/// The key is decoded as `T` and then serialized using the default options.
+pub fn bytekey_to_strkey<T>(value: &[u8]) -> Result<Vec<u8>, Error>
+where
+    T: Serialize + DeserializeOwned,
+{
+    let value = from_bytekey::<T>(value)?;
+    crate::to_vec(&value)
+}
+
+/// Converts a strkey encoded key to bytekey encoding.
+///
+/// The key is decoded as `T` using the default options and then serialized
+/// using the bytekey encoding.
+pub fn strkey_to_bytekey<'a, T>(value: &'a [u8]) -> Result<Vec<u8>, Error>
+where
+    T: Serialize + Deserialize<'a>,
+{
+    let value = crate::from_slice::<T>(value)?;
+    to_bytekey(&value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_bytekey() {
+        let key = to_bytekey(&(1234u32, "abc")).unwrap();
+        assert_eq!(&key, b"\x00\x00\x04\xd2abc\xff");
+    }
+
+    #[test]
+    fn test_from_bytekey() {
+        let value = from_bytekey::<(u32, String)>(b"\x00\x00\x04\xd2abc\xff").unwrap();
+        assert_eq!(value, (1234, "abc".to_string()));
+
+        assert!(from_bytekey::<u32>(b"\x00").is_err());
+    }
+
+    #[test]
+    fn test_transcode() {
+        let key = bytekey_to_strkey::<(i16, f32)>(b"\xb0\x39\xc4\x9a\x51\xec").unwrap();
+        assert_eq!(&key, b"b039:c49a51ec");
+
+        let key = strkey_to_bytekey::<(i16, f32)>(&key).unwrap();
+        assert_eq!(&key, b"\xb0\x39\xc4\x9a\x51\xec");
+    }
+
+    #[test]
+    fn test_transcode_preserves_order() {
+        let values = [(-5i32, "a"), (-5, "b"), (0, "a"), (7, "")];
+        let mut keys = Vec::new();
+
+        for value in values.iter() {
+            keys.push(bytekey_to_strkey::<(i32, String)>(&to_bytekey(value).unwrap()).unwrap());
+        }
+
+        assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
@@ -0,0 +1,206 @@
+//! Prefix compression of sorted keys
+//!
+//! Adjacent keys in a sorted run usually share a long prefix. A compressed
+//! key stores the length of the prefix shared with the previous key as a
+//! LEB128 variable-length integer followed by the remaining suffix, as in
+//! SSTable blocks. Decompressing needs the same previous key.
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::compress::{compress_key, decompress_key};
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let previous = strkey::to_vec(&("user", 1u8))?;
+//! let key = strkey::to_vec(&("user", 2u8))?;
+//!
+//! let compressed = compress_key(&previous, &key);
+//! assert_eq!(compressed, b"\x062");
+//! assert_eq!(decompress_key(&previous, &compressed)?, key);
+//! # Ok(())
+//! # }
+//! ```
+use std::convert::TryFrom;
+
+use crate::error::Error;
+
+/// Returns the length of the prefix shared by the two keys.
+pub fn shared_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Compresses the key against the previous key.
+pub fn compress_key(previous: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut output = Vec::new();
+    compress_key_into(previous, key, &mut output);
+    output
+}
+
+/// Compresses the key against the previous key, appending to the output.
+pub fn compress_key_into(previous: &[u8], key: &[u8], output: &mut Vec<u8>) {
+    let shared = shared_prefix_len(previous, key);
+
+    write_varint(shared as u64, output);
+    output.extend_from_slice(&key[shared..]);
+}
+
+/// Decompresses a key compressed against the previous key.
+///
+/// Returns [`Error::Syntax`] if the shared length is malformed or longer
+/// than the previous key.
+pub fn decompress_key(previous: &[u8], compressed: &[u8]) -> Result<Vec<u8>, Error> {
+    let (shared, suffix) = read_varint(compressed)?;
+    let prefix = usize::try_from(shared)
+        .ok()
+        .and_then(|shared| previous.get(..shared))
+        .ok_or(Error::Syntax)?;
+
+    let mut key = Vec::with_capacity(prefix.len() + suffix.len());
+    key.extend_from_slice(prefix);
+    key.extend_from_slice(suffix);
+
+    Ok(key)
+}
+
+/// Compresses a sequence of keys, each against the one before it.
+///
+/// The first key is compressed against an empty key.
+#[derive(Debug, Clone, Default)]
+pub struct PrefixCompressor {
+    previous: Vec<u8>,
+}
+
+impl PrefixCompressor {
+    /// Construct a compressor with no previous key.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compresses the key and remembers it as the previous key.
+    pub fn compress(&mut self, key: &[u8]) -> Vec<u8> {
+        let output = compress_key(&self.previous, key);
+        self.set_previous(key);
+        output
+    }
+
+    /// Returns the previous key.
+    pub fn previous(&self) -> &[u8] {
+        &self.previous
+    }
+
+    /// Forgets the previous key.
+    pub fn reset(&mut self) {
+        self.previous.clear();
+    }
+
+    fn set_previous(&mut self, key: &[u8]) {
+        self.previous.clear();
+        self.previous.extend_from_slice(key);
+    }
+}
+
+/// Decompresses a sequence of keys written by [`PrefixCompressor`].
+#[derive(Debug, Clone, Default)]
+pub struct PrefixDecompressor {
+    previous: Vec<u8>,
+}
+
+impl PrefixDecompressor {
+    /// Construct a decompressor with no previous key.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decompresses the key and remembers it as the previous key.
+    pub fn decompress(&mut self, compressed: &[u8]) -> Result<Vec<u8>, Error> {
+        let key = decompress_key(&self.previous, compressed)?;
+        self.previous.clone_from(&key);
+        Ok(key)
+    }
+
+    /// Returns the previous key.
+    pub fn previous(&self) -> &[u8] {
+        &self.previous
+    }
+
+    /// Forgets the previous key.
+    pub fn reset(&mut self) {
+        self.previous.clear();
+    }
+}
+
+pub(crate) fn write_varint(mut value: u64, output: &mut Vec<u8>) {
+    while value >= 0x80 {
+        output.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    output.push(value as u8);
+}
+
+pub(crate) fn read_varint(input: &[u8]) -> Result<(u64, &[u8]), Error> {
+    let mut value = 0u64;
+
+    for (index, &byte) in input.iter().enumerate().take(10) {
+        let bits = u64::from(byte & 0x7f);
+
+        if index == 9 && bits > 1 {
+            return Err(Error::Syntax);
+        }
+
+        value |= bits << (7 * index);
+
+        if byte & 0x80 == 0 {
+            return Ok((value, &input[index + 1..]));
+        }
+    }
+
+    Err(Error::Syntax)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress_key() {
+        assert_eq!(compress_key(b"", b"abc"), b"\x00abc");
+        assert_eq!(compress_key(b"abc", b"abd"), b"\x02d");
+        assert_eq!(compress_key(b"abc", b"ab"), b"\x02");
+        assert_eq!(decompress_key(b"abc", b"\x02d").unwrap(), b"abd");
+        assert_eq!(decompress_key(b"abc", b"\x02").unwrap(), b"ab");
+
+        assert!(matches!(decompress_key(b"a", b"\x02d"), Err(Error::Syntax)));
+        assert!(matches!(decompress_key(b"a", b""), Err(Error::Syntax)));
+        assert!(matches!(decompress_key(b"a", b"\x80"), Err(Error::Syntax)));
+    }
+
+    #[test]
+    fn test_compressor() {
+        let keys: Vec<&[u8]> = vec![b"a:01", b"a:02", b"b:01", b"b:01:x"];
+        let mut compressor = PrefixCompressor::new();
+        let mut decompressor = PrefixDecompressor::new();
+
+        for key in keys {
+            let compressed = compressor.compress(key);
+            assert_eq!(decompressor.decompress(&compressed).unwrap(), key);
+            assert_eq!(compressor.previous(), decompressor.previous());
+        }
+
+        compressor.reset();
+        assert!(compressor.previous().is_empty());
+    }
+
+    #[test]
+    fn test_varint() {
+        for value in [0, 1, 127, 128, 300, u64::MAX] {
+            let mut output = Vec::new();
+            write_varint(value, &mut output);
+            output.push(0xff);
+
+            assert_eq!(read_varint(&output).unwrap(), (value, &[0xff][..]));
+        }
+
+        assert!(read_varint(&[0xff; 10]).is_err());
+    }
+}
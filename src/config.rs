@@ -0,0 +1,1879 @@
+//! Configuration
+//!
+//! [`Config`] bundles the serializer and deserializer options so the same
+//! options are used for writing and reading keys. It also holds
+//! [`EncodingOverrides`] which change the encoding of values selected by
+//! newtype name or struct field name without wrapping each field in an
+//! adapter type.
+//!
+//! Example:
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use strkey::config::{Config, Encoding, EncodingOverrides};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct EventKey {
+//!     kind: String,
+//!     created_ts: u64,
+//!     id: u16,
+//! }
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let config = Config::new()
+//!     .with_overrides(EncodingOverrides::new().with_field("*_ts", Encoding::Decimal));
+//! let key = EventKey {
+//!     kind: "login".to_string(),
+//!     created_ts: 1618557093467,
+//!     id: 5,
+//! };
+//! let encoded = config.to_vec(&key)?;
+//!
+//! assert_eq!(&encoded, b"login:00000001618557093467:0005");
+//! assert_eq!(config.from_slice::<EventKey>(&encoded)?, key);
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    convert::TryFrom,
+    io::Read,
+    io::Write,
+    sync::Arc,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    de::{find_subslice, split_components, Deserializer, IoReader, SliceReader},
+    error::{ConfigError, Error},
+    hex_codec::encode_extend,
+    ser::Serializer,
+};
+
+/// Encoding of integers, floating point numbers, and byte arrays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Encoding {
+    /// Hexadecimal, which is the default.
+    #[default]
+    Hex,
+
+    /// Zero-padded decimal.
+    ///
+    /// The width is the number of digits of the largest value of the type,
+    /// such as 20 digits for 64-bit values. Signed integers and floating point
+    /// numbers are written as the decimal of their sortable unsigned
+    /// representation. Byte arrays are not supported.
+    Decimal,
+
+    /// Lowercase base32 with the extended hex alphabet (`0-9a-v`) and no padding.
+    ///
+    /// The alphabet preserves the sort order of the encoded bytes.
+    Base32,
+}
+
+/// Encoding of signed integers into their unsigned representation.
+///
+/// The unsigned representation is then written using the [`Encoding`] in
+/// effect, so `Biased` combined with [`Encoding::Decimal`] produces the
+/// biased decimal integers used by some other key schemes. Both variants
+/// preserve the order of values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SignedEncoding {
+    /// Flip the sign bit, which is the default.
+    ///
+    /// This is the same as adding a bias of half the range of the type.
+    #[default]
+    Offset,
+
+    /// Add the given bias.
+    ///
+    /// Values whose biased representation does not fit in the unsigned
+    /// type of the same width return [`Error::Data`].
+    Biased(u64),
+}
+
+impl SignedEncoding {
+    /// Returns the unsigned representation of a value of the given width.
+    pub(crate) fn encode(self, value: i128, bits: u32) -> Result<u128, Error> {
+        let mask = u128::MAX >> (128 - bits);
+
+        match self {
+            SignedEncoding::Offset => Ok((value as u128 ^ 1 << (bits - 1)) & mask),
+            SignedEncoding::Biased(bias) => value
+                .checked_add(bias.into())
+                .filter(|encoded| *encoded >= 0 && *encoded as u128 <= mask)
+                .map(|encoded| encoded as u128)
+                .ok_or_else(|| Error::Data(value.to_string())),
+        }
+    }
+
+    /// Returns the value of the given width from its unsigned representation.
+    pub(crate) fn decode(self, encoded: u128, bits: u32) -> Result<i128, Error> {
+        let shift = 128 - bits;
+
+        match self {
+            SignedEncoding::Offset => Ok(((encoded ^ 1 << (bits - 1)) << shift) as i128 >> shift),
+            SignedEncoding::Biased(bias) => i128::try_from(encoded)
+                .ok()
+                .map(|encoded| encoded - i128::from(bias))
+                .filter(|value| *value == (*value << shift) >> shift)
+                .ok_or_else(|| Error::Data(encoded.to_string())),
+        }
+    }
+}
+
+/// Registry of encoding overrides.
+///
+/// Overrides are matched against the names of newtype structs and the names
+/// of struct fields. A field pattern may begin or end with `*` to match a
+/// suffix or prefix of the field name. Fields are matched by name only, not
+/// by type, so a pattern such as `*_ts` applies to every field with that
+/// suffix whatever its type. A field that matches no pattern keeps the
+/// encoding of its enclosing value, such as a newtype with an override.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EncodingOverrides {
+    newtypes: HashMap<String, Encoding>,
+    fields: Vec<(String, Encoding)>,
+}
+
+impl EncodingOverrides {
+    /// Construct an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the registry has no overrides.
+    pub fn is_empty(&self) -> bool {
+        self.newtypes.is_empty() && self.fields.is_empty()
+    }
+
+    /// Returns the encodings used by the overrides.
+    pub fn encodings(&self) -> impl Iterator<Item = Encoding> + '_ {
+        self.newtypes
+            .values()
+            .chain(self.fields.iter().map(|(_, encoding)| encoding))
+            .copied()
+    }
+
+    /// Sets the encoding of values of the newtype struct with the given name.
+    pub fn insert_newtype<S: Into<String>>(&mut self, name: S, encoding: Encoding) {
+        self.newtypes.insert(name.into(), encoding);
+    }
+
+    /// Sets the encoding of values of the newtype struct with the given name and returns the registry.
+    pub fn with_newtype<S: Into<String>>(mut self, name: S, encoding: Encoding) -> Self {
+        self.insert_newtype(name, encoding);
+        self
+    }
+
+    /// Sets the encoding of struct fields matching the given pattern.
+    ///
+    /// Patterns are checked in insertion order.
+    pub fn insert_field<S: Into<String>>(&mut self, pattern: S, encoding: Encoding) {
+        self.fields.push((pattern.into(), encoding));
+    }
+
+    /// Sets the encoding of struct fields matching the given pattern and returns the registry.
+    pub fn with_field<S: Into<String>>(mut self, pattern: S, encoding: Encoding) -> Self {
+        self.insert_field(pattern, encoding);
+        self
+    }
+
+    /// Returns the encoding of the newtype struct with the given name.
+    pub fn newtype(&self, name: &str) -> Option<Encoding> {
+        self.newtypes.get(name).copied()
+    }
+
+    /// Returns the encoding of the struct field with the given name.
+    pub fn field(&self, name: &str) -> Option<Encoding> {
+        self.fields
+            .iter()
+            .find(|(pattern, _)| pattern_matches(pattern, name))
+            .map(|(_, encoding)| *encoding)
+    }
+}
+
+/// Registry of alternative names of enum unit variants accepted on decode.
+///
+/// Aliases allow a variant to be renamed without rewriting existing keys at
+/// once: the serializer writes the new name while the deserializer accepts
+/// both. Aliases declared with `#[serde(alias = "...")]` are also accepted
+/// without registering them here.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VariantAliases {
+    aliases: HashMap<(String, String), String>,
+}
+
+impl VariantAliases {
+    /// Construct an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the registry has no aliases.
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// Sets the alias of the variant of the enum with the given name.
+    pub fn insert<E, A, V>(&mut self, enum_name: E, alias: A, variant: V)
+    where
+        E: Into<String>,
+        A: Into<String>,
+        V: Into<String>,
+    {
+        self.aliases
+            .insert((enum_name.into(), alias.into()), variant.into());
+    }
+
+    /// Sets the alias of the variant of the enum with the given name and returns the registry.
+    pub fn with<E, A, V>(mut self, enum_name: E, alias: A, variant: V) -> Self
+    where
+        E: Into<String>,
+        A: Into<String>,
+        V: Into<String>,
+    {
+        self.insert(enum_name, alias, variant);
+        self
+    }
+
+    /// Returns the variant name of the alias of the enum with the given name.
+    pub fn variant(&self, enum_name: &str, alias: &str) -> Option<&str> {
+        self.aliases
+            .get(&(enum_name.to_string(), alias.to_string()))
+            .map(String::as_str)
+    }
+}
+
+/// Registry of short codes written in place of enum unit variant names.
+///
+/// Codes shorten keys where variant names are the longest components. The
+/// serializer writes the code instead of the variant name, as renamed by
+/// `#[serde(rename)]` or `#[serde(rename_all)]`, and the deserializer maps
+/// the code back. Codes must be unique within an enum. A code should not be
+/// the name of another variant of the same enum without a code; such keys
+/// are rejected as ambiguous on decode.
+///
+/// Example:
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+/// use strkey::config::{Config, VariantCodes};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// enum Kind {
+///     Administrator,
+///     Member,
+/// }
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let codes = VariantCodes::new()
+///     .with("Kind", "Administrator", "a")?
+///     .with("Kind", "Member", "m")?;
+/// let config = Config::new().with_variant_codes(codes);
+///
+/// let key = config.to_vec(&(Kind::Member, 1u8))?;
+/// assert_eq!(key, b"m:01");
+/// assert_eq!(config.from_slice::<(Kind, u8)>(&key)?, (Kind::Member, 1));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VariantCodes {
+    codes: HashMap<(String, String), String>,
+    variants: HashMap<(String, String), String>,
+}
+
+impl VariantCodes {
+    /// Construct an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the registry has no codes.
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    /// Sets the code of the variant of the enum with the given name.
+    ///
+    /// Returns [`Error::Other`] if the code is empty or is already the code
+    /// of another variant of the enum.
+    pub fn insert<E, V, C>(&mut self, enum_name: E, variant: V, code: C) -> Result<(), Error>
+    where
+        E: Into<String>,
+        V: Into<String>,
+        C: Into<String>,
+    {
+        let enum_name = enum_name.into();
+        let variant = variant.into();
+        let code = code.into();
+
+        if code.is_empty() {
+            return Err(Error::Other(format!("empty code for variant {}", variant)));
+        }
+
+        match self.variants.get(&(enum_name.clone(), code.clone())) {
+            Some(existing) if existing != &variant => {
+                return Err(Error::Other(format!(
+                    "code {} of {} is already used by {}",
+                    code, variant, existing
+                )));
+            }
+            _ => {}
+        }
+
+        if let Some(previous) = self
+            .codes
+            .insert((enum_name.clone(), variant.clone()), code.clone())
+        {
+            self.variants.remove(&(enum_name.clone(), previous));
+        }
+
+        self.variants.insert((enum_name, code), variant);
+
+        Ok(())
+    }
+
+    /// Sets the code of the variant of the enum with the given name and returns the registry.
+    pub fn with<E, V, C>(mut self, enum_name: E, variant: V, code: C) -> Result<Self, Error>
+    where
+        E: Into<String>,
+        V: Into<String>,
+        C: Into<String>,
+    {
+        self.insert(enum_name, variant, code)?;
+        Ok(self)
+    }
+
+    /// Returns the code of the variant of the enum with the given name.
+    pub fn code(&self, enum_name: &str, variant: &str) -> Option<&str> {
+        self.codes
+            .get(&(enum_name.to_string(), variant.to_string()))
+            .map(String::as_str)
+    }
+
+    /// Returns the variant name of the code of the enum with the given name.
+    pub fn variant(&self, enum_name: &str, code: &str) -> Option<&str> {
+        self.variants
+            .get(&(enum_name.to_string(), code.to_string()))
+            .map(String::as_str)
+    }
+
+    /// Checks that no code contains the given deliminator.
+    ///
+    /// The serializer also checks each code it writes.
+    pub fn validate(&self, deliminator: &[u8]) -> Result<(), Error> {
+        match self
+            .codes
+            .values()
+            .find(|code| find_subslice(code.as_bytes(), deliminator).is_some())
+        {
+            Some(code) => Err(Error::Data(code.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks that the deliminator cannot occur within a component.
+pub(crate) fn validate_deliminator<I>(
+    deliminator: &[u8],
+    percent_encoding: bool,
+    encodings: I,
+) -> Result<(), ConfigError>
+where
+    I: IntoIterator<Item = Encoding>,
+{
+    if deliminator.is_empty() {
+        return Err(ConfigError::EmptyDeliminator);
+    }
+
+    for encoding in std::iter::once(Encoding::Hex).chain(encodings) {
+        let alphabet: &[u8] = match encoding {
+            Encoding::Hex => b"0123456789abcdef",
+            Encoding::Decimal => b"0123456789",
+            Encoding::Base32 => BASE32_ALPHABET,
+        };
+
+        if deliminator.iter().all(|byte| alphabet.contains(byte)) {
+            return Err(ConfigError::EncodingCollision(encoding));
+        }
+    }
+
+    if find_subslice(b"true", deliminator).is_some()
+        || find_subslice(b"false", deliminator).is_some()
+    {
+        return Err(ConfigError::BoolCollision);
+    }
+
+    if percent_encoding
+        && deliminator
+            .iter()
+            .all(|&byte| byte.is_ascii_alphanumeric() || b"-._~%".contains(&byte))
+    {
+        return Err(ConfigError::EscapeCollision);
+    }
+
+    Ok(())
+}
+
+fn pattern_matches(pattern: &str, name: &str) -> bool {
+    if pattern == "*" {
+        true
+    } else if let Some(suffix) = pattern.strip_prefix('*') {
+        name.ends_with(suffix)
+    } else if let Some(prefix) = pattern.strip_suffix('*') {
+        name.starts_with(prefix)
+    } else {
+        pattern == name
+    }
+}
+
+/// Sort direction of components chosen at runtime.
+///
+/// Components are identified by their index in the key, starting at zero.
+/// Units do not count as components. Descending components have their bits
+/// inverted before encoding, like [`crate::types::Desc`], so they sort in
+/// reverse order. Only integers and floating point numbers can be descending;
+/// other types return [`Error::UnsupportedType`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SortSpec {
+    descending: BTreeSet<usize>,
+}
+
+impl SortSpec {
+    /// Construct a specification where all components are ascending.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether all components are ascending.
+    pub fn is_empty(&self) -> bool {
+        self.descending.is_empty()
+    }
+
+    /// Sets whether the component at the given index is descending.
+    pub fn set_descending(&mut self, index: usize, value: bool) {
+        if value {
+            self.descending.insert(index);
+        } else {
+            self.descending.remove(&index);
+        }
+    }
+
+    /// Sets the component at the given index as descending and returns the specification.
+    pub fn with_descending(mut self, index: usize) -> Self {
+        self.set_descending(index, true);
+        self
+    }
+
+    /// Returns whether the component at the given index is descending.
+    pub fn is_descending(&self, index: usize) -> bool {
+        self.descending.contains(&index)
+    }
+}
+
+/// Function used by [`Mask::Hash`].
+pub type HashFn = Arc<dyn Fn(&[u8]) -> Vec<u8> + Send + Sync>;
+
+/// Replacement of a redacted component.
+#[derive(Clone)]
+pub enum Mask {
+    /// Replaces the component with the given text.
+    Fixed(String),
+
+    /// Replaces the component with the hexadecimal result of the function
+    /// applied to the encoded component.
+    ///
+    /// The function should be a keyed hash, such as an HMAC with a secret
+    /// key, so the original value cannot be recovered by hashing guesses.
+    Hash(HashFn),
+}
+
+impl std::fmt::Debug for Mask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mask::Fixed(text) => f.debug_tuple("Fixed").field(text).finish(),
+            Mask::Hash(_) => f.write_str("Hash(..)"),
+        }
+    }
+}
+
+/// Components replaced with a mask on encode.
+///
+/// Components are identified by their index in the key, starting at zero,
+/// like [`SortSpec`]. Redacted keys are intended for replicas that must not
+/// contain the original values and generally cannot be decoded.
+#[derive(Debug, Clone, Default)]
+pub struct Redaction {
+    masks: BTreeMap<usize, Mask>,
+}
+
+impl Redaction {
+    /// Construct a redaction that replaces no components.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether no components are replaced.
+    pub fn is_empty(&self) -> bool {
+        self.masks.is_empty()
+    }
+
+    /// Sets the mask of the component at the given index.
+    pub fn insert(&mut self, index: usize, mask: Mask) {
+        self.masks.insert(index, mask);
+    }
+
+    /// Sets the mask of the component at the given index and returns the redaction.
+    pub fn with(mut self, index: usize, mask: Mask) -> Self {
+        self.insert(index, mask);
+        self
+    }
+
+    /// Returns the mask of the component at the given index.
+    pub fn mask(&self, index: usize) -> Option<&Mask> {
+        self.masks.get(&index)
+    }
+
+    /// Returns the already encoded key with the components replaced.
+    ///
+    /// The key is split on the deliminator, so a string component containing
+    /// the deliminator is seen as several components and only partly
+    /// replaced. Encode through a [`crate::Serializer`] with
+    /// [`crate::Serializer::set_redaction`], such as by [`Config::to_vec`],
+    /// to replace components as they are written instead.
+    ///
+    /// Returns [`Error::Data`] if a fixed mask contains the deliminator.
+    pub fn apply(&self, key: &[u8], deliminator: &[u8]) -> Result<Vec<u8>, Error> {
+        if self.is_empty() {
+            return Ok(key.to_vec());
+        }
+
+        let mut output = Vec::with_capacity(key.len());
+
+        for (index, component) in split_components(key, deliminator).enumerate() {
+            if index != 0 {
+                output.extend_from_slice(deliminator);
+            }
+
+            match self.masks.get(&index) {
+                Some(Mask::Fixed(text)) => {
+                    if find_subslice(text.as_bytes(), deliminator).is_some() {
+                        return Err(Error::Data(text.clone()));
+                    }
+
+                    output.extend_from_slice(text.as_bytes());
+                }
+                Some(Mask::Hash(function)) => encode_extend(&function(component), &mut output),
+                None => output.extend_from_slice(component),
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+/// Option that can cause keys to sort in a different order than their
+/// values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrderingIssue {
+    /// The deliminator is not a NUL byte, so a string component followed by
+    /// another component can sort after a longer string that starts with it.
+    Deliminator,
+
+    /// Percent-encoding is enabled, so escaped characters sort before
+    /// unescaped characters regardless of their values, such as `"a/"`
+    /// sorting before `"a."`.
+    PercentEncoding,
+
+    /// Strict ordering is disabled, so unit variants are written as their
+    /// names and sort alphabetically instead of in declaration order.
+    VariantNames,
+
+    /// Strict ordering is disabled, so strings containing the deliminator
+    /// are written without an error.
+    UncheckedStrings,
+}
+
+impl std::fmt::Display for OrderingIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            OrderingIssue::Deliminator => "deliminator is not a NUL byte",
+            OrderingIssue::PercentEncoding => "percent-encoding is enabled",
+            OrderingIssue::VariantNames => "unit variants are written as names",
+            OrderingIssue::UncheckedStrings => "strings are not checked for the deliminator",
+        })
+    }
+}
+
+/// Serializer and deserializer options.
+#[derive(Debug, Clone)]
+pub struct Config {
+    deliminator: Vec<u8>,
+    percent_encoding: bool,
+    strict_ordering: bool,
+    case_fold: bool,
+    recursion_limit: Option<usize>,
+    signed_encoding: SignedEncoding,
+    allow_missing_trailing: bool,
+    allow_trailing: bool,
+    overrides: Arc<EncodingOverrides>,
+    sort_spec: Arc<SortSpec>,
+    variant_aliases: Arc<VariantAliases>,
+    variant_codes: Arc<VariantCodes>,
+    redaction: Arc<Redaction>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            deliminator: b":".to_vec(),
+            percent_encoding: false,
+            strict_ordering: false,
+            case_fold: false,
+            recursion_limit: Some(crate::de::DEFAULT_RECURSION_LIMIT),
+            signed_encoding: SignedEncoding::Offset,
+            allow_missing_trailing: false,
+            allow_trailing: false,
+            overrides: Arc::new(EncodingOverrides::new()),
+            sort_spec: Arc::new(SortSpec::new()),
+            variant_aliases: Arc::new(VariantAliases::new()),
+            variant_codes: Arc::new(VariantCodes::new()),
+            redaction: Arc::new(Redaction::new()),
+        }
+    }
+}
+
+impl Config {
+    /// Construct a configuration with the default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a configuration whose keys sort in the same order as their
+    /// values.
+    ///
+    /// The deliminator is a NUL byte, which sorts before every other byte so
+    /// a string sorts before longer strings that start with it even when
+    /// followed by other components, and strict ordering is enabled. See
+    /// [`Self::ordering_issues`] for the conditions.
+    pub fn strict() -> Self {
+        Self::new()
+            .with_deliminator(&b"\0"[..])
+            .with_strict_ordering(true)
+    }
+
+    /// Construct a configuration that reads and writes keys in the format of
+    /// the given [`FORMAT_VERSION`](crate::FORMAT_VERSION).
+    ///
+    /// Returns an error if the version is unknown.
+    pub fn compat(version: u32) -> Result<Self, Error> {
+        match version {
+            1 => Ok(Self::default()),
+            _ => Err(Error::Other(format!(
+                "unsupported format version {}",
+                version
+            ))),
+        }
+    }
+
+    /// Returns the deliminator used to separate values.
+    pub fn deliminator(&self) -> &[u8] {
+        &self.deliminator
+    }
+
+    /// Sets the deliminator used to separate values.
+    ///
+    /// The deliminator can be any byte sequence, such as `":"` or `b"\0"`.
+    pub fn set_deliminator<D: Into<Vec<u8>>>(&mut self, deliminator: D) {
+        self.deliminator = deliminator.into();
+    }
+
+    /// Sets the deliminator used to separate values and returns the configuration.
+    pub fn with_deliminator<D: Into<Vec<u8>>>(mut self, deliminator: D) -> Self {
+        self.set_deliminator(deliminator);
+        self
+    }
+
+    /// Returns whether string components are percent-encoded.
+    pub fn percent_encoding(&self) -> bool {
+        self.percent_encoding
+    }
+
+    /// Sets whether string components are percent-encoded.
+    ///
+    /// Components are also percent-decoded on decode. Escaped strings do not
+    /// sort in the order of their values, so this is reported by
+    /// [`Self::ordering_issues`] and rejected with strict ordering. See
+    /// [`Serializer::set_percent_encoding`] and
+    /// [`Deserializer::set_percent_decoding`].
+    pub fn set_percent_encoding(&mut self, value: bool) {
+        self.percent_encoding = value;
+    }
+
+    /// Sets whether string components are percent-encoded and returns the configuration.
+    pub fn with_percent_encoding(mut self, value: bool) -> Self {
+        self.set_percent_encoding(value);
+        self
+    }
+
+    /// Returns whether strict ordering is enabled.
+    pub fn strict_ordering(&self) -> bool {
+        self.strict_ordering
+    }
+
+    /// Sets whether strict ordering is enabled.
+    ///
+    /// See [`Serializer::set_strict_ordering`].
+    pub fn set_strict_ordering(&mut self, value: bool) {
+        self.strict_ordering = value;
+    }
+
+    /// Sets whether strict ordering is enabled and returns the configuration.
+    pub fn with_strict_ordering(mut self, value: bool) -> Self {
+        self.set_strict_ordering(value);
+        self
+    }
+
+    /// Checks that the deliminator cannot occur within a component and that
+    /// the options do not conflict.
+    ///
+    /// Returns an error if the deliminator is empty, consists only of digits
+    /// of an encoding in use, occurs in `true` or `false`, or can be written
+    /// unescaped by percent-encoding, or if percent-encoding is enabled with
+    /// strict ordering.
+    ///
+    /// The same check runs when a key is encoded or when a key is decoded
+    /// with [`Self::from_slice`] or [`Self::from_reader`], which return
+    /// [`Error::Config`], so an invalid configuration is never used.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        validate_deliminator(
+            &self.deliminator,
+            self.percent_encoding,
+            self.overrides.encodings(),
+        )?;
+
+        if self.strict_ordering && self.percent_encoding {
+            return Err(ConfigError::UnorderedEscapes);
+        }
+
+        Ok(())
+    }
+
+    /// Checks the configuration with [`Self::validate`] and returns it.
+    ///
+    /// Example:
+    ///
+    /// ```rust
+    /// use strkey::{config::Config, error::ConfigError};
+    ///
+    /// assert!(Config::new().with_deliminator("/").validated().is_ok());
+    /// assert!(matches!(
+    ///     Config::new().with_deliminator("e").validated(),
+    ///     Err(ConfigError::EncodingCollision(_))
+    /// ));
+    /// ```
+    pub fn validated(self) -> Result<Self, ConfigError> {
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Returns the options that can cause keys to sort in a different order
+    /// than their values.
+    ///
+    /// Returns an empty list for [`Self::strict`]. Floating point numbers are
+    /// always ordered by IEEE 754 total order, where negative zero sorts
+    /// before zero and NaN sorts at the ends.
+    pub fn ordering_issues(&self) -> Vec<OrderingIssue> {
+        let mut issues = Vec::new();
+
+        if self.deliminator != b"\0" {
+            issues.push(OrderingIssue::Deliminator);
+        }
+
+        if self.percent_encoding {
+            issues.push(OrderingIssue::PercentEncoding);
+        }
+
+        if !self.strict_ordering {
+            issues.push(OrderingIssue::VariantNames);
+            issues.push(OrderingIssue::UncheckedStrings);
+        }
+
+        issues
+    }
+
+    /// Returns whether components are case-folded on decode.
+    pub fn case_fold(&self) -> bool {
+        self.case_fold
+    }
+
+    /// Sets whether components are case-folded on decode.
+    ///
+    /// See [`Deserializer::set_case_fold`]. The serializer is unaffected.
+    pub fn set_case_fold(&mut self, value: bool) {
+        self.case_fold = value;
+    }
+
+    /// Sets whether components are case-folded on decode and returns the configuration.
+    pub fn with_case_fold(mut self, value: bool) -> Self {
+        self.set_case_fold(value);
+        self
+    }
+
+    /// Returns the maximum nesting depth of types on decode.
+    pub fn recursion_limit(&self) -> Option<usize> {
+        self.recursion_limit
+    }
+
+    /// Sets the maximum nesting depth of types on decode.
+    ///
+    /// See [`Deserializer::set_recursion_limit`].
+    pub fn set_recursion_limit(&mut self, limit: Option<usize>) {
+        self.recursion_limit = limit;
+    }
+
+    /// Sets the maximum nesting depth of types on decode and returns the configuration.
+    pub fn with_recursion_limit(mut self, limit: Option<usize>) -> Self {
+        self.set_recursion_limit(limit);
+        self
+    }
+
+    /// Returns the encoding of signed integers.
+    pub fn signed_encoding(&self) -> SignedEncoding {
+        self.signed_encoding
+    }
+
+    /// Sets the encoding of signed integers.
+    ///
+    /// See [`Serializer::set_signed_encoding`].
+    pub fn set_signed_encoding(&mut self, value: SignedEncoding) {
+        self.signed_encoding = value;
+    }
+
+    /// Sets the encoding of signed integers and returns the configuration.
+    pub fn with_signed_encoding(mut self, value: SignedEncoding) -> Self {
+        self.set_signed_encoding(value);
+        self
+    }
+
+    /// Returns whether missing trailing components are allowed.
+    pub fn allow_missing_trailing(&self) -> bool {
+        self.allow_missing_trailing
+    }
+
+    /// Sets whether missing trailing components are allowed.
+    ///
+    /// See [`Deserializer::set_allow_missing_trailing`].
+    pub fn set_allow_missing_trailing(&mut self, value: bool) {
+        self.allow_missing_trailing = value;
+    }
+
+    /// Sets whether missing trailing components are allowed and returns the configuration.
+    pub fn with_allow_missing_trailing(mut self, value: bool) -> Self {
+        self.set_allow_missing_trailing(value);
+        self
+    }
+
+    /// Returns whether extra trailing components are allowed.
+    pub fn allow_trailing(&self) -> bool {
+        self.allow_trailing
+    }
+
+    /// Sets whether extra trailing components are allowed.
+    ///
+    /// See [`Deserializer::set_allow_trailing`].
+    pub fn set_allow_trailing(&mut self, value: bool) {
+        self.allow_trailing = value;
+    }
+
+    /// Sets whether extra trailing components are allowed and returns the configuration.
+    pub fn with_allow_trailing(mut self, value: bool) -> Self {
+        self.set_allow_trailing(value);
+        self
+    }
+
+    /// Returns the encoding overrides.
+    pub fn overrides(&self) -> &EncodingOverrides {
+        &self.overrides
+    }
+
+    /// Sets the encoding overrides.
+    pub fn set_overrides(&mut self, overrides: EncodingOverrides) {
+        self.overrides = Arc::new(overrides);
+    }
+
+    /// Sets the encoding overrides and returns the configuration.
+    pub fn with_overrides(mut self, overrides: EncodingOverrides) -> Self {
+        self.set_overrides(overrides);
+        self
+    }
+
+    /// Returns the sort specification.
+    pub fn sort_spec(&self) -> &SortSpec {
+        &self.sort_spec
+    }
+
+    /// Sets the sort specification.
+    pub fn set_sort_spec(&mut self, sort_spec: SortSpec) {
+        self.sort_spec = Arc::new(sort_spec);
+    }
+
+    /// Sets the sort specification and returns the configuration.
+    pub fn with_sort_spec(mut self, sort_spec: SortSpec) -> Self {
+        self.set_sort_spec(sort_spec);
+        self
+    }
+
+    /// Returns the enum variant aliases.
+    pub fn variant_aliases(&self) -> &VariantAliases {
+        &self.variant_aliases
+    }
+
+    /// Sets the enum variant aliases.
+    pub fn set_variant_aliases(&mut self, variant_aliases: VariantAliases) {
+        self.variant_aliases = Arc::new(variant_aliases);
+    }
+
+    /// Sets the enum variant aliases and returns the configuration.
+    pub fn with_variant_aliases(mut self, variant_aliases: VariantAliases) -> Self {
+        self.set_variant_aliases(variant_aliases);
+        self
+    }
+
+    /// Returns the enum variant codes.
+    pub fn variant_codes(&self) -> &VariantCodes {
+        &self.variant_codes
+    }
+
+    /// Sets the enum variant codes.
+    pub fn set_variant_codes(&mut self, variant_codes: VariantCodes) {
+        self.variant_codes = Arc::new(variant_codes);
+    }
+
+    /// Sets the enum variant codes and returns the configuration.
+    pub fn with_variant_codes(mut self, variant_codes: VariantCodes) -> Self {
+        self.set_variant_codes(variant_codes);
+        self
+    }
+
+    /// Returns the components replaced on encode.
+    pub fn redaction(&self) -> &Redaction {
+        &self.redaction
+    }
+
+    /// Sets the components replaced on encode.
+    ///
+    /// The redaction is applied by the serializer as each component is
+    /// written, so it applies to [`Self::serializer`] and every method that
+    /// uses it.
+    pub fn set_redaction(&mut self, redaction: Redaction) {
+        self.redaction = Arc::new(redaction);
+    }
+
+    /// Sets the components replaced on encode and returns the configuration.
+    pub fn with_redaction(mut self, redaction: Redaction) -> Self {
+        self.set_redaction(redaction);
+        self
+    }
+
+    /// Returns whether the redacted key is the unredacted key with the
+    /// redaction applied.
+    ///
+    /// The unredacted key is split on the deliminator as described in
+    /// [`Redaction::apply`].
+    pub fn redacted_matches(&self, redacted: &[u8], unredacted: &[u8]) -> bool {
+        match self.redaction.apply(unredacted, &self.deliminator) {
+            Ok(key) => key == redacted,
+            Err(_) => false,
+        }
+    }
+
+    /// Returns a serializer using this configuration.
+    pub fn serializer<W: Write>(&self, writer: W) -> Serializer<W> {
+        let mut serializer = Serializer::new(writer)
+            .with_deliminator(self.deliminator.as_slice())
+            .with_percent_encoding(self.percent_encoding)
+            .with_strict_ordering(self.strict_ordering)
+            .with_signed_encoding(self.signed_encoding);
+
+        if !self.overrides.is_empty() {
+            serializer.set_overrides(self.overrides.clone());
+        }
+
+        if !self.sort_spec.is_empty() {
+            serializer.set_sort_spec(self.sort_spec.clone());
+        }
+
+        if !self.variant_codes.is_empty() {
+            serializer.set_variant_codes(self.variant_codes.clone());
+        }
+
+        if !self.redaction.is_empty() {
+            serializer.set_redaction(self.redaction.clone());
+        }
+
+        serializer
+    }
+
+    /// Returns a deserializer for the given slice using this configuration.
+    pub fn deserializer<'a>(&'a self, input: &'a [u8]) -> Deserializer<'a, SliceReader<'a>> {
+        self.configure_deserializer(Deserializer::from_slice(input))
+    }
+
+    /// Returns a deserializer for the given reader using this configuration.
+    pub fn reader_deserializer<R: Read>(&self, input: R) -> Deserializer<'_, IoReader<'_, R>> {
+        self.configure_deserializer(Deserializer::from_reader(input))
+    }
+
+    fn configure_deserializer<'a, R>(
+        &'a self,
+        deserializer: Deserializer<'a, R>,
+    ) -> Deserializer<'a, R>
+    where
+        R: crate::de::ComponentRead<'a>,
+    {
+        let mut deserializer = deserializer
+            .with_deliminator(&self.deliminator)
+            .with_strict_ordering(self.strict_ordering)
+            .with_case_fold(self.case_fold)
+            .with_percent_decoding(self.percent_encoding)
+            .with_recursion_limit(self.recursion_limit)
+            .with_signed_encoding(self.signed_encoding)
+            .with_allow_missing_trailing(self.allow_missing_trailing)
+            .with_allow_trailing(self.allow_trailing);
+
+        if !self.overrides.is_empty() {
+            deserializer.set_overrides(self.overrides.clone());
+        }
+
+        if !self.sort_spec.is_empty() {
+            deserializer.set_sort_spec(self.sort_spec.clone());
+        }
+
+        if !self.variant_aliases.is_empty() {
+            deserializer.set_variant_aliases(self.variant_aliases.clone());
+        }
+
+        if !self.variant_codes.is_empty() {
+            deserializer.set_variant_codes(self.variant_codes.clone());
+        }
+
+        deserializer
+    }
+
+    /// Serializes the given value to a vector.
+    pub fn to_vec<T>(&self, value: &T) -> Result<Vec<u8>, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let mut buffer = Vec::new();
+        value.serialize(&mut self.serializer(&mut buffer))?;
+
+        Ok(buffer)
+    }
+
+    /// Serializes the given value to the given writer.
+    ///
+    /// Like [`crate::to_writer`], the key is written with a single call to
+    /// [`Write::write_all`].
+    pub fn to_writer<W, T>(&self, mut writer: W, value: &T) -> Result<(), Error>
+    where
+        W: Write,
+        T: ?Sized + Serialize,
+    {
+        let mut buffer = Vec::new();
+        value.serialize(&mut self.serializer(&mut buffer))?;
+        writer.write_all(&buffer)?;
+
+        Ok(())
+    }
+
+    /// Replaces the component at the given index of a key laid out as `T`.
+    ///
+    /// Only the new value is encoded, with the options and encoding
+    /// override that apply at the index. The components before the index
+    /// are decoded to find the component, the new component is decoded to
+    /// check it against `T`, and the components after it are copied as is
+    /// without being decoded.
+    /// See [`crate::value::replace_component`].
+    pub fn replace_component<T>(
+        &self,
+        key: &[u8],
+        index: usize,
+        value: &(impl Serialize + ?Sized),
+    ) -> Result<Vec<u8>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let mut start = 0;
+        let mut components = split_components(key, &self.deliminator);
+
+        for component in components.by_ref().take(index) {
+            start += component.len() + self.deliminator.len();
+        }
+
+        let current = components
+            .next()
+            .ok_or_else(|| Error::Other(format!("component {} not found", index)))?;
+        let end = start + current.len();
+
+        let overrides = Some(&*self.overrides).filter(|overrides| !overrides.is_empty());
+        let encoding =
+            crate::value::locate_component::<T>(&mut self.deserializer(key), index, overrides)?;
+
+        let mut component = Vec::new();
+        let mut serializer = self.serializer(&mut component);
+        serializer.begin_component(index, encoding);
+        value.serialize(&mut serializer)?;
+
+        if serializer.components_written() != index + 1
+            || find_subslice(&component, &self.deliminator).is_some()
+        {
+            return Err(Error::Data(
+                String::from_utf8_lossy(&component).into_owned(),
+            ));
+        }
+
+        // The deliminator is appended so that an empty component is read
+        // as a component rather than as the end of the input.
+        let mut input = component.clone();
+        input.extend_from_slice(&self.deliminator);
+        crate::value::check_component::<T>(
+            &mut self.deserializer(key),
+            &mut self.deserializer(&input),
+            index,
+            overrides,
+        )?;
+
+        let mut output = Vec::with_capacity(key.len() - current.len() + component.len());
+        output.extend_from_slice(&key[..start]);
+        output.extend_from_slice(&component);
+        output.extend_from_slice(&key[end..]);
+
+        Ok(output)
+    }
+
+    /// Returns the first component of a key without decoding the rest.
+    ///
+    /// Unlike [`crate::de::raw_kind_of`], the key is split on the configured
+    /// deliminator, and escapes are decoded when percent encoding is
+    /// enabled, so a kind written as `a b` is returned as `a b` rather than
+    /// `a%20b`. The component is borrowed when there is nothing to decode.
+    pub fn kind_of<'a>(&self, key: &'a [u8]) -> Result<Cow<'a, str>, Error> {
+        let component = crate::de::first_component(key, &self.deliminator)?;
+
+        if !self.percent_encoding {
+            return Ok(Cow::Borrowed(component));
+        }
+
+        Ok(match crate::de::percent_decode(component)? {
+            Some(decoded) => Cow::Owned(decoded),
+            None => Cow::Borrowed(component),
+        })
+    }
+
+    /// Returns the index of the first component of a key equal to the given
+    /// encoded component.
+    ///
+    /// This is [`crate::de::find_component`] using the configured
+    /// deliminator.
+    pub fn find_component(&self, key: &[u8], component: &[u8]) -> Option<usize> {
+        crate::de::find_component_with(key, component, &self.deliminator)
+    }
+
+    /// Deserializes the value from a byte array slice.
+    pub fn from_slice<'a, T>(&'a self, value: &'a [u8]) -> Result<T, Error>
+    where
+        T: Deserialize<'a>,
+    {
+        self.validate()?;
+
+        let mut deserializer = self.deserializer(value);
+        let output = T::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+
+        Ok(output)
+    }
+
+    /// Deserializes the value from the given reader.
+    pub fn from_reader<R, T>(&self, reader: R) -> Result<T, Error>
+    where
+        R: Read,
+        T: DeserializeOwned,
+    {
+        self.validate()?;
+
+        let mut deserializer = self.reader_deserializer(reader);
+        let output = T::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+
+        Ok(output)
+    }
+}
+
+const BASE32_ALPHABET: &[u8; 32] = b"0123456789abcdefghijklmnopqrstuv";
+
+fn decimal_width(size: usize) -> usize {
+    let max = u128::MAX >> (128 - size * 8);
+    max.to_string().len()
+}
+
+/// Appends the encoded data to the output.
+///
+/// `fixed` indicates the data is a fixed-width number rather than a byte array.
+pub(crate) fn encode(
+    encoding: Encoding,
+    data: &[u8],
+    fixed: bool,
+    output: &mut Vec<u8>,
+) -> Result<(), Error> {
+    match encoding {
+        Encoding::Hex => crate::hex_codec::encode_extend(data, output),
+        Encoding::Decimal => {
+            if !fixed || data.is_empty() || data.len() > 16 {
+                return Err(Error::UnsupportedType);
+            }
+
+            let mut buffer = [0u8; 16];
+            buffer[16 - data.len()..].copy_from_slice(data);
+            let text = format!(
+                "{:0width$}",
+                u128::from_be_bytes(buffer),
+                width = decimal_width(data.len())
+            );
+            output.extend_from_slice(text.as_bytes());
+        }
+        Encoding::Base32 => {
+            let mut buffer = 0u32;
+            let mut bits = 0;
+
+            for &byte in data {
+                buffer = (buffer << 8) | byte as u32;
+                bits += 8;
+
+                while bits >= 5 {
+                    bits -= 5;
+                    output.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize]);
+                }
+            }
+
+            if bits > 0 {
+                output.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize]);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Appends the decoded text to the output.
+///
+/// `size` is the number of bytes of a fixed-width number or `None` for a
+/// byte array.
+pub(crate) fn decode(
+    encoding: Encoding,
+    text: &str,
+    size: Option<usize>,
+    output: &mut Vec<u8>,
+) -> Result<(), Error> {
+    let error = || Error::Data(text.to_string());
+
+    match encoding {
+        Encoding::Hex => {
+            crate::hex_codec::decode_extend(text.as_bytes(), output).map_err(|_| error())?;
+        }
+        Encoding::Decimal => {
+            let size = size.ok_or(Error::UnsupportedType)?;
+
+            if text.len() != decimal_width(size) || !text.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(error());
+            }
+
+            let value = text.parse::<u128>().map_err(|_| error())?;
+
+            if value > u128::MAX >> (128 - size * 8) {
+                return Err(error());
+            }
+
+            output.extend_from_slice(&value.to_be_bytes()[16 - size..]);
+        }
+        Encoding::Base32 => {
+            let mut buffer = 0u32;
+            let mut bits = 0;
+
+            for &char in text.as_bytes() {
+                let value = BASE32_ALPHABET
+                    .iter()
+                    .position(|&item| item == char)
+                    .ok_or_else(error)?;
+                buffer = (buffer << 5) | value as u32;
+                bits += 5;
+
+                if bits >= 8 {
+                    bits -= 8;
+                    output.push((buffer >> bits) as u8);
+                }
+            }
+
+            if bits >= 5 || buffer & ((1 << bits) - 1) != 0 {
+                return Err(error());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Id(u32);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct MyKey {
+        id: Id,
+        start_ts: i8,
+        data: serde_bytes::ByteBuf,
+        other: u8,
+    }
+
+    fn my_key() -> MyKey {
+        MyKey {
+            id: Id(255),
+            start_ts: -1,
+            data: serde_bytes::ByteBuf::from(b"hi".to_vec()),
+            other: 1,
+        }
+    }
+
+    #[test]
+    fn test_overrides() {
+        let config = Config::new().with_overrides(
+            EncodingOverrides::new()
+                .with_newtype("Id", Encoding::Decimal)
+                .with_field("*_ts", Encoding::Decimal)
+                .with_field("data", Encoding::Base32),
+        );
+        let encoded = config.to_vec(&my_key()).unwrap();
+
+        assert_eq!(&encoded, b"0000000255:127:d1kg:01");
+        assert_eq!(config.from_slice::<MyKey>(&encoded).unwrap(), my_key());
+        assert_eq!(
+            config.from_reader::<_, MyKey>(encoded.as_slice()).unwrap(),
+            my_key()
+        );
+        assert_eq!(
+            crate::to_vec(&my_key()).unwrap(),
+            b"000000ff:7f:6869:01".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_overrides_nested() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Inner {
+            count: u32,
+            data_ts: u8,
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Wrapper(Inner);
+
+        let config = Config::new().with_overrides(
+            EncodingOverrides::new()
+                .with_newtype("Wrapper", Encoding::Decimal)
+                .with_field("*_ts", Encoding::Hex),
+        );
+        let value = Wrapper(Inner {
+            count: 255,
+            data_ts: 10,
+        });
+        let encoded = config.to_vec(&value).unwrap();
+
+        assert_eq!(&encoded, b"0000000255:0a");
+        assert_eq!(config.from_slice::<Wrapper>(&encoded).unwrap(), value);
+    }
+
+    #[test]
+    fn test_replace_component() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct AccountKey {
+            tenant: String,
+            id: u32,
+            name: String,
+        }
+
+        let config = Config::new()
+            .with_overrides(EncodingOverrides::new().with_field("id", Encoding::Decimal))
+            .with_allow_trailing(true);
+        let key = config
+            .to_vec(&AccountKey {
+                tenant: "a".to_string(),
+                id: 1,
+                name: "b".to_string(),
+            })
+            .unwrap();
+
+        assert_eq!(&key, b"a:0000000001:b");
+        assert_eq!(
+            config
+                .replace_component::<AccountKey>(&key, 1, &9u32)
+                .unwrap(),
+            b"a:0000000009:b"
+        );
+        assert!(matches!(
+            config.replace_component::<AccountKey>(&key, 0, "x:y"),
+            Err(Error::Data(_))
+        ));
+        assert!(config
+            .replace_component::<AccountKey>(&key, 1, &9u8)
+            .is_err());
+    }
+
+    #[test]
+    fn test_overrides_errors() {
+        let config = Config::new()
+            .with_overrides(EncodingOverrides::new().with_field("data", Encoding::Decimal));
+
+        assert!(config.to_vec(&my_key()).is_err());
+
+        let config = Config::new()
+            .with_overrides(EncodingOverrides::new().with_newtype("Id", Encoding::Decimal));
+
+        assert!(config.from_slice::<Id>(b"255").is_err());
+        assert!(config.from_slice::<Id>(b"4294967296").is_err());
+        assert_eq!(
+            config.from_slice::<Id>(b"4294967295").unwrap(),
+            Id(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn test_base32() {
+        for data in [&b""[..], b"a", b"ab", b"abcde", b"\xff\x00\x10"].iter() {
+            let mut encoded = Vec::new();
+            encode(Encoding::Base32, data, false, &mut encoded).unwrap();
+
+            let mut decoded = Vec::new();
+            decode(
+                Encoding::Base32,
+                std::str::from_utf8(&encoded).unwrap(),
+                None,
+                &mut decoded,
+            )
+            .unwrap();
+
+            assert_eq!(&decoded, data);
+        }
+
+        assert!(decode(Encoding::Base32, "w", None, &mut Vec::new()).is_err());
+        assert!(decode(Encoding::Base32, "1", None, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_sort_spec() {
+        let config = Config::new().with_sort_spec(SortSpec::new().with_descending(1));
+        let mut keys = [(1u8, 1i16, "a"), (1, -1, "b"), (0, 5, "c"), (1, 3, "d")]
+            .iter()
+            .map(|key| config.to_vec(key).unwrap())
+            .collect::<Vec<_>>();
+        keys.sort();
+
+        let values = keys
+            .iter()
+            .map(|key| config.from_slice::<(u8, i16, &str)>(key).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            values,
+            vec![(0, 5, "c"), (1, 3, "d"), (1, 1, "a"), (1, -1, "b")]
+        );
+        assert_eq!(config.to_vec(&((), 1u8, 1i16)).unwrap(), b"01:7ffe");
+        assert!(config.to_vec(&(1u8, "a")).is_err());
+        assert!(config.from_slice::<(u8, &str)>(b"01:a").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Config::new().validate().is_ok());
+        assert!(Config::strict().validate().is_ok());
+        assert!(Config::new().with_deliminator("/").validate().is_ok());
+        assert!(Config::new().with_deliminator("0x").validate().is_ok());
+
+        assert_eq!(
+            Config::new().with_deliminator("").validate(),
+            Err(ConfigError::EmptyDeliminator)
+        );
+        assert_eq!(
+            Config::new().with_deliminator("ab").validate(),
+            Err(ConfigError::EncodingCollision(Encoding::Hex))
+        );
+        assert_eq!(
+            Config::new()
+                .with_deliminator("g")
+                .with_overrides(EncodingOverrides::new().with_field("id", Encoding::Base32))
+                .validate(),
+            Err(ConfigError::EncodingCollision(Encoding::Base32))
+        );
+        assert_eq!(
+            Config::new().with_deliminator("ru").validate(),
+            Err(ConfigError::BoolCollision)
+        );
+        assert_eq!(
+            Config::new()
+                .with_deliminator("-")
+                .with_percent_encoding(true)
+                .validate(),
+            Err(ConfigError::EscapeCollision)
+        );
+        assert!(Config::new()
+            .with_deliminator("/")
+            .with_percent_encoding(true)
+            .validated()
+            .is_ok());
+        assert_eq!(
+            Config::strict().with_percent_encoding(true).validate(),
+            Err(ConfigError::UnorderedEscapes)
+        );
+    }
+
+    #[test]
+    fn test_validate_enforced() {
+        let config = Config::new().with_deliminator("e");
+
+        assert!(matches!(
+            config.to_vec(&(0xeeu8, 1u8)),
+            Err(Error::Config(ConfigError::EncodingCollision(Encoding::Hex)))
+        ));
+        assert!(matches!(
+            config.from_slice::<(u8, u8)>(b"eee01"),
+            Err(Error::Config(ConfigError::EncodingCollision(Encoding::Hex)))
+        ));
+
+        let mut buffer = Vec::new();
+        let mut serializer = Serializer::new(&mut buffer).with_deliminator("e");
+
+        assert!(matches!(
+            (0xeeu8, 1u8).serialize(&mut serializer),
+            Err(Error::Config(ConfigError::EncodingCollision(Encoding::Hex)))
+        ));
+
+        serializer.set_deliminator("/");
+        (0xeeu8, 1u8).serialize(&mut serializer).unwrap();
+        assert_eq!(&buffer, b"ee/01");
+    }
+
+    #[test]
+    fn test_kind_of() {
+        let config = Config::new()
+            .with_deliminator("/")
+            .with_percent_encoding(true);
+        let key = config.to_vec(&("order line", 1u8)).unwrap();
+
+        assert_eq!(&key, b"order%20line/01");
+        assert_eq!(config.kind_of(&key).unwrap(), "order line");
+        assert!(matches!(
+            config.kind_of(b"item/01").unwrap(),
+            Cow::Borrowed("item")
+        ));
+        assert!(config.kind_of(b"bad%2/01").is_err());
+        assert_eq!(
+            Config::new()
+                .with_deliminator("/")
+                .kind_of(b"a:b/c")
+                .unwrap(),
+            "a:b"
+        );
+    }
+
+    #[test]
+    fn test_signed_encoding() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Amount(i32);
+
+        let config = Config::new()
+            .with_overrides(EncodingOverrides::new().with_newtype("Amount", Encoding::Decimal))
+            .with_signed_encoding(SignedEncoding::Biased(1_000_000_000));
+
+        assert_eq!(&config.to_vec(&Amount(-5)).unwrap(), b"0999999995");
+        assert_eq!(&config.to_vec(&Amount(0)).unwrap(), b"1000000000");
+        assert_eq!(
+            config.from_slice::<Amount>(b"1000000042").unwrap(),
+            Amount(42)
+        );
+        assert!(config.to_vec(&Amount(-1_000_000_001)).is_err());
+        assert!(config.from_slice::<Amount>(b"4294967295").is_err());
+
+        let samples = [i32::MIN, -1_000_000_000, -1, 0, 1, 12345, i32::MAX];
+
+        for signed_encoding in [SignedEncoding::Offset, SignedEncoding::Biased(1 << 31)] {
+            let config = Config::new().with_signed_encoding(signed_encoding);
+            let keys = samples
+                .iter()
+                .map(|value| config.to_vec(value).unwrap())
+                .collect::<Vec<_>>();
+
+            assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+
+            for (key, value) in keys.iter().zip(samples.iter()) {
+                assert_eq!(config.from_slice::<i32>(key).unwrap(), *value);
+            }
+
+            assert_eq!(&keys[3], b"80000000");
+        }
+
+        let config = Config::new().with_signed_encoding(SignedEncoding::Biased(100));
+        let keys = (-100i8..=i8::MAX)
+            .map(|value| config.to_vec(&value).unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(config.from_slice::<i8>(b"00").unwrap(), -100);
+        assert!(config.from_slice::<i8>(b"ff").is_err());
+
+        for value in [i64::MIN, -1, 0, i64::MAX] {
+            let key = crate::to_vec(&value).unwrap();
+            let config = Config::new().with_signed_encoding(SignedEncoding::Offset);
+            assert_eq!(config.to_vec(&value).unwrap(), key);
+            assert_eq!(config.from_slice::<i64>(&key).unwrap(), value);
+        }
+
+        let value = i128::MIN + 7;
+        let config = Config::new().with_signed_encoding(SignedEncoding::Offset);
+        assert_eq!(
+            config
+                .from_slice::<i128>(&config.to_vec(&value).unwrap())
+                .unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_config_options() {
+        let config = Config::new()
+            .with_deliminator("/")
+            .with_percent_encoding(true)
+            .with_allow_trailing(true);
+
+        assert_eq!(config.to_vec(&("a b", 1u8)).unwrap(), b"a%20b/01");
+        assert_eq!(
+            config.from_slice::<(String, u8)>(b"a%20b/01").unwrap(),
+            ("a b".to_string(), 1)
+        );
+        assert_eq!(
+            config.from_slice::<(&str, u8)>(b"a/01/02").unwrap(),
+            ("a", 1)
+        );
+    }
+
+    #[test]
+    fn test_compat() {
+        assert!(Config::compat(crate::FORMAT_VERSION).is_ok());
+        assert!(Config::compat(0).is_err());
+        assert!(Config::compat(crate::FORMAT_VERSION + 1).is_err());
+    }
+
+    /// Pins the byte-exact encoding of version 1. These keys must continue to
+    /// decode with `Config::compat(1)` in later versions.
+    #[test]
+    fn test_compat_golden_v1() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum Kind {
+            Account,
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Key {
+            kind: Kind,
+            name: String,
+            id: u32,
+            score: f64,
+        }
+
+        type Golden = (
+            (u8, u16, u32, u64, u128),
+            (i8, i16, i32, i64, i128),
+            (f32, f64, bool, char, ()),
+        );
+
+        let config = Config::compat(1).unwrap();
+        let value: Golden = (
+            (1, 2, 3, 4, 5),
+            (-1, -2, 3, -4, 5),
+            (1.5, -0.25, true, 'é', ()),
+        );
+        let expected: &[u8] =
+            b"01:0002:00000003:0000000000000004:00000000000000000000000000000005:\
+            7f:7ffe:80000003:7ffffffffffffffc:80000000000000000000000000000005:\
+            bfc00000:402fffffffffffff:true:\xc3\xa9";
+
+        assert_eq!(config.to_vec(&value).unwrap(), expected);
+        assert_eq!(config.from_slice::<Golden>(expected).unwrap(), value);
+
+        let key = Key {
+            kind: Kind::Account,
+            name: "a b".to_string(),
+            id: 1234,
+            score: 0.0,
+        };
+        let expected: &[u8] = b"Account:a b:000004d2:8000000000000000";
+
+        assert_eq!(config.to_vec(&key).unwrap(), expected);
+        assert_eq!(config.from_slice::<Key>(expected).unwrap(), key);
+
+        let bytes = serde_bytes::Bytes::new(b"\x00\xff");
+        assert_eq!(config.to_vec(&bytes).unwrap(), b"00ff");
+        assert_eq!(
+            config
+                .clone()
+                .with_percent_encoding(true)
+                .to_vec(&"a:b/c")
+                .unwrap(),
+            b"a%3Ab%2Fc"
+        );
+    }
+
+    #[test]
+    fn test_strict() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum Level {
+            Low,
+            High,
+        }
+
+        let config = Config::strict();
+        assert!(config.ordering_issues().is_empty());
+
+        let key1 = config.to_vec(&("a", Level::High)).unwrap();
+        let key2 = config.to_vec(&("a!", Level::Low)).unwrap();
+
+        assert_eq!(&key1, b"a\x0000000001");
+        assert!(key1 < key2);
+        assert_eq!(
+            config.from_slice::<(String, Level)>(&key1).unwrap(),
+            ("a".to_string(), Level::High)
+        );
+        assert!(config.to_vec(&"a\0b").is_err());
+
+        assert_eq!(
+            Config::new().with_percent_encoding(true).ordering_issues(),
+            vec![
+                OrderingIssue::Deliminator,
+                OrderingIssue::PercentEncoding,
+                OrderingIssue::VariantNames,
+                OrderingIssue::UncheckedStrings
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redaction() {
+        let hash = |component: &[u8]| vec![component.len() as u8];
+        let config = Config::new().with_redaction(
+            Redaction::new()
+                .with(0, Mask::Fixed("*".to_string()))
+                .with(2, Mask::Hash(Arc::new(hash))),
+        );
+
+        let unredacted = Config::new().to_vec(&("alice", 1u8, "bob")).unwrap();
+        let redacted = config.to_vec(&("alice", 1u8, "bob")).unwrap();
+
+        assert_eq!(&redacted, b"*:01:03");
+        assert!(config.redacted_matches(&redacted, &unredacted));
+        assert!(!config.redacted_matches(&redacted, b"alice:02:bob"));
+
+        let mut output = Vec::new();
+        config.to_writer(&mut output, &("a", 2u8)).unwrap();
+        assert_eq!(&output, b"*:02");
+
+        let config =
+            Config::new().with_redaction(Redaction::new().with(0, Mask::Fixed(":".to_string())));
+        assert!(config.to_vec(&"a").is_err());
+
+        let config =
+            Config::new().with_redaction(Redaction::new().with(0, Mask::Fixed("*".to_string())));
+        let key = ("alice:secret-user-id", 1u8);
+
+        assert_eq!(&config.to_vec(&key).unwrap(), b"*:01");
+
+        let mut output = Vec::new();
+        key.serialize(&mut config.serializer(&mut output)).unwrap();
+        assert_eq!(&output, b"*:01");
+    }
+
+    #[test]
+    fn test_variant_aliases() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Kind {
+            User,
+            #[serde(alias = "grp")]
+            Group,
+        }
+
+        let config =
+            Config::new().with_variant_aliases(VariantAliases::new().with("Kind", "usr", "User"));
+
+        assert_eq!(
+            config.from_slice::<(Kind, u8)>(b"usr:01").unwrap(),
+            (Kind::User, 1)
+        );
+        assert_eq!(config.from_slice::<Kind>(b"User").unwrap(), Kind::User);
+        assert_eq!(config.from_slice::<Kind>(b"grp").unwrap(), Kind::Group);
+        assert!(config.from_slice::<Kind>(b"other").is_err());
+        assert!(Config::new().from_slice::<Kind>(b"usr").is_err());
+    }
+
+    #[test]
+    fn test_variant_codes() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(rename_all = "snake_case")]
+        enum Kind {
+            PowerUser,
+            Guest,
+            Member,
+        }
+
+        let codes = VariantCodes::new()
+            .with("Kind", "power_user", "p")
+            .unwrap()
+            .with("Kind", "guest", "member")
+            .unwrap();
+        let config = Config::new().with_variant_codes(codes.clone());
+
+        assert_eq!(codes.code("Kind", "power_user"), Some("p"));
+        assert_eq!(codes.variant("Kind", "p"), Some("power_user"));
+        assert_eq!(config.to_vec(&(Kind::PowerUser, 1u8)).unwrap(), b"p:01");
+        assert_eq!(
+            config.from_slice::<(Kind, u8)>(b"p:01").unwrap(),
+            (Kind::PowerUser, 1)
+        );
+        assert_eq!(config.to_vec(&Kind::Member).unwrap(), b"member");
+        assert!(config.from_slice::<Kind>(b"member").is_err());
+        assert!(config.from_slice::<Kind>(b"power_user").is_err());
+        assert_eq!(
+            Config::new().from_slice::<Kind>(b"power_user").unwrap(),
+            Kind::PowerUser
+        );
+
+        assert!(VariantCodes::new()
+            .with("Kind", "guest", "g")
+            .unwrap()
+            .with("Kind", "member", "g")
+            .is_err());
+        assert!(VariantCodes::new().with("Kind", "guest", "").is_err());
+
+        let codes = VariantCodes::new().with("Kind", "guest", "g:").unwrap();
+        assert!(codes.validate(b":").is_err());
+        assert!(codes.validate(b"/").is_ok());
+        assert!(Config::new()
+            .with_variant_codes(codes)
+            .to_vec(&Kind::Guest)
+            .is_err());
+    }
+}
@@ -0,0 +1,103 @@
+//! Compile-time encoding
+//!
+//! The [`strkey_prefix!`](crate::strkey_prefix) macro and the `const`
+//! functions in this module produce encoded components at compile time so
+//! static prefixes do not need to be serialized on each use.
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::const_encode;
+//!
+//! const ACCOUNT_PREFIX: &[u8] = strkey::strkey_prefix!("account", "by_id");
+//! const ID: [u8; 8] = const_encode::encode_u32(1234);
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! assert_eq!(ACCOUNT_PREFIX, strkey::to_prefix(&("account", "by_id"))?);
+//! assert_eq!(&ID, b"000004d2");
+//! # Ok(())
+//! # }
+//! ```
+
+/// Returns the encoded prefix of the given string literals as a `&'static [u8]`.
+///
+/// Each string is followed by the default deliminator. Strings are not
+/// checked for deliminators.
+#[macro_export]
+macro_rules! strkey_prefix {
+    ($($component:literal),+ $(,)?) => {{
+        $(const _: &str = $component;)+
+        concat!($($component, ":"),+).as_bytes()
+    }};
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+macro_rules! const_encode_fn {
+    ($($name:ident($type:ty, $unsigned:ty, $len:literal, $flip:expr);)*) => {
+        $(
+            #[doc = concat!("Encodes a `", stringify!($type), "` component.")]
+            pub const fn $name(value: $type) -> [u8; $len] {
+                let value = (value as $unsigned) ^ $flip;
+                let mut output = [0u8; $len];
+                let mut index = 0;
+
+                while index < $len {
+                    let shift = ($len - 1 - index) * 4;
+                    output[index] = HEX_DIGITS[((value >> shift) & 0xf) as usize];
+                    index += 1;
+                }
+
+                output
+            }
+        )*
+    };
+}
+
+const_encode_fn! {
+    encode_u8(u8, u8, 2, 0);
+    encode_u16(u16, u16, 4, 0);
+    encode_u32(u32, u32, 8, 0);
+    encode_u64(u64, u64, 16, 0);
+    encode_i8(i8, u8, 2, 1 << 7);
+    encode_i16(i16, u16, 4, 1 << 15);
+    encode_i32(i32, u32, 8, 1 << 31);
+    encode_i64(i64, u64, 16, 1 << 63);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix() {
+        const PREFIX: &[u8] = strkey_prefix!("a", "b c");
+
+        assert_eq!(PREFIX, b"a:b c:");
+        assert_eq!(strkey_prefix!("x"), b"x:");
+    }
+
+    #[test]
+    fn test_integers() {
+        for value in [0u64, 1, 0xabcdef, u64::MAX].iter() {
+            assert_eq!(&encode_u64(*value)[..], crate::to_vec(value).unwrap());
+            assert_eq!(
+                &encode_u8(*value as u8)[..],
+                crate::to_vec(&(*value as u8)).unwrap()
+            );
+        }
+
+        for value in [0i64, -1, i64::MIN, i64::MAX, 1234].iter() {
+            assert_eq!(&encode_i64(*value)[..], crate::to_vec(value).unwrap());
+            assert_eq!(
+                &encode_i16(*value as i16)[..],
+                crate::to_vec(&(*value as i16)).unwrap()
+            );
+        }
+
+        assert_eq!(&encode_u16(0xab)[..], b"00ab");
+        assert_eq!(&encode_u32(0xab)[..], b"000000ab");
+        assert_eq!(&encode_i8(-1)[..], b"7f");
+        assert_eq!(&encode_i32(0)[..], b"80000000");
+    }
+}
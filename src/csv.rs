@@ -0,0 +1,147 @@
+//! CSV conversion
+//!
+//! Converts between CSV records and keys using a [`ColumnMapping`] that
+//! assigns CSV columns to key components. Column values use the text form of
+//! [`Value`].
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::{csv::ColumnMapping, FieldType};
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let mapping = ColumnMapping::new()
+//!     .with_column("country", FieldType::Str)
+//!     .with_column("id", FieldType::U16);
+//!
+//! let input = "id,name,country\n1,Alice,ca\n2,Bob,us\n";
+//! let keys = strkey::csv::read_keys(input.as_bytes(), &mapping)?;
+//! assert_eq!(keys, vec![b"ca:0001".to_vec(), b"us:0002".to_vec()]);
+//!
+//! let mut output = Vec::new();
+//! strkey::csv::write_keys(&mut output, &keys, &mapping)?;
+//! assert_eq!(output, b"country,id\nca,1\nus,2\n");
+//! # Ok(())
+//! # }
+//! ```
+use std::io::{Read, Write};
+
+use crate::{
+    error::Error,
+    value::{FieldType, Schema, Value},
+};
+
+/// Assignment of CSV columns, by header name, to key components.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ColumnMapping {
+    columns: Vec<String>,
+    schema: Schema,
+}
+
+impl ColumnMapping {
+    /// Construct an empty mapping.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a component read from the given column and returns the mapping.
+    pub fn with_column<S: Into<String>>(mut self, column: S, field: FieldType) -> Self {
+        self.columns.push(column.into());
+        self.schema.push(field);
+        self
+    }
+
+    /// Returns the column names in component order.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Returns the schema of the components.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+}
+
+/// Reads CSV records with a header row and encodes each record into a key.
+///
+/// Columns not in the mapping are ignored. Returns an error if a mapped column
+/// is missing from the header.
+pub fn read_keys<R: Read>(reader: R, mapping: &ColumnMapping) -> Result<Vec<Vec<u8>>, Error> {
+    let mut reader = csv::Reader::from_reader(reader);
+    let headers = reader.headers()?.clone();
+    let indexes = mapping
+        .columns
+        .iter()
+        .map(|column| {
+            headers
+                .iter()
+                .position(|header| header == column)
+                .ok_or_else(|| Error::Other(format!("column {} not found", column)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut keys = Vec::new();
+
+    for record in reader.records() {
+        let record = record?;
+        let values = indexes
+            .iter()
+            .zip(mapping.schema.fields())
+            .map(|(&index, &field)| Value::parse(record.get(index).unwrap_or_default(), field))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        keys.push(crate::encode_with_schema(&values, &mapping.schema)?);
+    }
+
+    Ok(keys)
+}
+
+/// Decodes keys and writes them as CSV records with a header row.
+pub fn write_keys<W, I>(writer: W, keys: I, mapping: &ColumnMapping) -> Result<(), Error>
+where
+    W: Write,
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(&mapping.columns)?;
+
+    for key in keys {
+        let values = crate::decode_with_schema(key.as_ref(), &mapping.schema)?;
+        writer.write_record(values.iter().map(|value| value.to_string()))?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> ColumnMapping {
+        ColumnMapping::new()
+            .with_column("name", FieldType::Str)
+            .with_column("score", FieldType::I16)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let keys = read_keys("score,name\n-1,\"a,b\"\n2,c\n".as_bytes(), &mapping()).unwrap();
+
+        assert_eq!(keys, vec![b"a,b:7fff".to_vec(), b"c:8002".to_vec()]);
+
+        let mut output = Vec::new();
+        write_keys(&mut output, &keys, &mapping()).unwrap();
+
+        assert_eq!(output, b"name,score\n\"a,b\",-1\nc,2\n");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(read_keys("name\na\n".as_bytes(), &mapping()).is_err());
+        assert!(read_keys("name,score\na,x\n".as_bytes(), &mapping()).is_err());
+        assert!(write_keys(Vec::new(), &[b"a"], &mapping()).is_err());
+    }
+}
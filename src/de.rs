@@ -1,12 +1,34 @@
 //! Deserialization
-use std::{collections::VecDeque, convert::TryInto, io::Read, marker::PhantomData};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, VecDeque},
+    convert::{TryFrom, TryInto},
+    io::{BufRead, Read},
+    marker::PhantomData,
+    ops::Range,
+    sync::Arc,
+};
 
 use serde::{
-    de::{DeserializeOwned, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor},
+    de::{
+        DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+        VariantAccess, Visitor,
+    },
     Deserialize,
 };
 
-use crate::error::Error;
+use crate::{
+    config::{
+        Config, Encoding, EncodingOverrides, SignedEncoding, SortSpec, VariantAliases, VariantCodes,
+    },
+    error::Error,
+    hex_codec::{Backend as HexBackend, HexCodec},
+    metrics::Operation,
+    value::Value,
+};
+
+/// Default maximum nesting depth of types used by [`Deserializer`].
+pub const DEFAULT_RECURSION_LIMIT: usize = 128;
 
 /// Deserializer for deserializing values in strkey encoding.
 ///
@@ -29,6 +51,21 @@ use crate::error::Error;
 pub struct Deserializer<'de, R: ComponentRead<'de>> {
     input: R,
     buffer: Vec<u8>,
+    allow_missing_trailing: bool,
+    allow_trailing: bool,
+    strict_ordering: bool,
+    case_fold: bool,
+    percent_decoding: bool,
+    recursion_limit: Option<usize>,
+    depth: usize,
+    overrides: Option<Arc<EncodingOverrides>>,
+    encoding: Encoding,
+    signed_encoding: SignedEncoding,
+    sort_spec: Option<Arc<SortSpec>>,
+    variant_aliases: Option<Arc<VariantAliases>>,
+    variant_codes: Option<Arc<VariantCodes>>,
+    components_read: usize,
+    recorder: Option<FieldRecorder>,
     _de: PhantomData<&'de ()>,
 }
 
@@ -40,50 +77,463 @@ impl<'de, R: ComponentRead<'de>> Deserializer<'de, R> {
         Deserializer {
             input,
             buffer: Vec::new(),
-            _de: PhantomData::default(),
+            allow_missing_trailing: false,
+            allow_trailing: false,
+            strict_ordering: false,
+            case_fold: false,
+            percent_decoding: false,
+            recursion_limit: Some(DEFAULT_RECURSION_LIMIT),
+            depth: 0,
+            overrides: None,
+            encoding: Encoding::Hex,
+            signed_encoding: SignedEncoding::Offset,
+            sort_spec: None,
+            variant_aliases: None,
+            variant_codes: None,
+            components_read: 0,
+            recorder: None,
+            _de: PhantomData,
         }
     }
 
     /// Returns the deliminator used to separate values.
-    pub fn deliminator(&self) -> &str {
+    pub fn deliminator(&self) -> &[u8] {
         self.input.deliminator()
     }
 
     /// Sets the deliminator used to separate values.
-    pub fn set_deliminator(&mut self, deliminator: &'de str) {
-        self.input.set_deliminator(deliminator);
+    ///
+    /// The deliminator can be any byte sequence, such as `":"` or `b"\0"`.
+    pub fn set_deliminator<D: AsRef<[u8]> + ?Sized>(&mut self, deliminator: &'de D) {
+        self.input.set_deliminator(deliminator.as_ref());
     }
 
     /// Sets the deliminator used to separate values and returns a new serializer.
-    pub fn with_deliminator(mut self, deliminator: &'de str) -> Self {
+    pub fn with_deliminator<D: AsRef<[u8]> + ?Sized>(mut self, deliminator: &'de D) -> Self {
         self.set_deliminator(deliminator);
         self
     }
 
+    /// Returns whether missing trailing components are allowed.
+    pub fn allow_missing_trailing(&self) -> bool {
+        self.allow_missing_trailing
+    }
+
+    /// Sets whether missing trailing components are allowed.
+    ///
+    /// When enabled, a struct or tuple whose trailing fields are absent from
+    /// the input ends early instead of returning [`Error::Syntax`]. Serde then
+    /// fills the absent struct fields with their `#[serde(default)]` values.
+    /// This allows new fields to be appended to a key layout while still
+    /// reading keys written before the fields existed.
+    pub fn set_allow_missing_trailing(&mut self, value: bool) {
+        self.allow_missing_trailing = value;
+    }
+
+    /// Sets whether missing trailing components are allowed and returns a new deserializer.
+    pub fn with_allow_missing_trailing(mut self, value: bool) -> Self {
+        self.set_allow_missing_trailing(value);
+        self
+    }
+
+    /// Returns whether extra trailing components are allowed.
+    pub fn allow_trailing(&self) -> bool {
+        self.allow_trailing
+    }
+
+    /// Sets whether extra trailing components are allowed.
+    ///
+    /// When enabled, [`Self::end`] discards any remaining components instead
+    /// of returning [`Error::Syntax`]. This allows keys written with fields
+    /// appended to the layout to be read by older code.
+    pub fn set_allow_trailing(&mut self, value: bool) {
+        self.allow_trailing = value;
+    }
+
+    /// Sets whether extra trailing components are allowed and returns a new deserializer.
+    pub fn with_allow_trailing(mut self, value: bool) -> Self {
+        self.set_allow_trailing(value);
+        self
+    }
+
+    /// Returns whether strict ordering is enabled.
+    pub fn strict_ordering(&self) -> bool {
+        self.strict_ordering
+    }
+
+    /// Sets whether strict ordering is enabled.
+    ///
+    /// When enabled, unit variants are read as their `u32` index. See
+    /// [`crate::Serializer::set_strict_ordering`].
+    pub fn set_strict_ordering(&mut self, value: bool) {
+        self.strict_ordering = value;
+    }
+
+    /// Sets whether strict ordering is enabled and returns a new deserializer.
+    pub fn with_strict_ordering(mut self, value: bool) -> Self {
+        self.set_strict_ordering(value);
+        self
+    }
+
+    /// Returns whether components are case-folded.
+    pub fn case_fold(&self) -> bool {
+        self.case_fold
+    }
+
+    /// Sets whether components are case-folded.
+    ///
+    /// When enabled, strings are converted to lowercase, and booleans and
+    /// enum variant names are matched ignoring ASCII case. This is intended
+    /// for keys typed by hand and is disabled by default.
+    pub fn set_case_fold(&mut self, value: bool) {
+        self.case_fold = value;
+    }
+
+    /// Sets whether components are case-folded and returns a new deserializer.
+    pub fn with_case_fold(mut self, value: bool) -> Self {
+        self.set_case_fold(value);
+        self
+    }
+
+    /// Returns whether string components are percent-decoded.
+    pub fn percent_decoding(&self) -> bool {
+        self.percent_decoding
+    }
+
+    /// Sets whether string components are percent-decoded.
+    ///
+    /// When enabled, `%XX` escapes in strings, chars, and variant names are
+    /// decoded and `+` is read as a space, accepting keys that arrived via
+    /// URLs or HTML forms. This is the counterpart of
+    /// [`crate::Serializer::set_percent_encoding`]. A malformed escape or an
+    /// escape producing invalid UTF-8 returns [`Error::Data`].
+    pub fn set_percent_decoding(&mut self, value: bool) {
+        self.percent_decoding = value;
+    }
+
+    /// Sets whether string components are percent-decoded and returns a new deserializer.
+    pub fn with_percent_decoding(mut self, value: bool) -> Self {
+        self.set_percent_decoding(value);
+        self
+    }
+
+    /// Returns the maximum nesting depth of types.
+    pub fn recursion_limit(&self) -> Option<usize> {
+        self.recursion_limit
+    }
+
+    /// Sets the maximum nesting depth of types.
+    ///
+    /// Each nested newtype, tuple, struct, or enum counts as one level.
+    /// Exceeding the limit returns [`Error::RecursionLimitExceeded`] instead
+    /// of overflowing the stack on recursive types. The default is
+    /// [`DEFAULT_RECURSION_LIMIT`]. `None` disables the limit.
+    pub fn set_recursion_limit(&mut self, limit: Option<usize>) {
+        self.recursion_limit = limit;
+    }
+
+    /// Sets the maximum nesting depth of types and returns a new deserializer.
+    pub fn with_recursion_limit(mut self, limit: Option<usize>) -> Self {
+        self.set_recursion_limit(limit);
+        self
+    }
+
+    /// Returns the encoding of signed integers.
+    pub fn signed_encoding(&self) -> SignedEncoding {
+        self.signed_encoding
+    }
+
+    /// Sets the encoding of signed integers.
+    ///
+    /// This must match [`crate::Serializer::set_signed_encoding`]. A
+    /// component outside the range of the type returns [`Error::Data`].
+    pub fn set_signed_encoding(&mut self, value: SignedEncoding) {
+        self.signed_encoding = value;
+    }
+
+    /// Sets the encoding of signed integers and returns a new deserializer.
+    pub fn with_signed_encoding(mut self, value: SignedEncoding) -> Self {
+        self.set_signed_encoding(value);
+        self
+    }
+
+    /// Returns the encoding overrides.
+    pub fn overrides(&self) -> Option<&EncodingOverrides> {
+        self.overrides.as_deref()
+    }
+
+    /// Sets the encoding overrides.
+    ///
+    /// See [`crate::config::EncodingOverrides`].
+    pub fn set_overrides(&mut self, overrides: Arc<EncodingOverrides>) {
+        self.overrides = Some(overrides);
+    }
+
+    /// Sets the encoding overrides and returns a new deserializer.
+    pub fn with_overrides(mut self, overrides: Arc<EncodingOverrides>) -> Self {
+        self.set_overrides(overrides);
+        self
+    }
+
+    /// Returns the enum variant aliases.
+    pub fn variant_aliases(&self) -> Option<&VariantAliases> {
+        self.variant_aliases.as_deref()
+    }
+
+    /// Sets the enum variant aliases.
+    ///
+    /// Aliases are not used when strict ordering is enabled as variants are
+    /// decoded by index. See [`crate::config::VariantAliases`].
+    pub fn set_variant_aliases(&mut self, variant_aliases: Arc<VariantAliases>) {
+        self.variant_aliases = Some(variant_aliases);
+    }
+
+    /// Sets the enum variant aliases and returns a new deserializer.
+    pub fn with_variant_aliases(mut self, variant_aliases: Arc<VariantAliases>) -> Self {
+        self.set_variant_aliases(variant_aliases);
+        self
+    }
+
+    /// Returns the enum variant codes.
+    pub fn variant_codes(&self) -> Option<&VariantCodes> {
+        self.variant_codes.as_deref()
+    }
+
+    /// Sets the enum variant codes.
+    ///
+    /// A variant with a code is only accepted by its code. Codes are not
+    /// used when strict ordering is enabled as variants are decoded by
+    /// index. See [`crate::config::VariantCodes`].
+    pub fn set_variant_codes(&mut self, variant_codes: Arc<VariantCodes>) {
+        self.variant_codes = Some(variant_codes);
+    }
+
+    /// Sets the enum variant codes and returns a new deserializer.
+    pub fn with_variant_codes(mut self, variant_codes: Arc<VariantCodes>) -> Self {
+        self.set_variant_codes(variant_codes);
+        self
+    }
+
+    /// Returns the sort specification.
+    pub fn sort_spec(&self) -> Option<&SortSpec> {
+        self.sort_spec.as_deref()
+    }
+
+    /// Sets the sort specification.
+    ///
+    /// See [`crate::config::SortSpec`].
+    pub fn set_sort_spec(&mut self, sort_spec: Arc<SortSpec>) {
+        self.sort_spec = Some(sort_spec);
+    }
+
+    /// Sets the sort specification and returns a new deserializer.
+    pub fn with_sort_spec(mut self, sort_spec: Arc<SortSpec>) -> Self {
+        self.set_sort_spec(sort_spec);
+        self
+    }
+
+    /// Sets the buffer used for decoding components.
+    ///
+    /// Passing a buffer from a previous deserializer, obtained with
+    /// [`Self::into_scratch`], reuses its allocation.
+    pub fn set_scratch(&mut self, mut buffer: Vec<u8>) {
+        buffer.clear();
+        self.buffer = buffer;
+    }
+
+    /// Sets the buffer used for decoding components and returns a new deserializer.
+    pub fn with_scratch(mut self, buffer: Vec<u8>) -> Self {
+        self.set_scratch(buffer);
+        self
+    }
+
+    /// Unwrap and return the buffer used for decoding components.
+    pub fn into_scratch(self) -> Vec<u8> {
+        self.buffer
+    }
+
     /// Validates that the reader has fully processed the given input.
+    ///
+    /// If extra trailing components are allowed, they are discarded.
     pub fn end(&mut self) -> Result<(), Error> {
-        if self.input.next_component()?.is_some() {
+        if self.allow_trailing {
+            self.skip_remaining()?;
+            Ok(())
+        } else if self.input.next_component()?.is_some() {
             Err(Error::Syntax)
         } else {
             Ok(())
         }
     }
 
+    /// Returns an iterator over the remaining components without decoding
+    /// them.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use strkey::Deserializer;
+    ///
+    /// # fn main() -> Result<(), strkey::Error> {
+    /// let mut deserializer = Deserializer::from_slice(b"account:000004d2");
+    /// let components = deserializer
+    ///     .iter_components()
+    ///     .map(|component| component.map(|component| component.to_string()))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    ///
+    /// assert_eq!(components, vec!["account", "000004d2"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_components(&mut self) -> Components<'_, 'de, R> {
+        Components {
+            deserializer: self,
+            done: false,
+        }
+    }
+
+    /// Discards the remaining components and returns the number discarded.
+    pub fn skip_remaining(&mut self) -> Result<usize, Error> {
+        let mut count = 0;
+
+        while self.input.next_component()?.is_some() {
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    pub(crate) fn record_fields(&mut self) {
+        self.recorder = Some(FieldRecorder::default());
+    }
+
+    pub(crate) fn take_fields(&mut self) -> BTreeMap<&'static str, Value> {
+        self.recorder
+            .take()
+            .map(|recorder| recorder.values)
+            .unwrap_or_default()
+    }
+
+    fn recurse<F, T>(&mut self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Self) -> Result<T, Error>,
+    {
+        if let Some(limit) = self.recursion_limit {
+            if self.depth >= limit {
+                return Err(Error::RecursionLimitExceeded(limit));
+            }
+        }
+
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+
+        result
+    }
+
+    fn next_string_component(&mut self) -> Result<Component<'de>, Error> {
+        let mut component = self.next_component()?;
+
+        if self.percent_decoding {
+            if let Some(text) = percent_decode(component.as_str())? {
+                component = Component::Owned(text);
+            }
+        }
+
+        if self.case_fold {
+            component = Component::Owned(component.as_str().to_lowercase());
+        }
+
+        Ok(component)
+    }
+
+    /// Numbers the next component as if `index` components were already
+    /// read and decodes it with the given encoding, or the default encoding if
+    /// none.
+    ///
+    /// See [`crate::ser::Serializer::begin_component`].
+    pub(crate) fn begin_component(&mut self, index: usize, encoding: Option<Encoding>) {
+        self.components_read = index;
+        self.encoding = encoding.unwrap_or_default();
+    }
+
+    fn is_descending(&self) -> bool {
+        match &self.sort_spec {
+            Some(sort_spec) => sort_spec.is_descending(self.components_read - 1),
+            None => false,
+        }
+    }
+
     fn next_component(&mut self) -> Result<Component<'de>, Error> {
         let component = self.input.next_component()?.ok_or(Error::Syntax)?;
+        self.components_read += 1;
+
+        if self.is_descending() {
+            return Err(Error::UnsupportedType);
+        }
+
         Ok(component)
     }
 
-    fn next_component_decode_hex(&mut self) -> Result<(Component<'de>, &[u8]), Error> {
+    fn next_component_decode(
+        &mut self,
+        size: Option<usize>,
+    ) -> Result<(Component<'de>, &[u8]), Error> {
         let component = self.input.next_component()?.ok_or(Error::Syntax)?;
+        self.components_read += 1;
+
+        if self.encoding == Encoding::Hex {
+            let index = self.components_read - 1;
+
+            if let Some(size) = size {
+                if component.as_bytes().len() != size * 2 {
+                    return Err(Error::WrongWidth(
+                        index,
+                        component.as_bytes().len() / 2,
+                        size,
+                    ));
+                }
+            }
+
+            self.buffer.resize(component.as_str().len() / 2, 0);
+
+            HexBackend::decode_to_slice(component.as_bytes(), &mut self.buffer)
+                .map_err(|error| Error::Hex(index, error))?;
+        } else {
+            self.buffer.clear();
+            crate::config::decode(self.encoding, component.as_str(), size, &mut self.buffer)?;
+        }
 
-        self.buffer.resize(component.as_str().len() / 2, 0);
+        if self.is_descending() {
+            if size.is_none() {
+                return Err(Error::UnsupportedType);
+            }
 
-        hex::decode_to_slice(component.as_str(), &mut self.buffer)
-            .map_err(|error| Error::Data(format!("{}", error)))?;
+            for byte in self.buffer.iter_mut() {
+                *byte = !*byte;
+            }
+        }
 
         Ok((component, &self.buffer))
     }
+
+    fn next_fixed<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let index = self.components_read;
+        let (_, buffer) = self.next_component_decode(Some(N))?;
+
+        buffer
+            .try_into()
+            .map_err(|_| Error::WrongWidth(index, buffer.len(), N))
+    }
+
+    fn next_signed<const N: usize>(&mut self) -> Result<i128, Error> {
+        let buffer = self.next_fixed::<N>()?;
+        let mut bytes = [0u8; 16];
+        bytes[16 - N..].copy_from_slice(&buffer);
+
+        self.signed_encoding
+            .decode(u128::from_be_bytes(bytes), N as u32 * 8)
+    }
 }
 
 impl<'de> Deserializer<'de, SliceReader<'de>> {
@@ -91,6 +541,36 @@ impl<'de> Deserializer<'de, SliceReader<'de>> {
     pub fn from_slice(input: &'de [u8]) -> Self {
         Self::new(SliceReader::new(input))
     }
+
+    /// Replaces the input with the given slice so the deserializer can be
+    /// reused for another key.
+    ///
+    /// The options and allocated memory are kept.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use serde::de::Deserialize;
+    /// use strkey::Deserializer;
+    ///
+    /// # fn main() -> Result<(), strkey::Error> {
+    /// let keys = [&b"a:01"[..], &b"b:02"[..]];
+    /// let mut deserializer = Deserializer::from_slice(b"");
+    ///
+    /// for key in keys.iter() {
+    ///     deserializer.reset(key);
+    ///     let (_name, id) = <(&str, u8)>::deserialize(&mut deserializer)?;
+    ///     deserializer.end()?;
+    ///     assert!(id > 0);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reset(&mut self, input: &'de [u8]) {
+        self.input.reset(input);
+        self.buffer.clear();
+        self.components_read = 0;
+    }
 }
 
 impl<'de, R: Read> Deserializer<'de, IoReader<'de, R>> {
@@ -98,9 +578,42 @@ impl<'de, R: Read> Deserializer<'de, IoReader<'de, R>> {
     pub fn from_reader(input: R) -> Self {
         Self::new(IoReader::new(input))
     }
+
+    /// Unwrap and return the reader.
+    ///
+    /// Unless the reader has a terminator, the key is read until the end of
+    /// the input, so nothing remains to be read. See
+    /// [`IoReader::set_terminator`] for decoding a key followed by other
+    /// data.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use serde::de::Deserialize;
+    /// use strkey::{de::IoReader, Deserializer};
+    ///
+    /// # fn main() -> Result<(), strkey::Error> {
+    /// let input = &b"account:000004d2\npayload"[..];
+    /// let mut deserializer = Deserializer::new(IoReader::new(input).with_terminator(b'\n'));
+    /// let key = <(String, u32)>::deserialize(&mut deserializer)?;
+    /// deserializer.end()?;
+    ///
+    /// let mut payload = String::new();
+    /// deserializer.into_inner().read_to_string(&mut payload)?;
+    ///
+    /// assert_eq!(key, ("account".to_string(), 1234));
+    /// assert_eq!(payload, "payload");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_inner(self) -> R {
+        self.input.into_inner()
+    }
 }
 
-impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut Deserializer<'de, R> {
+impl<'de, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &mut Deserializer<'de, R> {
     type Error = Error;
 
     fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
@@ -114,137 +627,138 @@ impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut De
     where
         V: Visitor<'de>,
     {
-        if let Some(component) = self.input.next_component()? {
-            match component.as_str() {
-                "true" => visitor.visit_bool(true),
-                "false" => visitor.visit_bool(false),
-                _ => Err(Error::Data(component.to_owned())),
-            }
-        } else {
-            Err(Error::Syntax)
-        }
+        let component = self.next_component()?;
+
+        let value = match component.as_str() {
+            "true" => true,
+            "false" => false,
+            text if self.case_fold && text.eq_ignore_ascii_case("true") => true,
+            text if self.case_fold && text.eq_ignore_ascii_case("false") => false,
+            _ => return Err(Error::Data(component.to_owned())),
+        };
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::Bool(value)))?;
+
+        visitor.visit_bool(value)
     }
 
-    /// signed integer magic https://github.com/danburkert/bytekey/blob/6980b9e33281d875f03f4c9a953b93a384eac085/src/decoder.rs#L76
+    // signed integer magic https://github.com/danburkert/bytekey/blob/6980b9e33281d875f03f4c9a953b93a384eac085/src/decoder.rs#L76
 
     fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 1] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let value = self.next_signed::<1>()? as i8;
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::I64(value.into())))?;
 
-        visitor.visit_i8(i8::from_be_bytes(buffer) ^ i8::MIN)
+        visitor.visit_i8(value)
     }
 
     fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 2] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let value = self.next_signed::<2>()? as i16;
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::I64(value.into())))?;
 
-        visitor.visit_i16(i16::from_be_bytes(buffer) ^ i16::MIN)
+        visitor.visit_i16(value)
     }
 
     fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 4] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let value = self.next_signed::<4>()? as i32;
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::I64(value.into())))?;
 
-        visitor.visit_i32(i32::from_be_bytes(buffer) ^ i32::MIN)
+        visitor.visit_i32(value)
     }
 
     fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 8] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let value = self.next_signed::<8>()? as i64;
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::I64(value)))?;
 
-        visitor.visit_i64(i64::from_be_bytes(buffer) ^ i64::MIN)
+        visitor.visit_i64(value)
     }
 
     fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 16] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let value = self.next_signed::<16>()?;
+        FieldRecorder::record(&mut self.recorder, || {
+            i64::try_from(value)
+                .map(Value::I64)
+                .map_err(|_| Error::Data(value.to_string()))
+        })?;
 
-        visitor.visit_i128(i128::from_be_bytes(buffer) ^ i128::MIN)
+        visitor.visit_i128(value)
     }
 
     fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 1] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let buffer = self.next_fixed::<1>()?;
+
+        let value = u8::from_be_bytes(buffer);
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::U64(value.into())))?;
 
-        visitor.visit_u8(u8::from_be_bytes(buffer))
+        visitor.visit_u8(value)
     }
 
     fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 2] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let buffer = self.next_fixed::<2>()?;
+
+        let value = u16::from_be_bytes(buffer);
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::U64(value.into())))?;
 
-        visitor.visit_u16(u16::from_be_bytes(buffer))
+        visitor.visit_u16(value)
     }
 
     fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 4] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let buffer = self.next_fixed::<4>()?;
+
+        let value = u32::from_be_bytes(buffer);
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::U64(value.into())))?;
 
-        visitor.visit_u32(u32::from_be_bytes(buffer))
+        visitor.visit_u32(value)
     }
 
     fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 8] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let buffer = self.next_fixed::<8>()?;
+
+        let value = u64::from_be_bytes(buffer);
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::U64(value)))?;
 
-        visitor.visit_u64(u64::from_be_bytes(buffer))
+        visitor.visit_u64(value)
     }
 
     fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 16] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let buffer = self.next_fixed::<16>()?;
+
+        let value = u128::from_be_bytes(buffer);
+        FieldRecorder::record(&mut self.recorder, || {
+            u64::try_from(value)
+                .map(Value::U64)
+                .map_err(|_| Error::Data(value.to_string()))
+        })?;
 
-        visitor.visit_u128(u128::from_be_bytes(buffer))
+        visitor.visit_u128(value)
     }
 
     // Floating point magic https://github.com/danburkert/bytekey/blob/6980b9e33281d875f03f4c9a953b93a384eac085/src/decoder.rs#L104
@@ -253,38 +767,45 @@ impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut De
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 4] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let buffer = self.next_fixed::<4>()?;
         let val = i32::from_be_bytes(buffer);
         let t = ((val ^ i32::MIN) >> 31) | i32::MIN;
 
-        visitor.visit_f32(f32::from_bits((val ^ t) as u32))
+        let value = f32::from_bits((val ^ t) as u32);
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::F64(value.into())))?;
+
+        visitor.visit_f32(value)
     }
 
     fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (component, buffer) = self.next_component_decode_hex()?;
-        let buffer: [u8; 8] = buffer
-            .try_into()
-            .map_err(|_| Error::Data(component.to_owned()))?;
+        let buffer = self.next_fixed::<8>()?;
         let val = i64::from_be_bytes(buffer);
         let t = ((val ^ i64::MIN) >> 63) | i64::MIN;
 
-        visitor.visit_f64(f64::from_bits((val ^ t) as u64))
+        let value = f64::from_bits((val ^ t) as u64);
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::F64(value)))?;
+
+        visitor.visit_f64(value)
     }
 
     fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let component = self.next_component()?;
+        let mut component = self.next_component()?;
+
+        if self.percent_decoding {
+            if let Some(text) = percent_decode(component.as_str())? {
+                component = Component::Owned(text);
+            }
+        }
 
         if component.as_str().char_indices().count() == 1 {
             if let Some(char) = component.as_str().chars().next() {
+                FieldRecorder::record(&mut self.recorder, || Ok(Value::Str(char.to_string())))?;
                 visitor.visit_char(char)
             } else {
                 Err(Error::Data(component.to_owned()))
@@ -298,7 +819,9 @@ impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut De
     where
         V: Visitor<'de>,
     {
-        let component = self.next_component()?;
+        let component = self.next_string_component()?;
+
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::Str(component.to_owned())))?;
 
         match component {
             Component::Owned(value) => visitor.visit_string(value),
@@ -310,7 +833,9 @@ impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut De
     where
         V: Visitor<'de>,
     {
-        let component = self.next_component()?;
+        let component = self.next_string_component()?;
+
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::Str(component.to_owned())))?;
 
         match component {
             Component::Owned(value) => visitor.visit_string(value),
@@ -322,18 +847,22 @@ impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut De
     where
         V: Visitor<'de>,
     {
-        let (_component, buffer) = self.next_component_decode_hex()?;
+        self.next_component_decode(None)?;
+        let buffer = &self.buffer;
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::Bytes(buffer.clone())))?;
 
-        visitor.visit_bytes(buffer)
+        visitor.visit_bytes(&self.buffer)
     }
 
     fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        let (_component, buffer) = self.next_component_decode_hex()?;
+        self.next_component_decode(None)?;
+        let buffer = &self.buffer;
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::Bytes(buffer.clone())))?;
 
-        visitor.visit_bytes(buffer)
+        visitor.visit_byte_buf(self.buffer.to_vec())
     }
 
     fn deserialize_option<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
@@ -348,6 +877,7 @@ impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut De
         V: Visitor<'de>,
     {
         self.input.preload_components()?;
+        FieldRecorder::record(&mut self.recorder, || Ok(Value::Unit))?;
         visitor.visit_unit()
     }
 
@@ -365,13 +895,27 @@ impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut De
 
     fn deserialize_newtype_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        self.recurse(|deserializer| {
+            if let Some(encoding) = deserializer
+                .overrides
+                .as_ref()
+                .and_then(|overrides| overrides.newtype(name))
+            {
+                let previous = std::mem::replace(&mut deserializer.encoding, encoding);
+                let result = visitor.visit_newtype_struct(&mut *deserializer);
+                deserializer.encoding = previous;
+
+                result
+            } else {
+                visitor.visit_newtype_struct(deserializer)
+            }
+        })
     }
 
     fn deserialize_seq<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
@@ -381,15 +925,15 @@ impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut De
         Err(Error::UnsupportedType)
     }
 
-    fn deserialize_tuple<V>(mut self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(CollectionDeserializer::new(&mut self))
+        self.recurse(|deserializer| visitor.visit_seq(CollectionDeserializer::new(deserializer)))
     }
 
     fn deserialize_tuple_struct<V>(
-        mut self,
+        self,
         _name: &'static str,
         _len: usize,
         visitor: V,
@@ -397,7 +941,7 @@ impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut De
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(CollectionDeserializer::new(&mut self))
+        self.recurse(|deserializer| visitor.visit_seq(CollectionDeserializer::new(deserializer)))
     }
 
     fn deserialize_map<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
@@ -408,27 +952,35 @@ impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut De
     }
 
     fn deserialize_struct<V>(
-        mut self,
+        self,
         _name: &'static str,
-        _fields: &'static [&'static str],
+        fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_seq(CollectionDeserializer::new(&mut self))
+        self.recurse(|deserializer| {
+            visitor.visit_seq(CollectionDeserializer::with_fields(deserializer, fields))
+        })
     }
 
     fn deserialize_enum<V>(
-        mut self,
-        _name: &'static str,
-        _variants: &'static [&'static str],
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_enum(CollectionDeserializer::new(&mut self))
+        self.recurse(|deserializer| {
+            let mut access = CollectionDeserializer::new(deserializer);
+            access.enum_name = Some(name);
+            access.variants = variants;
+
+            visitor.visit_enum(access)
+        })
     }
 
     fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -452,11 +1004,28 @@ impl<'de, 'a, R: ComponentRead<'de>> serde::de::Deserializer<'de> for &'a mut De
 
 struct CollectionDeserializer<'a, 'de: 'a, R: ComponentRead<'de>> {
     deserializer: &'a mut Deserializer<'de, R>,
+    fields: &'static [&'static str],
+    index: usize,
+    enum_name: Option<&'static str>,
+    variants: &'static [&'static str],
 }
 
 impl<'a, 'de, R: ComponentRead<'de>> CollectionDeserializer<'a, 'de, R> {
     fn new(deserializer: &'a mut Deserializer<'de, R>) -> Self {
-        Self { deserializer }
+        Self::with_fields(deserializer, &[])
+    }
+
+    fn with_fields(
+        deserializer: &'a mut Deserializer<'de, R>,
+        fields: &'static [&'static str],
+    ) -> Self {
+        Self {
+            deserializer,
+            fields,
+            index: 0,
+            enum_name: None,
+            variants: &[],
+        }
     }
 }
 
@@ -467,7 +1036,54 @@ impl<'de, 'a, R: ComponentRead<'de>> SeqAccess<'de> for CollectionDeserializer<'
     where
         T: serde::de::DeserializeSeed<'de>,
     {
-        seed.deserialize(&mut *self.deserializer).map(Some)
+        if self.deserializer.allow_missing_trailing
+            && !self.deserializer.input.has_next_component()?
+        {
+            return Ok(None);
+        }
+
+        let field = self.fields.get(self.index);
+        self.index += 1;
+
+        if let (Some(recorder), Some(field)) = (&mut self.deserializer.recorder, field) {
+            let previous = recorder.field.replace(field);
+            let result = self.deserialize_element(seed, Some(field));
+
+            if let Some(recorder) = &mut self.deserializer.recorder {
+                recorder.field = previous;
+            }
+
+            return result;
+        }
+
+        self.deserialize_element(seed, field)
+    }
+}
+
+impl<'a, 'de, R: ComponentRead<'de>> CollectionDeserializer<'a, 'de, R> {
+    fn deserialize_element<T>(
+        &mut self,
+        seed: T,
+        field: Option<&&'static str>,
+    ) -> Result<Option<T::Value>, Error>
+    where
+        T: serde::de::DeserializeSeed<'de>,
+    {
+        let encoding = match (&self.deserializer.overrides, field) {
+            (Some(overrides), Some(field)) => overrides.field(field),
+            _ => None,
+        };
+
+        match encoding {
+            Some(encoding) => {
+                let previous = std::mem::replace(&mut self.deserializer.encoding, encoding);
+                let result = seed.deserialize(&mut *self.deserializer).map(Some);
+                self.deserializer.encoding = previous;
+
+                result
+            }
+            None => seed.deserialize(&mut *self.deserializer).map(Some),
+        }
     }
 }
 
@@ -497,7 +1113,66 @@ impl<'de, 'a, R: ComponentRead<'de>> EnumAccess<'de> for CollectionDeserializer<
     where
         V: serde::de::DeserializeSeed<'de>,
     {
-        let val = seed.deserialize(&mut *self.deserializer)?;
+        let aliases = match (&self.deserializer.variant_aliases, self.enum_name) {
+            (Some(aliases), Some(name)) => Some((aliases.clone(), name)),
+            _ => None,
+        };
+        let codes = match (&self.deserializer.variant_codes, self.enum_name) {
+            (Some(codes), Some(name)) => Some((codes.clone(), name)),
+            _ => None,
+        };
+
+        let val = if self.deserializer.strict_ordering {
+            let index = u32::deserialize(&mut *self.deserializer)?;
+            seed.deserialize(IntoDeserializer::<Error>::into_deserializer(index))?
+        } else if aliases.is_some()
+            || codes.is_some()
+            || self.deserializer.case_fold
+            || self.deserializer.percent_decoding
+        {
+            let mut component = self.deserializer.next_component()?;
+
+            if self.deserializer.percent_decoding {
+                if let Some(text) = percent_decode(component.as_str())? {
+                    component = Component::Owned(text);
+                }
+            }
+
+            let mut variant = component.as_str();
+
+            if let Some((codes, name)) = &codes {
+                match codes.variant(name, variant) {
+                    Some(coded) => {
+                        if self.variants.contains(&variant) && codes.code(name, variant).is_none() {
+                            return Err(Error::Data(variant.to_string()));
+                        }
+
+                        variant = coded;
+                    }
+                    None if codes.code(name, variant).is_some() => {
+                        return Err(Error::Data(variant.to_string()));
+                    }
+                    None => {}
+                }
+            }
+
+            if let Some((aliases, name)) = &aliases {
+                variant = aliases.variant(name, variant).unwrap_or(variant);
+            }
+
+            if self.deserializer.case_fold {
+                variant = self
+                    .variants
+                    .iter()
+                    .find(|name| name.eq_ignore_ascii_case(variant))
+                    .copied()
+                    .unwrap_or(variant);
+            }
+
+            seed.deserialize(IntoDeserializer::<Error>::into_deserializer(variant))?
+        } else {
+            seed.deserialize(&mut *self.deserializer)?
+        };
 
         Ok((val, self))
     }
@@ -536,22 +1211,51 @@ impl<'de, 'a, R: ComponentRead<'de>> VariantAccess<'de> for CollectionDeserializ
     }
 }
 
-/// Represents either a owned or borrowed string (values within separators).
-///
-/// Intended for use only within this crate.
-pub enum Component<'de> {
-    /// Owned string.
-    Owned(String),
-
-    /// Borrowed string.
-    Borrowed(&'de str),
+/// Records the value of each named struct field while deserializing.
+#[derive(Default)]
+struct FieldRecorder {
+    field: Option<&'static str>,
+    values: BTreeMap<&'static str, Value>,
 }
 
-impl<'de> Component<'de> {
+impl FieldRecorder {
+    fn record<F>(recorder: &mut Option<Self>, value: F) -> Result<(), Error>
+    where
+        F: FnOnce() -> Result<Value, Error>,
+    {
+        if let Some(recorder) = recorder {
+            if let Some(field) = recorder.field {
+                if recorder.values.insert(field, value()?).is_some() {
+                    return Err(Error::Data(format!(
+                        "field {} has more than one component",
+                        field
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Represents either a owned or borrowed string (values within separators).
+///
+/// Components compare equal by their string regardless of whether they are
+/// owned or borrowed.
+#[derive(Clone)]
+pub enum Component<'de> {
+    /// Owned string.
+    Owned(String),
+
+    /// Borrowed string.
+    Borrowed(&'de str),
+}
+
+impl<'de> Component<'de> {
     /// Get string as reference.
     pub fn as_str(&self) -> &str {
         match self {
-            Component::Owned(value) => &value,
+            Component::Owned(value) => value,
             Component::Borrowed(value) => value,
         }
     }
@@ -573,28 +1277,105 @@ impl<'de> Component<'de> {
     }
 }
 
+impl<'de> std::fmt::Display for Component<'de> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'de> std::fmt::Debug for Component<'de> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Component").field(&self.as_str()).finish()
+    }
+}
+
+impl<'a, 'b> PartialEq<Component<'b>> for Component<'a> {
+    fn eq(&self, other: &Component<'b>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<'de> Eq for Component<'de> {}
+
+impl<'de> PartialEq<str> for Component<'de> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'de, 'a> PartialEq<&'a str> for Component<'de> {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// Iterator returned by [`Deserializer::iter_components`].
+pub struct Components<'a, 'de, R: ComponentRead<'de>> {
+    deserializer: &'a mut Deserializer<'de, R>,
+    done: bool,
+}
+
+impl<'a, 'de, R: ComponentRead<'de>> Iterator for Components<'a, 'de, R> {
+    type Item = Result<Component<'de>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.deserializer.input.next_component() {
+            Ok(Some(component)) => {
+                self.deserializer.components_read += 1;
+                Some(Ok(component))
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(error) => {
+                self.done = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl<'a, 'de, R: ComponentRead<'de>> std::iter::FusedIterator for Components<'a, 'de, R> {}
+
 /// Trait that reads components (values within separators) from an input.
 ///
-/// This trait is not intended to be implemented outside of this crate.
-pub trait ComponentRead<'de> {
+/// This trait is sealed and cannot be implemented outside of this crate, so
+/// methods can be added to it without breaking changes.
+pub trait ComponentRead<'de>: sealed::Sealed {
     /// Return the deliminator used to separate values.
-    fn deliminator(&self) -> &'de str;
+    fn deliminator(&self) -> &'de [u8];
 
     /// Set the deliminator used to separate values.
-    fn set_deliminator(&mut self, deliminator: &'de str);
+    fn set_deliminator(&mut self, deliminator: &'de [u8]);
 
     /// Split input into components if it hasn't been already.
     fn preload_components(&mut self) -> Result<(), Error>;
 
     /// Return the next value.
     fn next_component(&mut self) -> Result<Option<Component<'de>>, Error>;
+
+    /// Return whether there are remaining values.
+    fn has_next_component(&mut self) -> Result<bool, Error>;
+}
+
+pub(crate) mod sealed {
+    /// Prevents [`super::ComponentRead`] from being implemented outside of
+    /// this crate.
+    pub trait Sealed {}
 }
 
 /// Component reader for a std io reader.
 pub struct IoReader<'de, R: Read> {
     input: R,
-    deliminator: &'de str,
-    components: Option<VecDeque<Component<'de>>>,
+    deliminator: &'de [u8],
+    terminator: Option<u8>,
+    components: Option<VecDeque<Vec<u8>>>,
+    index: usize,
 }
 
 impl<'de, R: Read> IoReader<'de, R> {
@@ -602,128 +1383,833 @@ impl<'de, R: Read> IoReader<'de, R> {
     pub fn new(input: R) -> Self {
         Self {
             input,
-            deliminator: ":",
+            deliminator: b":",
+            terminator: None,
             components: None,
+            index: 0,
+        }
+    }
+
+    /// Returns the byte that marks the end of the key.
+    pub fn terminator(&self) -> Option<u8> {
+        self.terminator
+    }
+
+    /// Sets the byte that marks the end of the key.
+    ///
+    /// When set, the key is read up to and including the terminator instead
+    /// of until the end of the input, so the data after the key can be read
+    /// from [`Self::into_inner`]. The input is read one byte at a time so no
+    /// data after the terminator is consumed; wrapping the input in a
+    /// [`std::io::BufReader`] avoids the cost of many small reads.
+    pub fn set_terminator(&mut self, terminator: Option<u8>) {
+        self.terminator = terminator;
+    }
+
+    /// Sets the byte that marks the end of the key and returns the reader.
+    pub fn with_terminator(mut self, terminator: u8) -> Self {
+        self.set_terminator(Some(terminator));
+        self
+    }
+
+    /// Unwrap and return the reader.
+    pub fn into_inner(self) -> R {
+        self.input
+    }
+
+    fn read_key(&mut self, buf: &mut Vec<u8>) -> std::io::Result<()> {
+        let terminator = match self.terminator {
+            Some(terminator) => terminator,
+            None => {
+                self.input.read_to_end(buf)?;
+                return Ok(());
+            }
+        };
+
+        let mut byte = [0u8; 1];
+
+        loop {
+            match self.input.read(&mut byte) {
+                Ok(0) => return Ok(()),
+                Ok(_) if byte[0] == terminator => return Ok(()),
+                Ok(_) => buf.push(byte[0]),
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
         }
     }
 }
 
+impl<'de, R: Read> sealed::Sealed for IoReader<'de, R> {}
+
 impl<'de, R: Read> ComponentRead<'de> for IoReader<'de, R> {
-    fn deliminator(&self) -> &'de str {
-        &self.deliminator
+    fn deliminator(&self) -> &'de [u8] {
+        self.deliminator
     }
 
-    fn set_deliminator(&mut self, deliminator: &'de str) {
+    fn set_deliminator(&mut self, deliminator: &'de [u8]) {
         self.deliminator = deliminator
     }
 
     fn preload_components(&mut self) -> Result<(), Error> {
         if self.components.is_none() {
-            let mut buf = String::new();
-            self.input.read_to_string(&mut buf)?;
+            let mut buf = Vec::new();
+            self.read_key(&mut buf)?;
+
+            self.components = Some(
+                split_components(&buf, self.deliminator)
+                    .map(|component| component.to_vec())
+                    .collect(),
+            );
+        }
 
-            let mut components = VecDeque::new();
+        Ok(())
+    }
+
+    fn next_component(&mut self) -> Result<Option<Component<'de>>, Error> {
+        self.preload_components()?;
+
+        match self.components.as_mut().unwrap().pop_front() {
+            Some(component) => {
+                let component = String::from_utf8(component)
+                    .map_err(|error| Error::ComponentUtf8(self.index, error.utf8_error()))?;
+                self.index += 1;
+
+                Ok(Some(Component::Owned(component)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn has_next_component(&mut self) -> Result<bool, Error> {
+        self.preload_components()?;
+
+        Ok(!self.components.as_ref().unwrap().is_empty())
+    }
+}
+
+/// Component reader for a slice.
+pub struct SliceReader<'de> {
+    input: &'de [u8],
+    deliminator: &'de [u8],
+    components: VecDeque<&'de [u8]>,
+    loaded: bool,
+    index: usize,
+}
+
+impl<'de> SliceReader<'de> {
+    /// Construct a component reader for a slice.
+    pub fn new(input: &'de [u8]) -> Self {
+        Self {
+            input,
+            deliminator: b":",
+            components: VecDeque::new(),
+            loaded: false,
+            index: 0,
+        }
+    }
+
+    /// Replaces the input with the given slice.
+    ///
+    /// The deliminator and allocated memory are kept.
+    pub fn reset(&mut self, input: &'de [u8]) {
+        self.input = input;
+        self.components.clear();
+        self.loaded = false;
+        self.index = 0;
+    }
+}
+
+impl<'de> sealed::Sealed for SliceReader<'de> {}
+
+impl<'de> ComponentRead<'de> for SliceReader<'de> {
+    fn deliminator(&self) -> &'de [u8] {
+        self.deliminator
+    }
+
+    fn set_deliminator(&mut self, deliminator: &'de [u8]) {
+        self.deliminator = deliminator
+    }
+
+    fn preload_components(&mut self) -> Result<(), Error> {
+        if !self.loaded {
+            self.components.clear();
+            self.components
+                .extend(split_components(self.input, self.deliminator));
+            self.loaded = true;
+        }
+
+        Ok(())
+    }
+
+    fn next_component(&mut self) -> Result<Option<Component<'de>>, Error> {
+        self.preload_components()?;
+
+        match self.components.pop_front() {
+            Some(component) => {
+                let component = std::str::from_utf8(component)
+                    .map_err(|error| Error::ComponentUtf8(self.index, error))?;
+                self.index += 1;
+
+                Ok(Some(Component::Borrowed(component)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn has_next_component(&mut self) -> Result<bool, Error> {
+        self.preload_components()?;
+
+        Ok(!self.components.is_empty())
+    }
+}
+
+/// Splits the input on the deliminator.
+///
+/// An empty input has no components. Each component is validated as UTF-8
+/// separately by the reader so an invalid byte only affects its component.
+pub(crate) fn split_components<'a>(
+    input: &'a [u8],
+    deliminator: &'a [u8],
+) -> impl Iterator<Item = &'a [u8]> + 'a {
+    let mut remaining = if input.is_empty() { None } else { Some(input) };
+
+    std::iter::from_fn(move || {
+        let current = remaining?;
+
+        match find_subslice(current, deliminator) {
+            Some(index) => {
+                remaining = Some(&current[index + deliminator.len()..]);
+                Some(&current[..index])
+            }
+            None => {
+                remaining = None;
+                Some(current)
+            }
+        }
+    })
+}
+
+/// Decodes `%XX` escapes and `+` in the text.
+///
+/// Returns `None` if the text contains neither.
+pub(crate) fn percent_decode(text: &str) -> Result<Option<String>, Error> {
+    if !text.contains(['%', '+']) {
+        return Ok(None);
+    }
+
+    let error = || Error::Data(text.to_string());
+    let mut output = Vec::with_capacity(text.len());
+    let mut bytes = text.bytes();
+
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'%' => {
+                let high = bytes.next().and_then(|byte| (byte as char).to_digit(16));
+                let low = bytes.next().and_then(|byte| (byte as char).to_digit(16));
+
+                match (high, low) {
+                    (Some(high), Some(low)) => output.push((high << 4 | low) as u8),
+                    _ => return Err(error()),
+                }
+            }
+            b'+' => output.push(b' '),
+            byte => output.push(byte),
+        }
+    }
+
+    String::from_utf8(output).map(Some).map_err(|_| error())
+}
+
+pub(crate) fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Deserialize the value from a byte array slice.
+pub fn from_slice<'a, T>(value: &'a [u8]) -> Result<T, Error>
+where
+    T: Deserialize<'a>,
+{
+    crate::metrics::observe::<T, _, _>(Operation::Decode, || {
+        let mut deserializer = Deserializer::from_slice(value);
+        let output = serde::de::Deserialize::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+
+        Ok((output, Some(value.len())))
+    })
+}
+
+/// Returns the first component of a key without decoding the rest.
+///
+/// The first component is conventionally a namespace or table name, so this
+/// is a cheap way to tag keys by kind. This uses the default configuration.
+/// See [`crate::config::Config::kind_of`] for other deliminators and for
+/// decoding escapes, and [`raw_kind_of`] for a faster variant that borrows
+/// the component as is.
+///
+/// Example:
+///
+/// ```rust
+/// # fn main() -> Result<(), strkey::Error> {
+/// let key = strkey::to_vec(&("user", 42u32))?;
+/// assert_eq!(strkey::kind_of(&key)?, "user");
+/// # Ok(())
+/// # }
+/// ```
+pub fn kind_of(key: &[u8]) -> Result<Cow<'_, str>, Error> {
+    Config::new().kind_of(key)
+}
+
+/// Returns the raw first component of a key without decoding the rest.
+///
+/// This is the fast path of [`kind_of`] for keys split on `:`. It is raw
+/// component access: the key is always split on the default deliminator
+/// `:`, no configuration is constructed, and the component is returned
+/// exactly as it appears in the key. Escapes written by percent encoding or
+/// [`crate::types::PathComponent`], such as `%3a`, are not decoded, so the
+/// result differs from [`crate::config::Config::kind_of`] on percent-encoded
+/// keys.
+///
+/// Returns [`Error::Syntax`] for an empty key and [`Error::ComponentUtf8`]
+/// if the component is not valid UTF-8.
+///
+/// Example:
+///
+/// ```rust
+/// # fn main() -> Result<(), strkey::Error> {
+/// let key = strkey::to_vec(&("user", 42u32, "a:b"))?;
+/// assert_eq!(strkey::raw_kind_of(&key)?, "user");
+/// assert_eq!(strkey::raw_kind_of(b"order%20line:01")?, "order%20line");
+/// # Ok(())
+/// # }
+/// ```
+pub fn raw_kind_of(key: &[u8]) -> Result<&str, Error> {
+    first_component(key, b":")
+}
+
+/// Returns the index of the first component of a key equal to the given
+/// encoded component.
+///
+/// Unlike a substring search, only whole components match, so an ID does not
+/// match inside a longer hexadecimal component. The key is split on the
+/// default deliminator. The component must be encoded the same way as in the
+/// key, such as by [`crate::to_vec`], so a component containing the
+/// deliminator never matches. Use [`crate::config::Config::find_component`]
+/// for other deliminators.
+///
+/// Example:
+///
+/// ```rust
+/// # fn main() -> Result<(), strkey::Error> {
+/// let key = strkey::to_vec(&("order", 0x12345678u32, 0x34u8))?;
+/// let id = strkey::to_vec(&0x34u8)?;
+///
+/// assert_eq!(key.windows(2).position(|window| window == id), Some(8));
+/// assert_eq!(strkey::find_component(&key, &id), Some(2));
+/// assert_eq!(strkey::find_component(&key, b"56"), None);
+/// # Ok(())
+/// # }
+/// ```
+pub fn find_component(key: &[u8], component: &[u8]) -> Option<usize> {
+    find_component_with(key, component, b":")
+}
+
+pub(crate) fn find_component_with(
+    key: &[u8],
+    component: &[u8],
+    deliminator: &[u8],
+) -> Option<usize> {
+    if find_subslice(component, deliminator).is_some() {
+        return None;
+    }
+
+    split_components(key, deliminator).position(|candidate| candidate == component)
+}
+
+pub(crate) fn first_component<'a>(key: &'a [u8], deliminator: &[u8]) -> Result<&'a str, Error> {
+    if key.is_empty() {
+        return Err(Error::Syntax);
+    }
+
+    let component = match find_subslice(key, deliminator) {
+        Some(index) => &key[..index],
+        None => key,
+    };
+
+    std::str::from_utf8(component).map_err(|error| Error::ComponentUtf8(0, error))
+}
+
+/// Decodes the leading components of a key laid out as `T` into `P`.
+///
+/// `P` must have the same component types as the start of `T`, which is
+/// checked by probing both types. Only the components of `P` are decoded;
+/// the rest of the key is not read. Returns [`Error::Other`] if `P` is not a
+/// prefix of `T`.
+///
+/// Example:
+///
+/// ```rust
+/// # fn main() -> Result<(), strkey::Error> {
+/// let key = strkey::to_vec(&("acme", 7u32, [0xabu8; 16]))?;
+/// let (tenant, id) = strkey::de::decode_prefix::<(&str, u32), (&str, u32, [u8; 16])>(&key)?;
+///
+/// assert_eq!((tenant, id), ("acme", 7));
+/// # Ok(())
+/// # }
+/// ```
+pub fn decode_prefix<'a, P, T>(key: &'a [u8]) -> Result<P, Error>
+where
+    P: Deserialize<'a>,
+    T: Deserialize<'a>,
+{
+    let prefix = crate::value::probe_layout::<P>();
+    let layout = crate::value::probe_layout::<T>();
+
+    if prefix.is_empty() || !layout.starts_with(&prefix) {
+        return Err(Error::Other(format!(
+            "{} is not a prefix of {}",
+            std::any::type_name::<P>(),
+            std::any::type_name::<T>()
+        )));
+    }
+
+    crate::metrics::observe::<P, _, _>(Operation::Decode, || {
+        let mut deserializer = Deserializer::from_slice(key);
+        let output = P::deserialize(&mut deserializer)?;
+
+        Ok((output, Some(key.len())))
+    })
+}
+
+/// Deserialize strkey encoding to produce the requested value from the given reader.
+pub fn from_reader<R, T>(reader: R) -> Result<T, Error>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    crate::metrics::observe::<T, _, _>(Operation::Decode, || {
+        let mut deserializer = Deserializer::from_reader(reader);
+        let output = serde::de::Deserialize::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+
+        Ok((output, None))
+    })
+}
+
+/// Deserialize the value from a byte array slice using the given seed.
+pub fn from_slice_seed<'a, S>(seed: S, value: &'a [u8]) -> Result<S::Value, Error>
+where
+    S: DeserializeSeed<'a>,
+{
+    let mut deserializer = Deserializer::from_slice(value);
+    let output = seed.deserialize(&mut deserializer)?;
+    deserializer.end()?;
+
+    Ok(output)
+}
+
+/// Deserialize the value from the given reader using the given seed.
+pub fn from_reader_seed<'de, R, S>(seed: S, reader: R) -> Result<S::Value, Error>
+where
+    R: Read,
+    S: DeserializeSeed<'de>,
+{
+    let mut deserializer = Deserializer::from_reader(reader);
+    let output = seed.deserialize(&mut deserializer)?;
+    deserializer.end()?;
+
+    Ok(output)
+}
+
+/// Value of one of two types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Either<L, R> {
+    /// Value of the first type.
+    Left(L),
+    /// Value of the second type.
+    Right(R),
+}
+
+/// Deserialize the value from a byte array slice as the first of two types that matches.
+///
+/// If neither type matches, returns [`Error::NoLayoutMatched`] containing
+/// the errors of both types.
+pub fn from_slice_either<'a, L, R>(value: &'a [u8]) -> Result<Either<L, R>, Error>
+where
+    L: Deserialize<'a>,
+    R: Deserialize<'a>,
+{
+    let left_error = match from_slice::<L>(value) {
+        Ok(output) => return Ok(Either::Left(output)),
+        Err(error) => error,
+    };
+    let right_error = match from_slice::<R>(value) {
+        Ok(output) => return Ok(Either::Right(output)),
+        Err(error) => error,
+    };
+
+    Err(Error::NoLayoutMatched(vec![left_error, right_error]))
+}
+
+/// Deserialize the value from a byte array slice and return the byte span of
+/// each component consumed.
+///
+/// The spans are in the order the components were read and exclude the
+/// deliminators. This allows tools to locate the components of a key using
+/// the same parsing as the deserializer.
+///
+/// Example:
+///
+/// ```
+/// # fn main() -> Result<(), strkey::Error> {
+/// let (value, spans) = strkey::de::from_slice_with_spans::<(&str, u8)>(b"abc:05")?;
+///
+/// assert_eq!(value, ("abc", 5));
+/// assert_eq!(spans, vec![0..3, 4..6]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_slice_with_spans<'a, T>(value: &'a [u8]) -> Result<(T, Vec<Range<usize>>), Error>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::new(SpanReader::new(value));
+    let output = serde::de::Deserialize::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+
+    Ok((output, deserializer.input.spans))
+}
+
+/// Returns the byte span of each component consumed when deserializing the
+/// value from a byte array slice.
+///
+/// See [`from_slice_with_spans`].
+pub fn spans<'a, T>(value: &'a [u8]) -> Result<Vec<Range<usize>>, Error>
+where
+    T: Deserialize<'a>,
+{
+    from_slice_with_spans::<T>(value).map(|(_output, spans)| spans)
+}
+
+/// Component reader that records the byte offsets of each component read.
+struct SpanReader<'de> {
+    input: &'de [u8],
+    deliminator: &'de [u8],
+    components: VecDeque<Range<usize>>,
+    loaded: bool,
+    spans: Vec<Range<usize>>,
+}
+
+impl<'de> SpanReader<'de> {
+    fn new(input: &'de [u8]) -> Self {
+        Self {
+            input,
+            deliminator: b":",
+            components: VecDeque::new(),
+            loaded: false,
+            spans: Vec::new(),
+        }
+    }
+}
+
+impl<'de> sealed::Sealed for SpanReader<'de> {}
+
+impl<'de> ComponentRead<'de> for SpanReader<'de> {
+    fn deliminator(&self) -> &'de [u8] {
+        self.deliminator
+    }
+
+    fn set_deliminator(&mut self, deliminator: &'de [u8]) {
+        self.deliminator = deliminator
+    }
+
+    fn preload_components(&mut self) -> Result<(), Error> {
+        if !self.loaded {
+            let mut start = 0;
+            let deliminator_len = self.deliminator.len();
+
+            self.components.clear();
+            self.components
+                .extend(
+                    split_components(self.input, self.deliminator).map(|component| {
+                        let span = start..start + component.len();
+                        start = span.end + deliminator_len;
+                        span
+                    }),
+                );
+            self.loaded = true;
+        }
+
+        Ok(())
+    }
+
+    fn next_component(&mut self) -> Result<Option<Component<'de>>, Error> {
+        self.preload_components()?;
+
+        match self.components.pop_front() {
+            Some(span) => {
+                let component = std::str::from_utf8(&self.input[span.clone()])
+                    .map_err(|error| Error::ComponentUtf8(self.spans.len(), error))?;
+                self.spans.push(span);
+
+                Ok(Some(Component::Borrowed(component)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn has_next_component(&mut self) -> Result<bool, Error> {
+        self.preload_components()?;
+
+        Ok(!self.components.is_empty())
+    }
+}
+
+/// Reusable buffers for deserializing many keys from readers.
+///
+/// The key is read into a buffer owned by the scratch space and deserialized
+/// as a slice, so the value can borrow strings from the buffer and
+/// components are not copied into separate allocations as with
+/// [`from_reader`]. The buffers are reused by the next call once the value is
+/// dropped.
+///
+/// Example:
+///
+/// ```
+/// use strkey::de::Scratch;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let mut scratch = Scratch::new();
+///
+/// for key in [&b"user:0001"[..], &b"group:0002"[..]] {
+///     let (kind, id) = scratch.from_reader::<_, (&str, u16)>(key)?;
+///     assert!(kind == "user" || kind == "group");
+///     assert!(id > 0);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Scratch {
+    input: Vec<u8>,
+    buffer: Vec<u8>,
+}
 
-            if !buf.is_empty() {
-                for component in buf.split(self.deliminator) {
-                    components.push_back(Component::Owned(component.to_string()));
-                }
-            }
+impl Scratch {
+    /// Construct an empty scratch space.
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-            self.components = Some(components);
+    /// Construct a scratch space with the given capacity for the key.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            input: Vec::with_capacity(capacity),
+            buffer: Vec::with_capacity(capacity),
         }
-
-        Ok(())
     }
 
-    fn next_component(&mut self) -> Result<Option<Component<'de>>, Error> {
-        self.preload_components()?;
+    /// Reads all of the given reader and deserializes the value.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_reader<'s, R, T>(&'s mut self, mut reader: R) -> Result<T, Error>
+    where
+        R: Read,
+        T: Deserialize<'s>,
+    {
+        self.input.clear();
+        reader.read_to_end(&mut self.input)?;
 
-        let components = self.components.as_mut().unwrap();
+        let mut deserializer =
+            Deserializer::from_slice(&self.input).with_scratch(std::mem::take(&mut self.buffer));
+        let result =
+            T::deserialize(&mut deserializer).and_then(|output| deserializer.end().map(|_| output));
+        self.buffer = deserializer.into_scratch();
 
-        Ok(components.pop_front())
+        result
     }
 }
 
-/// Component reader for a slice.
-pub struct SliceReader<'de> {
-    input: &'de [u8],
-    deliminator: &'de str,
-    components: Option<VecDeque<Component<'de>>>,
+/// Returns an iterator that deserializes one value per line from the given reader.
+///
+/// See [`KeyIter`] for details.
+pub fn iter_from_reader<R, T>(reader: R) -> KeyIter<R, T>
+where
+    R: BufRead,
+    T: DeserializeOwned,
+{
+    KeyIter::new(reader)
 }
 
-impl<'de> SliceReader<'de> {
-    /// Construct a component reader for a slice.
-    pub fn new(input: &'de [u8]) -> Self {
+/// Iterator that deserializes values from records of a reader.
+///
+/// Records are separated by a newline by default. An empty final record is
+/// ignored so input ending with a separator does not produce an error.
+pub struct KeyIter<R, T> {
+    reader: R,
+    separator: u8,
+    buffer: Vec<u8>,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T: DeserializeOwned> KeyIter<R, T> {
+    /// Construct an iterator reading records from the given reader.
+    pub fn new(reader: R) -> Self {
         Self {
-            input,
-            deliminator: ":",
-            components: None,
+            reader,
+            separator: b'\n',
+            buffer: Vec::new(),
+            _value: PhantomData,
         }
     }
-}
 
-impl<'de> ComponentRead<'de> for SliceReader<'de> {
-    fn deliminator(&self) -> &'de str {
-        &self.deliminator
+    /// Returns the byte used to separate records.
+    pub fn separator(&self) -> u8 {
+        self.separator
     }
 
-    fn set_deliminator(&mut self, deliminator: &'de str) {
-        self.deliminator = deliminator
+    /// Sets the byte used to separate records.
+    pub fn set_separator(&mut self, separator: u8) {
+        self.separator = separator;
     }
 
-    fn preload_components(&mut self) -> Result<(), Error> {
-        if self.components.is_none() {
-            let decoded_str = std::str::from_utf8(self.input)?;
-
-            let mut components = VecDeque::new();
+    /// Sets the byte used to separate records and returns a new iterator.
+    pub fn with_separator(mut self, separator: u8) -> Self {
+        self.set_separator(separator);
+        self
+    }
 
-            if !decoded_str.is_empty() {
-                for component in decoded_str.split(self.deliminator) {
-                    components.push_back(Component::Borrowed(component));
-                }
-            }
+    /// Unwrap and return the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
 
-            self.components = Some(components);
-        }
+impl<R: BufRead, T: DeserializeOwned> Iterator for KeyIter<R, T> {
+    type Item = Result<T, Error>;
 
-        Ok(())
-    }
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.clear();
 
-    fn next_component(&mut self) -> Result<Option<Component<'de>>, Error> {
-        self.preload_components()?;
+        match self.reader.read_until(self.separator, &mut self.buffer) {
+            Ok(0) => return None,
+            Ok(_) => {}
+            Err(error) => return Some(Err(error.into())),
+        }
 
-        let components = self.components.as_mut().unwrap();
+        if self.buffer.last() == Some(&self.separator) {
+            self.buffer.pop();
+        }
 
-        Ok(components.pop_front())
+        Some(from_slice(&self.buffer))
     }
 }
 
-/// Deserialize the value from a byte array slice.
-pub fn from_slice<'a, T>(value: &'a [u8]) -> Result<T, Error>
+/// Returns an iterator that deserializes one value per line of the input
+/// and reports the position of each line that fails to decode.
+///
+/// Decoding continues after a bad line so the remaining lines can be
+/// salvaged from a partially corrupted export. Lines end with `\n` and an
+/// optional preceding `\r`. An empty final line is ignored.
+///
+/// Example:
+///
+/// ```rust
+/// # fn main() -> Result<(), strkey::Error> {
+/// let input = b"a:01\nb:zz\nc:03\n";
+/// let results = strkey::de::scan_recovering::<(&str, u8)>(input).collect::<Vec<_>>();
+///
+/// assert_eq!(results[0].as_ref().unwrap(), &("a", 1));
+/// assert_eq!(results[1].as_ref().unwrap_err().line, 2);
+/// assert_eq!(results[1].as_ref().unwrap_err().offset, 5);
+/// assert_eq!(results[2].as_ref().unwrap(), &("c", 3));
+/// # Ok(())
+/// # }
+/// ```
+pub fn scan_recovering<'a, T>(input: &'a [u8]) -> ScanRecovering<'a, T>
 where
     T: Deserialize<'a>,
 {
-    let mut deserializer = Deserializer::from_slice(value);
-    let output = serde::de::Deserialize::deserialize(&mut deserializer)?;
-    deserializer.end()?;
+    ScanRecovering {
+        input,
+        offset: 0,
+        line: 0,
+        _value: PhantomData,
+    }
+}
 
-    Ok(output)
+/// Error decoding a line, returned by [`ScanRecovering`].
+#[derive(Debug)]
+pub struct LineError {
+    /// Line number, starting at 1.
+    pub line: usize,
+
+    /// Byte offset of the start of the line in the input.
+    pub offset: usize,
+
+    /// Reason the line could not be decoded.
+    pub error: Error,
 }
 
-/// Deserialize strkey encoding to produce the requested value from the given reader.
-pub fn from_reader<R, T>(reader: R) -> Result<T, Error>
-where
-    R: Read,
-    T: DeserializeOwned,
-{
-    let mut deserializer = Deserializer::from_reader(reader);
-    let output = serde::de::Deserialize::deserialize(&mut deserializer)?;
-    deserializer.end()?;
+impl std::fmt::Display for LineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {} at offset {}: {}",
+            self.line, self.offset, self.error
+        )
+    }
+}
 
-    Ok(output)
+impl std::error::Error for LineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
 }
 
+/// Iterator returned by [`scan_recovering`].
+pub struct ScanRecovering<'a, T> {
+    input: &'a [u8],
+    offset: usize,
+    line: usize,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: Deserialize<'a>> Iterator for ScanRecovering<'a, T> {
+    type Item = Result<T, LineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = &self.input[self.offset..];
+
+        if remaining.is_empty() {
+            return None;
+        }
+
+        let (line, consumed) = match remaining.iter().position(|&byte| byte == b'\n') {
+            Some(index) => (&remaining[..index], index + 1),
+            None => (remaining, remaining.len()),
+        };
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let offset = self.offset;
+
+        self.offset += consumed;
+        self.line += 1;
+
+        Some(from_slice(line).map_err(|error| LineError {
+            line: self.line,
+            offset,
+            error,
+        }))
+    }
+}
+
+impl<'a, T: Deserialize<'a>> std::iter::FusedIterator for ScanRecovering<'a, T> {}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -803,6 +2289,44 @@ mod tests {
         assert!(from_slice::<f64>(b"hh").is_err());
     }
 
+    #[cfg(feature = "ordered-float")]
+    #[test]
+    fn test_ordered_float() {
+        use ordered_float::{NotNan, OrderedFloat};
+
+        let value = from_slice::<OrderedFloat<f32>>(b"c49a51ec").unwrap();
+        assert_eq!(value, OrderedFloat(1234.56f32));
+
+        let value = from_slice::<NotNan<f64>>(b"c0934a456d5cfaad").unwrap();
+        assert_eq!(value, NotNan::new(1234.5678f64).unwrap());
+
+        let nan = crate::to_vec(&f32::NAN).unwrap();
+        assert!(from_slice::<OrderedFloat<f32>>(&nan).unwrap().is_nan());
+        assert!(from_slice::<NotNan<f32>>(&nan).is_err());
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn test_heapless() {
+        let value = from_slice::<(heapless::String<8>, u8)>(b"abc:01").unwrap();
+        assert_eq!(value.0.as_str(), "abc");
+
+        let error = from_slice::<heapless::String<2>>(b"abc").unwrap_err();
+        assert!(error.to_string().contains("no more than 2 bytes"));
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_bytes_crate() {
+        let value = from_slice::<bytes::Bytes>(b"cafe").unwrap();
+        assert_eq!(value, bytes::Bytes::from_static(b"\xca\xfe"));
+
+        let value = from_slice::<(bytes::BytesMut, u8)>(b"cafe:01").unwrap();
+        assert_eq!(&value.0[..], b"\xca\xfe");
+
+        assert!(from_slice::<bytes::Bytes>(b"xyz").is_err());
+    }
+
     #[test]
     fn test_char() {
         let value = from_slice::<char>(b"\xF0\x9F\x90\xBA").unwrap();
@@ -832,6 +2356,14 @@ mod tests {
 
         assert!(from_slice::<&Bytes>(b"h").is_err());
         assert!(from_slice::<ByteBuf>(b"hh").is_err());
+
+        let mut deserializer = Deserializer::from_slice(b"cafe:00");
+        ByteBuf::deserialize(&mut deserializer).unwrap();
+        let capacity = deserializer.buffer.capacity();
+
+        assert!(capacity >= 2);
+        assert_eq!(ByteBuf::deserialize(&mut deserializer).unwrap(), b"\x00");
+        assert_eq!(deserializer.buffer.capacity(), capacity);
     }
 
     #[test]
@@ -877,6 +2409,7 @@ mod tests {
     #[test]
     fn test_newtype_variant() {
         #[derive(Deserialize)]
+        #[allow(dead_code)]
         enum MyEnum {
             Hello(u8),
         }
@@ -911,6 +2444,7 @@ mod tests {
     #[test]
     fn test_tuple_variant() {
         #[derive(Deserialize)]
+        #[allow(dead_code)]
         enum MyEnum {
             Hello(u8, u8),
         }
@@ -967,10 +2501,544 @@ mod tests {
         assert_eq!(value.1, "world");
     }
 
+    #[test]
+    fn test_iter_components() {
+        let mut deserializer = Deserializer::from_slice(b"abc:01:");
+        let _ = String::deserialize(&mut deserializer).unwrap();
+        let components = deserializer
+            .iter_components()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(components, vec!["01", ""]);
+        assert_eq!(components[0].to_string(), "01");
+        assert_eq!(format!("{:?}", components[0]), r#"Component("01")"#);
+        assert_eq!(components[0], Component::Owned("01".to_string()));
+        assert_eq!(components[0].clone(), components[0]);
+        assert!(deserializer.iter_components().next().is_none());
+
+        let mut deserializer = Deserializer::from_slice(b"a:\xff");
+        let mut components = deserializer.iter_components();
+        assert_eq!(components.next().unwrap().unwrap(), "a");
+        assert!(components.next().unwrap().is_err());
+        assert!(components.next().is_none());
+    }
+
+    #[test]
+    fn test_reader_terminator() {
+        let mut input = &b"a:01\nb:02\n"[..];
+
+        for expected in [("a".to_string(), 1u8), ("b".to_string(), 2)] {
+            let mut deserializer =
+                Deserializer::new(IoReader::new(&mut input).with_terminator(b'\n'));
+            assert_eq!(
+                <(String, u8)>::deserialize(&mut deserializer).unwrap(),
+                expected
+            );
+            deserializer.end().unwrap();
+        }
+
+        assert!(input.is_empty());
+
+        let mut deserializer = Deserializer::from_reader(&b"a:01"[..]);
+        assert_eq!(deserializer.input.terminator(), None);
+        String::deserialize(&mut deserializer).unwrap();
+        assert!(deserializer.into_inner().is_empty());
+    }
+
+    #[test]
+    fn test_case_fold() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Kind {
+            User,
+        }
+
+        let mut deserializer = Deserializer::from_slice(b"ACCOUNT:USER:True").with_case_fold(true);
+
+        assert!(deserializer.case_fold());
+        assert_eq!(
+            <(String, Kind, bool)>::deserialize(&mut deserializer).unwrap(),
+            ("account".to_string(), Kind::User, true)
+        );
+
+        let mut deserializer = Deserializer::from_slice(b"USER");
+        assert!(Kind::deserialize(&mut deserializer).is_err());
+    }
+
+    #[test]
+    fn test_kind_of() {
+        assert_eq!(kind_of(b"user:0000002a").unwrap(), "user");
+        assert_eq!(kind_of(b"user").unwrap(), "user");
+        assert!(matches!(kind_of(b""), Err(Error::Syntax)));
+        assert_eq!(
+            kind_of(b"a%3ab:c").unwrap(),
+            Config::new().kind_of(b"a%3ab:c").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_raw_kind_of() {
+        assert_eq!(raw_kind_of(b"user:0000002a").unwrap(), "user");
+        assert_eq!(raw_kind_of(b"user").unwrap(), "user");
+        assert_eq!(raw_kind_of(b":0000002a").unwrap(), "");
+        assert_eq!(raw_kind_of(b"a%3ab:c").unwrap(), "a%3ab");
+        assert!(matches!(raw_kind_of(b""), Err(Error::Syntax)));
+        assert!(matches!(
+            raw_kind_of(b"\xff:a"),
+            Err(Error::ComponentUtf8(0, _))
+        ));
+        assert_eq!(raw_kind_of(b"user:\xff").unwrap(), "user");
+    }
+
+    #[test]
+    fn test_find_component() {
+        assert_eq!(find_component(b"a:0000002a:2a", b"2a"), Some(2));
+        assert_eq!(find_component(b"a:0000002a:2a", b"a"), Some(0));
+        assert_eq!(find_component(b"a:0000002a:2a", b"0000002a"), Some(1));
+        assert_eq!(find_component(b"a:0000002a", b"2a"), None);
+        assert_eq!(find_component(b"a::b", b""), Some(1));
+        assert_eq!(find_component(b"a:b", b"a:b"), None);
+        assert_eq!(find_component(b"", b""), None);
+        assert_eq!(find_component_with(b"a/b:c", b"b:c", b"/"), Some(1));
+    }
+
+    #[test]
+    fn test_percent_decoding() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Kind {
+            #[serde(rename = "a b")]
+            Spaced,
+        }
+
+        let mut deserializer =
+            Deserializer::from_slice(b"a%20b%2fc+d:%c3%A9:a+b:x").with_percent_decoding(true);
+
+        assert!(deserializer.percent_decoding());
+        assert_eq!(
+            <(String, char, Kind, &str)>::deserialize(&mut deserializer).unwrap(),
+            ("a b/c d".to_string(), 'é', Kind::Spaced, "x")
+        );
+
+        let mut deserializer = Deserializer::from_slice(b"a%2").with_percent_decoding(true);
+        assert!(matches!(
+            String::deserialize(&mut deserializer),
+            Err(Error::Data(_))
+        ));
+
+        let mut deserializer = Deserializer::from_slice(b"%ff").with_percent_decoding(true);
+        assert!(matches!(
+            String::deserialize(&mut deserializer),
+            Err(Error::Data(_))
+        ));
+
+        let mut deserializer = Deserializer::from_slice(b"a%20b");
+        assert_eq!(String::deserialize(&mut deserializer).unwrap(), "a%20b");
+    }
+
+    #[test]
+    fn test_component_errors() {
+        use std::error::Error as _;
+
+        assert!(matches!(
+            from_slice::<(u8, u16)>(b"01:001"),
+            Err(Error::WrongWidth(1, 1, 2))
+        ));
+
+        let error = from_slice::<(u8, u16)>(b"01:00zz").unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Hex(1, crate::error::HexError::InvalidDigit(2))
+        ));
+        assert!(error.source().is_some());
+
+        let error = from_slice::<&str>(b"\xff").unwrap_err();
+        assert!(matches!(error, Error::ComponentUtf8(0, _)));
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn test_scan_recovering() {
+        let input = b"a:01\r\n\xff:02\n\nb:0003\nc:03";
+        let mut iter = scan_recovering::<(String, u8)>(input);
+
+        assert_eq!(iter.next().unwrap().unwrap(), ("a".to_string(), 1));
+
+        let error = iter.next().unwrap().unwrap_err();
+        assert_eq!((error.line, error.offset), (2, 6));
+        assert!(matches!(error.error, Error::ComponentUtf8(0, _)));
+        assert!(error.to_string().starts_with("line 2 at offset 6: "));
+
+        let error = iter.next().unwrap().unwrap_err();
+        assert_eq!((error.line, error.offset), (3, 11));
+
+        let error = iter.next().unwrap().unwrap_err();
+        assert_eq!((error.line, error.offset), (4, 12));
+        assert!(matches!(error.error, Error::WrongWidth(1, 2, 1)));
+
+        assert_eq!(iter.next().unwrap().unwrap(), ("c".to_string(), 3));
+        assert!(iter.next().is_none());
+        assert!(iter.next().is_none());
+        assert_eq!(scan_recovering::<u8>(b"01\n").count(), 1);
+    }
+
+    #[test]
+    fn test_recursion_limit() {
+        struct Endless;
+
+        impl<'de> Deserialize<'de> for Endless {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserializer.deserialize_newtype_struct("Endless", EndlessVisitor)
+            }
+        }
+
+        struct EndlessVisitor;
+
+        impl<'de> Visitor<'de> for EndlessVisitor {
+            type Value = Endless;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("endless newtype")
+            }
+
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                Endless::deserialize(deserializer)
+            }
+        }
+
+        let mut deserializer = Deserializer::from_slice(b"01");
+        assert_eq!(
+            deserializer.recursion_limit(),
+            Some(DEFAULT_RECURSION_LIMIT)
+        );
+        assert!(matches!(
+            Endless::deserialize(&mut deserializer),
+            Err(Error::RecursionLimitExceeded(DEFAULT_RECURSION_LIMIT))
+        ));
+
+        let mut deserializer = Deserializer::from_slice(b"01").with_recursion_limit(Some(2));
+        assert!(matches!(
+            <(((u8,),),)>::deserialize(&mut deserializer),
+            Err(Error::RecursionLimitExceeded(2))
+        ));
+
+        let mut deserializer = Deserializer::from_slice(b"01").with_recursion_limit(Some(3));
+        assert_eq!(
+            <(((u8,),),)>::deserialize(&mut deserializer).unwrap(),
+            (((1,),),)
+        );
+    }
+
+    #[test]
+    fn test_strict_ordering() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Level {
+            Low,
+            High,
+        }
+
+        let mut deserializer =
+            Deserializer::from_slice(b"00000001:00000000").with_strict_ordering(true);
+
+        assert!(deserializer.strict_ordering());
+        assert_eq!(
+            <(Level, Level)>::deserialize(&mut deserializer).unwrap(),
+            (Level::High, Level::Low)
+        );
+
+        let mut deserializer = Deserializer::from_slice(b"00000002").with_strict_ordering(true);
+        assert!(Level::deserialize(&mut deserializer).is_err());
+    }
+
+    #[test]
+    fn test_binary_deliminator() {
+        let mut deserializer = Deserializer::from_slice(b"a\x00b:c\x0001").with_deliminator("\0");
+
+        assert_eq!(deserializer.deliminator(), b"\0");
+        assert_eq!(
+            <(&str, &str, u8)>::deserialize(&mut deserializer).unwrap(),
+            ("a", "b:c", 1)
+        );
+
+        let mut deserializer = Deserializer::from_slice(b"a\xff01").with_deliminator(&[0xff]);
+
+        assert_eq!(
+            <(&str, u8)>::deserialize(&mut deserializer).unwrap(),
+            ("a", 1)
+        );
+    }
+
     #[test]
     fn test_deliminator_and_tuple_nesting() {
         let value = from_slice::<((&str, &str), (u8, u8), ((), ()))>(b"hello:world:01:02").unwrap();
 
         assert_eq!(value, (("hello", "world"), (1u8, 2u8), ((), ())));
     }
+
+    #[test]
+    fn test_iter_from_reader() {
+        let values = iter_from_reader::<_, (String, u8)>(b"a:01\nb:02\n".as_ref())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(values, vec![("a".to_string(), 1), ("b".to_string(), 2)]);
+
+        let values = iter_from_reader::<_, u8>(b"01;x;03".as_ref())
+            .with_separator(b';')
+            .collect::<Vec<_>>();
+
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0].as_ref().unwrap(), &1);
+        assert!(values[1].is_err());
+        assert_eq!(values[2].as_ref().unwrap(), &3);
+    }
+
+    #[test]
+    fn test_seed() {
+        struct Counted<'a>(&'a mut usize);
+
+        impl<'de, 'a> DeserializeSeed<'de> for Counted<'a> {
+            type Value = (String, u8);
+
+            fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                *self.0 += 1;
+                <(String, u8)>::deserialize(deserializer)
+            }
+        }
+
+        let mut count = 0;
+
+        let value = from_slice_seed(Counted(&mut count), b"a:01").unwrap();
+        assert_eq!(value, ("a".to_string(), 1));
+
+        let value = from_reader_seed(Counted(&mut count), b"b:02".as_ref()).unwrap();
+        assert_eq!(value, ("b".to_string(), 2));
+
+        assert!(from_slice_seed(Counted(&mut count), b"a:01:02").is_err());
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_allow_missing_trailing() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct MyStruct {
+            name: String,
+            #[serde(default)]
+            version: u8,
+            #[serde(default)]
+            suffix: String,
+        }
+
+        assert!(from_slice::<MyStruct>(b"abc:01").is_err());
+
+        let mut deserializer =
+            Deserializer::from_slice(b"abc:01").with_allow_missing_trailing(true);
+        let value = MyStruct::deserialize(&mut deserializer).unwrap();
+        deserializer.end().unwrap();
+
+        assert_eq!(
+            value,
+            MyStruct {
+                name: "abc".to_string(),
+                version: 1,
+                suffix: String::new()
+            }
+        );
+
+        let mut deserializer = Deserializer::from_slice(b"abc").with_allow_missing_trailing(true);
+        let value = MyStruct::deserialize(&mut deserializer).unwrap();
+
+        assert_eq!(value.version, 0);
+
+        let mut deserializer = Deserializer::from_slice(b"").with_allow_missing_trailing(true);
+        assert!(MyStruct::deserialize(&mut deserializer).is_err());
+    }
+
+    #[test]
+    fn test_allow_trailing() {
+        assert!(from_slice::<(&str, u8)>(b"abc:01:02:03").is_err());
+
+        let mut deserializer = Deserializer::from_slice(b"abc:01:02:03").with_allow_trailing(true);
+        let value = <(&str, u8)>::deserialize(&mut deserializer).unwrap();
+        deserializer.end().unwrap();
+
+        assert_eq!(value, ("abc", 1));
+
+        let mut deserializer = Deserializer::from_slice(b"abc:01:02:03");
+        <(&str, u8)>::deserialize(&mut deserializer).unwrap();
+
+        assert_eq!(deserializer.skip_remaining().unwrap(), 2);
+        deserializer.end().unwrap();
+    }
+
+    #[test]
+    fn test_component_utf8() {
+        let mut deserializer = Deserializer::from_slice(b"abc:\xff:01");
+        assert_eq!(String::deserialize(&mut deserializer).unwrap(), "abc");
+        assert!(matches!(
+            String::deserialize(&mut deserializer),
+            Err(Error::ComponentUtf8(1, _))
+        ));
+
+        assert!(matches!(
+            from_slice::<(String, String)>(b"a:b\xffc"),
+            Err(Error::ComponentUtf8(1, _))
+        ));
+        assert!(matches!(
+            from_reader::<_, (String, String)>(&b"a:b\xffc"[..]),
+            Err(Error::ComponentUtf8(1, _))
+        ));
+
+        let mut deserializer = Deserializer::from_reader(&b"abc:\xff:01"[..]);
+        assert_eq!(String::deserialize(&mut deserializer).unwrap(), "abc");
+        assert!(matches!(
+            String::deserialize(&mut deserializer),
+            Err(Error::ComponentUtf8(1, _))
+        ));
+    }
+
+    #[test]
+    fn test_split_components() {
+        let split = |input: &'static [u8], deliminator: &'static [u8]| {
+            split_components(input, deliminator).collect::<Vec<_>>()
+        };
+
+        assert_eq!(split(b"", b":"), Vec::<&[u8]>::new());
+        assert_eq!(split(b"a", b":"), vec![&b"a"[..]]);
+        assert_eq!(split(b":", b":"), vec![&b""[..], &b""[..]]);
+        assert_eq!(split(b"a::b", b"::"), vec![&b"a"[..], &b"b"[..]]);
+        assert_eq!(split(b"a:::b", b"::"), vec![&b"a"[..], &b":b"[..]]);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut deserializer = Deserializer::from_slice(b"a/01/02")
+            .with_deliminator("/")
+            .with_allow_trailing(true);
+
+        assert_eq!(
+            <(&str, u8)>::deserialize(&mut deserializer).unwrap(),
+            ("a", 1)
+        );
+        deserializer.end().unwrap();
+
+        deserializer.reset(b"b/03");
+        assert_eq!(
+            <(&str, u8)>::deserialize(&mut deserializer).unwrap(),
+            ("b", 3)
+        );
+        deserializer.end().unwrap();
+
+        deserializer.reset(b"c");
+        assert!(<(&str, u8)>::deserialize(&mut deserializer).is_err());
+
+        deserializer.reset(b"d/cafe");
+        assert_eq!(
+            <(&str, ByteBuf)>::deserialize(&mut deserializer).unwrap(),
+            ("d", ByteBuf::from(b"\xca\xfe".to_vec()))
+        );
+    }
+
+    #[test]
+    fn test_scratch() {
+        let mut scratch = Scratch::with_capacity(16);
+
+        let value = scratch
+            .from_reader::<_, (&str, u8)>(&b"abc:01"[..])
+            .unwrap();
+        assert_eq!(value, ("abc", 1));
+
+        let value = scratch
+            .from_reader::<_, (&str, ByteBuf)>(&b"de:cafe"[..])
+            .unwrap();
+        assert_eq!(value, ("de", ByteBuf::from(b"\xca\xfe".to_vec())));
+
+        assert!(scratch.from_reader::<_, u8>(&b"01:02"[..]).is_err());
+        assert_eq!(scratch.from_reader::<_, u8>(&b"03"[..]).unwrap(), 3);
+
+        let mut deserializer = Deserializer::from_slice(b"0102").with_scratch(vec![9; 8]);
+        u16::deserialize(&mut deserializer).unwrap();
+        assert!(deserializer.into_scratch().capacity() >= 8);
+    }
+
+    #[test]
+    fn test_spans() {
+        assert_eq!(
+            spans::<(String, (), u16, ByteBuf)>(b"a b:0001:").unwrap(),
+            vec![0..3, 4..8, 9..9]
+        );
+        assert_eq!(spans::<()>(b"").unwrap(), vec![]);
+        assert!(spans::<u8>(b"01:02").is_err());
+
+        let mut deserializer = Deserializer::new(SpanReader::new(b"a//b")).with_deliminator("//");
+        <(&str, &str)>::deserialize(&mut deserializer).unwrap();
+        assert_eq!(deserializer.input.spans, vec![0..1, 3..4]);
+
+        let mut deserializer = Deserializer::new(SpanReader::new(b"a:b")).with_deliminator("/");
+        <String>::deserialize(&mut deserializer).unwrap();
+        assert_eq!(deserializer.input.spans, vec![0..3]);
+    }
+
+    #[test]
+    fn test_decode_prefix() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Route<'a> {
+            tenant: &'a str,
+            shard: u16,
+        }
+
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Full {
+            route: (String, u16),
+            hash: serde_bytes::ByteBuf,
+        }
+
+        let key = b"acme:0007:cafe";
+
+        assert_eq!(
+            decode_prefix::<Route, Full>(key).unwrap(),
+            Route {
+                tenant: "acme",
+                shard: 7
+            }
+        );
+        assert_eq!(decode_prefix::<(String,), Full>(key).unwrap().0, "acme");
+        assert!(matches!(
+            decode_prefix::<(u16,), Full>(key),
+            Err(Error::Other(_))
+        ));
+        assert!(matches!(
+            decode_prefix::<(), Full>(key),
+            Err(Error::Other(_))
+        ));
+        assert!(decode_prefix::<Route, Full>(b"acme").is_err());
+    }
+
+    #[test]
+    fn test_from_slice_either() {
+        type Layout = Either<(&'static str, u8), (&'static str, u16)>;
+
+        assert_eq!(
+            from_slice_either::<(&str, u8), (&str, u16)>(b"a:01").unwrap(),
+            Layout::Left(("a", 1))
+        );
+        assert_eq!(
+            from_slice_either::<(&str, u8), (&str, u16)>(b"a:0001").unwrap(),
+            Layout::Right(("a", 1))
+        );
+
+        match from_slice_either::<(&str, u8), (&str, u16)>(b"a:01:02") {
+            Err(Error::NoLayoutMatched(errors)) => assert_eq!(errors.len(), 2),
+            _ => panic!(),
+        }
+    }
 }
@@ -0,0 +1,172 @@
+//! Displaying keys
+//!
+//! Helpers for showing and inspecting encoded keys in logs and tools.
+//!
+//! Example:
+//!
+//! ```rust
+//! # fn main() -> Result<(), strkey::Error> {
+//! let key = strkey::to_vec(&("account", 1234u32, "a long description"))?;
+//!
+//! assert_eq!(strkey::truncate_display(&key, 20), "account:000004d2:…");
+//! # Ok(())
+//! # }
+//! ```
+
+const ELLIPSIS: char = '…';
+
+const CANDIDATES: [&str; 8] = [":", "/", "\0", "|", "#", ";", ",", "."];
+
+/// Returns the key as text shortened to at most `max_len` characters.
+///
+/// The key is cut at a component boundary and ends with an ellipsis after
+/// the last deliminator kept. If the first component alone is too long, it
+/// is cut within the component, but never between the two digits of a
+/// hexadecimal byte. Bytes that are not valid UTF-8 are replaced with
+/// U+FFFD.
+pub fn truncate_display(key: &[u8], max_len: usize) -> String {
+    let text = String::from_utf8_lossy(key);
+
+    if text.chars().count() <= max_len {
+        return text.into_owned();
+    }
+
+    if max_len == 0 {
+        return String::new();
+    }
+
+    let budget = max_len - 1;
+    let mut output = String::new();
+    let mut output_len = 0;
+
+    for component in text.split(':') {
+        let component_len = component.chars().count() + 1;
+
+        if output_len + component_len > budget {
+            break;
+        }
+
+        output.push_str(component);
+        output.push(':');
+        output_len += component_len;
+    }
+
+    if output.is_empty() {
+        let first = text.split(':').next().unwrap_or_default();
+        let mut keep = budget;
+
+        if is_hex(first) && keep % 2 == 1 {
+            keep -= 1;
+        }
+
+        output.extend(first.chars().take(keep));
+    }
+
+    output.push(ELLIPSIS);
+    output
+}
+
+/// Guesses the deliminator used by a sample of keys.
+///
+/// This is a best-effort heuristic for inspecting keys of an unknown
+/// configuration. Each common deliminator is scored by the fraction of keys
+/// containing it and by how consistently it appears the same number of
+/// times per key, away from the start and end of the key. Returns `None` if
+/// no candidate appears in at least half of the keys.
+///
+/// Example:
+///
+/// ```rust
+/// let keys = [&b"user/0001/profile"[..], b"user/0002/profile", b"user/0003"];
+///
+/// assert_eq!(strkey::sniff_deliminator(&keys), Some("/"));
+/// ```
+pub fn sniff_deliminator<I, K>(keys: I) -> Option<&'static str>
+where
+    I: IntoIterator<Item = K>,
+    K: AsRef<[u8]>,
+{
+    let keys = keys.into_iter().collect::<Vec<_>>();
+
+    if keys.is_empty() {
+        return None;
+    }
+
+    let mut best = None;
+    let mut best_score = 0.0;
+
+    for candidate in CANDIDATES {
+        let counts = keys
+            .iter()
+            .map(|key| count_interior(key.as_ref(), candidate.as_bytes()))
+            .collect::<Vec<_>>();
+        let present = counts.iter().filter(|&&count| count > 0).count();
+
+        if present * 2 < keys.len() {
+            continue;
+        }
+
+        let mode = (1..=counts.iter().copied().max().unwrap_or_default())
+            .max_by_key(|&count| counts.iter().filter(|&&other| other == count).count())
+            .unwrap_or_default();
+        let consistent = counts.iter().filter(|&&count| count == mode).count();
+        let score = (present + consistent) as f64 / keys.len() as f64;
+
+        if score > best_score {
+            best = Some(candidate);
+            best_score = score;
+        }
+    }
+
+    best
+}
+
+/// Counts the occurrences not at the start or end of the key.
+///
+/// Returns zero if the key is empty or starts or ends with the needle.
+fn count_interior(key: &[u8], needle: &[u8]) -> usize {
+    if key.starts_with(needle) || key.ends_with(needle) {
+        return 0;
+    }
+
+    crate::de::split_components(key, needle)
+        .count()
+        .saturating_sub(1)
+}
+
+fn is_hex(text: &str) -> bool {
+    !text.is_empty() && text.len() & 1 == 0 && text.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_deliminator() {
+        assert_eq!(sniff_deliminator([b"a:01:02", b"b:03:04"]), Some(":"));
+        assert_eq!(sniff_deliminator([b"a\0b", b"c\0d"]), Some("\0"));
+        assert_eq!(
+            sniff_deliminator(["2024.01/x:1", "2024.02/y:2", "2024.03/z"]),
+            Some("/")
+        );
+        assert_eq!(sniff_deliminator(["/a", "/b"]), None);
+        assert_eq!(sniff_deliminator(["abc", "def"]), None);
+        assert_eq!(sniff_deliminator(Vec::<&str>::new()), None);
+        assert_eq!(sniff_deliminator([b"" as &[u8], b"a:b"]), Some(":"));
+        assert_eq!(sniff_deliminator([b""]), None);
+    }
+
+    #[test]
+    fn test_truncate_display() {
+        assert_eq!(truncate_display(b"abc:01", 6), "abc:01");
+        assert_eq!(truncate_display(b"abc:01:def", 8), "abc:01:…");
+        assert_eq!(truncate_display(b"abc:01:def", 7), "abc:…");
+        assert_eq!(truncate_display(b"ghijkl:01", 4), "ghi…");
+        assert_eq!(truncate_display(b"0011223344:01", 6), "0011…");
+        assert_eq!(truncate_display(b"0011223344:01", 5), "0011…");
+        assert_eq!(truncate_display(b"abc\xff:01", 6), "abc\u{fffd}:…");
+        assert_eq!(truncate_display(b"abc", 0), "");
+        assert_eq!(truncate_display(b"abc", 1), "…");
+    }
+}
@@ -0,0 +1,127 @@
+//! Key-value dump files
+//!
+//! A dump file stores one entry per line as `key=value` where the key is an
+//! encoded key and the value is hexadecimal. The format is intended for
+//! snapshots and exports of entire key spaces. Keys may contain `=` but not
+//! newlines.
+//!
+//! Example:
+//!
+//! ```rust
+//! # fn main() -> Result<(), strkey::Error> {
+//! let key = strkey::to_vec(&("user", 1u8))?;
+//! let mut output = Vec::new();
+//! strkey::dump::write_entries(&mut output, vec![(&key, b"hi")])?;
+//!
+//! assert_eq!(output, b"user:01=6869\n");
+//!
+//! let entries = strkey::dump::read_entries(output.as_slice())
+//!     .collect::<Result<Vec<_>, _>>()?;
+//!
+//! assert_eq!(entries, vec![(key, b"hi".to_vec())]);
+//! # Ok(())
+//! # }
+//! ```
+use std::io::{BufRead, Write};
+
+use crate::error::Error;
+
+/// Writes key-value entries, one per line.
+///
+/// Returns an error if a key contains a newline.
+pub fn write_entries<W, I, K, V>(mut writer: W, entries: I) -> Result<(), Error>
+where
+    W: Write,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+{
+    for (key, value) in entries {
+        let key = key.as_ref();
+
+        if key.contains(&b'\n') {
+            return Err(Error::Data(String::from_utf8_lossy(key).into_owned()));
+        }
+
+        writer.write_all(key)?;
+        writer.write_all(b"=")?;
+        writer.write_all(hex::encode(value).as_bytes())?;
+        writer.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+/// Returns an iterator of the key-value entries in the reader.
+pub fn read_entries<R: BufRead>(reader: R) -> EntryIter<R> {
+    EntryIter {
+        reader,
+        buffer: Vec::new(),
+    }
+}
+
+/// Iterator over key-value entries returned by [`read_entries`].
+pub struct EntryIter<R> {
+    reader: R,
+    buffer: Vec<u8>,
+}
+
+impl<R: BufRead> EntryIter<R> {
+    fn parse_line(&self) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let line = self.buffer.strip_suffix(b"\n").unwrap_or(&self.buffer);
+        let index = line
+            .iter()
+            .rposition(|&byte| byte == b'=')
+            .ok_or(Error::Syntax)?;
+        let value =
+            hex::decode(&line[index + 1..]).map_err(|error| Error::Data(format!("{}", error)))?;
+
+        Ok((line[..index].to_vec(), value))
+    }
+}
+
+impl<R: BufRead> Iterator for EntryIter<R> {
+    type Item = Result<(Vec<u8>, Vec<u8>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.clear();
+
+        match self.reader.read_until(b'\n', &mut self.buffer) {
+            Ok(0) => None,
+            Ok(_) => Some(self.parse_line()),
+            Err(error) => Some(Err(error.into())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let entries = vec![
+            (b"a=b:01".to_vec(), b"\x00\xff".to_vec()),
+            (b"c".to_vec(), Vec::new()),
+        ];
+        let mut output = Vec::new();
+        write_entries(&mut output, entries.clone()).unwrap();
+
+        assert_eq!(output, b"a=b:01=00ff\nc=\n");
+        assert_eq!(
+            read_entries(output.as_slice())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap(),
+            entries
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(write_entries(Vec::new(), vec![(b"a\nb", b"")]).is_err());
+
+        let results = read_entries(b"a\nb=x\n".as_ref()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result.is_err()));
+    }
+}
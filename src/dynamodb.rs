@@ -0,0 +1,538 @@
+//! DynamoDB-style composite keys
+//!
+//! Stores such as DynamoDB identify items by a partition key and a sort key.
+//! The functions in this module serialize a struct into both keys by splitting
+//! the struct's fields at a designated field: fields before it form the
+//! partition key and the designated field and the fields after it form the
+//! sort key.
+//!
+//! Example:
+//!
+//! ```rust
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct OrderKey {
+//!     tenant: &'static str,
+//!     customer: u32,
+//!     year: u16,
+//!     order_id: u32,
+//! }
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let key = strkey::dynamodb::to_composite_key(
+//!     &OrderKey {
+//!         tenant: "acme",
+//!         customer: 1234,
+//!         year: 2021,
+//!         order_id: 5,
+//!     },
+//!     "year",
+//! )?;
+//!
+//! assert_eq!(key.partition_key, "acme:000004d2");
+//! assert_eq!(key.sort_key, "07e5:00000005");
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    cell::Cell,
+    collections::{HashMap, VecDeque},
+};
+
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed, SeqAccess, Visitor},
+    ser::{Impossible, SerializeStruct},
+    Serialize,
+};
+
+use crate::{
+    de::{sealed, split_components, Component, ComponentRead, Deserializer},
+    error::Error,
+    ser::Serializer,
+};
+
+/// Partition key and sort key pair.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompositeKey {
+    /// The partition (hash) key.
+    pub partition_key: String,
+
+    /// The sort (range) key.
+    pub sort_key: String,
+}
+
+impl CompositeKey {
+    /// Returns a key condition that matches items with the same partition key
+    /// and a sort key that begins with this sort key.
+    ///
+    /// The given names are the attribute names of the partition key and sort
+    /// key in the table. They are referenced through expression attribute names
+    /// so reserved words can be used.
+    ///
+    /// To query a prefix of the sort key, serialize a struct containing only
+    /// the leading fields of the full key.
+    pub fn begins_with_condition(
+        &self,
+        partition_key_name: &str,
+        sort_key_name: &str,
+    ) -> KeyCondition {
+        let mut attribute_names = HashMap::new();
+        attribute_names.insert("#pk".to_string(), partition_key_name.to_string());
+        attribute_names.insert("#sk".to_string(), sort_key_name.to_string());
+
+        let mut attribute_values = HashMap::new();
+        attribute_values.insert(":pk".to_string(), self.partition_key.clone());
+        attribute_values.insert(":sk".to_string(), self.sort_key.clone());
+
+        KeyCondition {
+            expression: "#pk = :pk AND begins_with(#sk, :sk)".to_string(),
+            attribute_names,
+            attribute_values,
+        }
+    }
+}
+
+/// Key condition expression for a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCondition {
+    /// The key condition expression.
+    pub expression: String,
+
+    /// The expression attribute names (placeholder to attribute name).
+    pub attribute_names: HashMap<String, String>,
+
+    /// The expression attribute values as strings (placeholder to value).
+    pub attribute_values: HashMap<String, String>,
+}
+
+/// Serializes a struct into a partition key and a sort key.
+///
+/// The sort key begins at the field named `sort_key_field`. Returns an error
+/// if the value is not a struct or the field does not exist.
+pub fn to_composite_key<T>(value: &T, sort_key_field: &str) -> Result<CompositeKey, Error>
+where
+    T: ?Sized + Serialize,
+{
+    let mut partition_key = Vec::new();
+    let mut sort_key = Vec::new();
+
+    let mut serializer = CompositeSerializer {
+        partition_key: Serializer::new(&mut partition_key),
+        sort_key: Serializer::new(&mut sort_key),
+        sort_key_field,
+        in_sort_key: false,
+    };
+    value.serialize(&mut serializer)?;
+
+    if !serializer.in_sort_key {
+        return Err(Error::Other(format!(
+            "sort key field {} not found",
+            sort_key_field
+        )));
+    }
+
+    Ok(CompositeKey {
+        partition_key: String::from_utf8(partition_key).map_err(|error| error.utf8_error())?,
+        sort_key: String::from_utf8(sort_key).map_err(|error| error.utf8_error())?,
+    })
+}
+
+/// Deserializes a struct from a partition key and a sort key.
+///
+/// Like [`to_composite_key`], the fields before the field named
+/// `sort_key_field` are decoded from the partition key and the rest from the
+/// sort key. Each key must contain exactly the components of its fields, so
+/// an empty string field at either end of a key round-trips.
+pub fn from_composite_key<T>(key: &CompositeKey, sort_key_field: &str) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    let partition_remaining = Cell::new(0);
+    let sort_remaining = Cell::new(0);
+
+    let mut deserializer = CompositeDeserializer {
+        partition_key: Deserializer::new(PartReader::new(&key.partition_key, &partition_remaining)),
+        sort_key: Deserializer::new(PartReader::new(&key.sort_key, &sort_remaining)),
+        sort_key_field,
+        in_sort_key: false,
+    };
+    let value = T::deserialize(&mut deserializer)?;
+
+    if !deserializer.in_sort_key {
+        return Err(Error::Other(format!(
+            "sort key field {} not found",
+            sort_key_field
+        )));
+    }
+
+    if partition_remaining.get() != 0 || sort_remaining.get() != 0 {
+        return Err(Error::Syntax);
+    }
+
+    Ok(value)
+}
+
+struct CompositeSerializer<'a> {
+    partition_key: Serializer<&'a mut Vec<u8>>,
+    sort_key: Serializer<&'a mut Vec<u8>>,
+    sort_key_field: &'a str,
+    in_sort_key: bool,
+}
+
+macro_rules! unsupported {
+    ($($name:ident($($arg:ty),*) -> $ret:ty;)*) => {
+        $(
+            fn $name(self, $(_: $arg),*) -> Result<$ret, Self::Error> {
+                Err(Error::UnsupportedType)
+            }
+        )*
+    };
+}
+
+impl<'a, 'b> serde::Serializer for &'b mut CompositeSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    unsupported! {
+        serialize_bool(bool) -> ();
+        serialize_i8(i8) -> ();
+        serialize_i16(i16) -> ();
+        serialize_i32(i32) -> ();
+        serialize_i64(i64) -> ();
+        serialize_u8(u8) -> ();
+        serialize_u16(u16) -> ();
+        serialize_u32(u32) -> ();
+        serialize_u64(u64) -> ();
+        serialize_f32(f32) -> ();
+        serialize_f64(f64) -> ();
+        serialize_char(char) -> ();
+        serialize_str(&str) -> ();
+        serialize_bytes(&[u8]) -> ();
+        serialize_none() -> ();
+        serialize_unit() -> ();
+        serialize_unit_struct(&'static str) -> ();
+        serialize_unit_variant(&'static str, u32, &'static str) -> ();
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::UnsupportedType)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::UnsupportedType)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(self)
+    }
+}
+
+impl<'a, 'b> SerializeStruct for &'b mut CompositeSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        if key == self.sort_key_field {
+            self.in_sort_key = true;
+        }
+
+        if self.in_sort_key {
+            value.serialize(&mut self.sort_key)
+        } else {
+            value.serialize(&mut self.partition_key)
+        }
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+}
+
+/// Component reader for one part of a composite key.
+///
+/// An empty part is either no components or a single empty component, so
+/// the empty component is returned if requested but not required.
+struct PartReader<'de> {
+    deliminator: &'de [u8],
+    components: VecDeque<&'de str>,
+    optional_empty: bool,
+    remaining: &'de Cell<usize>,
+}
+
+impl<'de> PartReader<'de> {
+    fn new(input: &'de str, remaining: &'de Cell<usize>) -> Self {
+        let components = split_components(input.as_bytes(), b":")
+            .map(|component| std::str::from_utf8(component).unwrap())
+            .collect::<VecDeque<_>>();
+        remaining.set(components.len());
+
+        Self {
+            deliminator: b":",
+            components,
+            optional_empty: input.is_empty(),
+            remaining,
+        }
+    }
+}
+
+impl<'de> sealed::Sealed for PartReader<'de> {}
+
+impl<'de> ComponentRead<'de> for PartReader<'de> {
+    fn deliminator(&self) -> &'de [u8] {
+        self.deliminator
+    }
+
+    fn set_deliminator(&mut self, deliminator: &'de [u8]) {
+        self.deliminator = deliminator;
+    }
+
+    fn preload_components(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn next_component(&mut self) -> Result<Option<Component<'de>>, Error> {
+        if let Some(component) = self.components.pop_front() {
+            self.remaining.set(self.components.len());
+            return Ok(Some(Component::Borrowed(component)));
+        }
+
+        if self.optional_empty {
+            self.optional_empty = false;
+            return Ok(Some(Component::Borrowed("")));
+        }
+
+        Ok(None)
+    }
+
+    fn has_next_component(&mut self) -> Result<bool, Error> {
+        Ok(!self.components.is_empty())
+    }
+}
+
+struct CompositeDeserializer<'de, 'a> {
+    partition_key: Deserializer<'de, PartReader<'de>>,
+    sort_key: Deserializer<'de, PartReader<'de>>,
+    sort_key_field: &'a str,
+    in_sort_key: bool,
+}
+
+impl<'de, 'a, 'b> serde::Deserializer<'de> for &'b mut CompositeDeserializer<'de, 'a> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::UnsupportedType)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(CompositeFields {
+            deserializer: self,
+            fields: fields.iter(),
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct CompositeFields<'de, 'a, 'b> {
+    deserializer: &'b mut CompositeDeserializer<'de, 'a>,
+    fields: std::slice::Iter<'static, &'static str>,
+}
+
+impl<'de, 'a, 'b> SeqAccess<'de> for CompositeFields<'de, 'a, 'b> {
+    type Error = Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let field = match self.fields.next() {
+            Some(field) => field,
+            None => return Ok(None),
+        };
+
+        if *field == self.deserializer.sort_key_field {
+            self.deserializer.in_sort_key = true;
+        }
+
+        if self.deserializer.in_sort_key {
+            seed.deserialize(&mut self.deserializer.sort_key).map(Some)
+        } else {
+            seed.deserialize(&mut self.deserializer.partition_key)
+                .map(Some)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct MyKey {
+        tenant: String,
+        user_id: u16,
+        kind: String,
+        item_id: u8,
+    }
+
+    fn my_key() -> MyKey {
+        MyKey {
+            tenant: "acme".to_string(),
+            user_id: 1,
+            kind: "order".to_string(),
+            item_id: 2,
+        }
+    }
+
+    #[test]
+    fn test_to_composite_key() {
+        let key = to_composite_key(&my_key(), "kind").unwrap();
+
+        assert_eq!(key.partition_key, "acme:0001");
+        assert_eq!(key.sort_key, "order:02");
+
+        let key = to_composite_key(&my_key(), "tenant").unwrap();
+
+        assert_eq!(key.partition_key, "");
+        assert_eq!(key.sort_key, "acme:0001:order:02");
+    }
+
+    #[test]
+    fn test_to_composite_key_errors() {
+        assert!(to_composite_key(&my_key(), "missing").is_err());
+        assert!(to_composite_key(&("a", 1u8), "a").is_err());
+    }
+
+    #[test]
+    fn test_from_composite_key() {
+        let key = to_composite_key(&my_key(), "kind").unwrap();
+        let value = from_composite_key::<MyKey>(&key, "kind").unwrap();
+
+        assert_eq!(value, my_key());
+        assert!(from_composite_key::<MyKey>(&key, "user_id").is_err());
+        assert!(from_composite_key::<MyKey>(&key, "missing").is_err());
+
+        let key = to_composite_key(&my_key(), "tenant").unwrap();
+        assert_eq!(
+            from_composite_key::<MyKey>(&key, "tenant").unwrap(),
+            my_key()
+        );
+    }
+
+    #[test]
+    fn test_from_composite_key_empty_strings() {
+        #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+        struct PairKey {
+            partition: String,
+            sort: String,
+        }
+
+        for (partition, sort) in [("", "b"), ("a", ""), ("", "")] {
+            let value = PairKey {
+                partition: partition.to_string(),
+                sort: sort.to_string(),
+            };
+            let key = to_composite_key(&value, "sort").unwrap();
+
+            assert_eq!(key.partition_key, partition);
+            assert_eq!(key.sort_key, sort);
+            assert_eq!(from_composite_key::<PairKey>(&key, "sort").unwrap(), value);
+        }
+
+        let value = MyKey {
+            tenant: String::new(),
+            ..my_key()
+        };
+        let key = to_composite_key(&value, "user_id").unwrap();
+
+        assert_eq!(key.partition_key, "");
+        assert_eq!(from_composite_key::<MyKey>(&key, "user_id").unwrap(), value);
+    }
+
+    #[test]
+    fn test_begins_with_condition() {
+        let key = CompositeKey {
+            partition_key: "acme:0001".to_string(),
+            sort_key: "order".to_string(),
+        };
+        let condition = key.begins_with_condition("PK", "SK");
+
+        assert_eq!(condition.expression, "#pk = :pk AND begins_with(#sk, :sk)");
+        assert_eq!(condition.attribute_names["#pk"], "PK");
+        assert_eq!(condition.attribute_names["#sk"], "SK");
+        assert_eq!(condition.attribute_values[":pk"], "acme:0001");
+        assert_eq!(condition.attribute_values[":sk"], "order");
+    }
+}
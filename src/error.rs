@@ -2,6 +2,8 @@
 
 use thiserror::Error as ThisError;
 
+use crate::config::Encoding;
+
 /// Result alias
 pub type Result<T> = std::result::Result<T, self::Error>;
 
@@ -20,6 +22,25 @@ pub enum Error {
     #[error("UTF-8 decoding error: {0}")]
     Utf8StringDecode(#[from] std::str::Utf8Error),
 
+    /// Error decoding a component as UTF-8.
+    ///
+    /// Contains the index of the component and the decoding error.
+    #[error("UTF-8 decoding error on component {0}: {1}")]
+    ComponentUtf8(usize, #[source] std::str::Utf8Error),
+
+    /// Error decoding a component as hexadecimal.
+    ///
+    /// Contains the index of the component and the decoding error.
+    #[error("Hex decoding error on component {0}: {1}")]
+    Hex(usize, #[source] HexError),
+
+    /// A fixed-width component has the wrong width.
+    ///
+    /// Contains the index of the component, the width in bytes, and the
+    /// expected width in bytes.
+    #[error("Component {0} has width {1} instead of {2}")]
+    WrongWidth(usize, usize, usize),
+
     /// Error decoding a component.
     ///
     /// This occurs when the encoded values do not match the given type, for
@@ -34,15 +55,108 @@ pub enum Error {
     #[error("Encoding syntax error")]
     Syntax,
 
+    /// The encoded key is longer than the allowed maximum.
+    ///
+    /// Contains the length of the key and the maximum length.
+    #[error("Key length {0} exceeds maximum length {1}")]
+    KeyTooLong(usize, usize),
+
+    /// The encoded key is shorter than the required length.
+    ///
+    /// Contains the length of the key and the required length.
+    #[error("Key length {0} is shorter than required length {1}")]
+    KeyTooShort(usize, usize),
+
+    /// Nested types exceeded the deserializer's recursion limit.
+    ///
+    /// Contains the limit.
+    #[error("Recursion limit {0} exceeded")]
+    RecursionLimitExceeded(usize),
+
+    /// None of the alternative layouts matched the key.
+    ///
+    /// Contains the error of each layout in the order they were tried.
+    #[error("No layout matched: {}", .0.iter().map(|error| error.to_string()).collect::<Vec<_>>().join("; "))]
+    NoLayoutMatched(Vec<Error>),
+
+    /// The key belongs to a different tenant than the key space.
+    ///
+    /// Contains the tenant ID of the key space.
+    #[error("Key does not belong to tenant {0}")]
+    TenantMismatch(String),
+
+    /// Invalid combination of serializer options.
+    #[error("Configuration error: {0}")]
+    Config(#[from] ConfigError),
+
     /// Standard IO error.
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Error from the bytekey encoding.
+    #[cfg(feature = "bytekey")]
+    #[error("Bytekey error: {0}")]
+    Bytekey(#[from] bytekey::Error),
+
+    /// Error from the CSV encoding.
+    #[cfg(feature = "csv")]
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    /// Error from the JSON encoding.
+    #[cfg(feature = "serde_json")]
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
     /// Some other Serde error.
     #[error("Other error: {0}")]
     Other(String),
 }
 
+/// Invalid combination of serializer options.
+///
+/// These combinations let the deliminator occur within a component, so keys
+/// would be split incorrectly on decode.
+#[derive(Debug, Clone, PartialEq, Eq, ThisError)]
+pub enum ConfigError {
+    /// The deliminator is empty.
+    #[error("deliminator is empty")]
+    EmptyDeliminator,
+
+    /// The deliminator can occur in components with the given encoding.
+    #[error("deliminator can occur in {0:?} encoded components")]
+    EncodingCollision(Encoding),
+
+    /// The deliminator can occur in `true` or `false`.
+    #[error("deliminator can occur in boolean components")]
+    BoolCollision,
+
+    /// The deliminator can occur in percent-encoded strings.
+    #[error("deliminator can occur in percent-encoded strings")]
+    EscapeCollision,
+
+    /// Percent-encoding is enabled with strict ordering, but escaped strings
+    /// do not sort in the order of their values.
+    #[error("percent-encoding does not preserve ordering")]
+    UnorderedEscapes,
+}
+
+/// Error decoding hexadecimal digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ThisError)]
+pub enum HexError {
+    /// The byte at the index is not a hexadecimal digit.
+    #[error("invalid digit at index {0}")]
+    InvalidDigit(usize),
+
+    /// The number of digits is odd.
+    #[error("odd number of digits")]
+    OddLength,
+
+    /// The number of digits does not match the output length.
+    #[error("invalid length")]
+    InvalidLength,
+}
+
 impl serde::ser::Error for Error {
     fn custom<T>(msg: T) -> Self
     where
@@ -0,0 +1,136 @@
+//! Expiry index keys
+//!
+//! An expiry index stores a key for each record laid out as
+//! `expires_at:primary_key` where `expires_at` is a timestamp in milliseconds
+//! since the Unix epoch. A sweep job scans the index up to the current time,
+//! deletes the records, and removes the index keys.
+//!
+//! Example:
+//!
+//! ```rust
+//! use std::collections::BTreeSet;
+//! use strkey::expiry::{self, ExpiryKey};
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let mut index = BTreeSet::new();
+//! index.insert(strkey::to_vec(&("ttl", ExpiryKey::new(1000, ("session", 1u8))))?);
+//! index.insert(strkey::to_vec(&("ttl", ExpiryKey::new(5000, ("session", 2u8))))?);
+//!
+//! let expired = index
+//!     .range(expiry::sweep_range(&"ttl", 2000)?)
+//!     .map(|key| strkey::from_slice::<(&str, ExpiryKey<(&str, u8)>)>(key).map(|k| k.1.key))
+//!     .collect::<Result<Vec<_>, _>>()?;
+//!
+//! assert_eq!(expired, vec![("session", 1)]);
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    ops::Bound,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::{error::Error, range::ByteRange};
+
+/// Key of an expiry index entry.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExpiryKey<K> {
+    /// The expiry time in milliseconds since the Unix epoch.
+    pub expires_at: u64,
+
+    /// The primary key of the record.
+    pub key: K,
+}
+
+impl_struct_serde!(ExpiryKey<K> {
+    expires_at: u64,
+    key: K,
+});
+
+impl<K> ExpiryKey<K> {
+    /// Construct a key for a record expiring at the given time in milliseconds.
+    pub fn new(expires_at: u64, key: K) -> Self {
+        Self { expires_at, key }
+    }
+
+    /// Construct a key for a record expiring at the given system time.
+    ///
+    /// Times before the Unix epoch are clamped to the epoch.
+    pub fn from_system_time(expires_at: SystemTime, key: K) -> Self {
+        let millis = expires_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+
+        Self::new(millis.min(u64::MAX as u128) as u64, key)
+    }
+
+    /// Unwrap and return the primary key.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+}
+
+/// Returns the encoded key range of index entries expiring at or before the given time.
+///
+/// `prefix` is the namespace of the index which precedes each [`ExpiryKey`].
+/// Use `&()` if the index has no namespace.
+pub fn sweep_range<P>(prefix: &P, now: u64) -> Result<ByteRange, Error>
+where
+    P: ?Sized + Serialize,
+{
+    let start = crate::to_vec(&(prefix, 0u64))?;
+    let end = crate::to_prefix(&(prefix, now))?;
+
+    let end = match crate::range::prefix_successor(&end) {
+        Some(end) => Bound::Excluded(end),
+        None => Bound::Unbounded,
+    };
+
+    Ok((Bound::Included(start), end))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeBounds;
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let key = ExpiryKey::new(0x10, ("user", 5u8));
+        let encoded = crate::to_vec(&key).unwrap();
+
+        assert_eq!(&encoded, b"0000000000000010:user:05");
+
+        let decoded = crate::from_slice::<ExpiryKey<(String, u8)>>(&encoded).unwrap();
+        assert_eq!(decoded.into_key(), ("user".to_string(), 5));
+    }
+
+    #[test]
+    fn test_sweep_range() {
+        let range = sweep_range(&(), 100).unwrap();
+
+        for (expires_at, expected) in [(0, true), (99, true), (100, true), (101, false)].iter() {
+            let key = crate::to_vec(&ExpiryKey::new(*expires_at, "zzz")).unwrap();
+            assert_eq!(range.contains(&key), *expected);
+        }
+
+        let range = sweep_range(&(), u64::MAX).unwrap();
+        let key = crate::to_vec(&ExpiryKey::new(u64::MAX, "zzz")).unwrap();
+        assert!(range.contains(&key));
+    }
+
+    #[test]
+    fn test_sweep_range_prefix() {
+        let range = sweep_range("ttl", 100).unwrap();
+
+        let key = crate::to_vec(&("ttl", ExpiryKey::new(50, 1u8))).unwrap();
+        assert!(range.contains(&key));
+
+        let key = crate::to_vec(&("tt", ExpiryKey::new(50, 1u8))).unwrap();
+        assert!(!range.contains(&key));
+    }
+}
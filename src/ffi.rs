@@ -0,0 +1,190 @@
+//! C bindings
+//!
+//! Functions with a stable C ABI so services written in other languages can
+//! produce the same keys. Schemas and values use the JSON format described in
+//! the [`crate::json`] module.
+//!
+//! To produce a shared library, build a crate that depends on this crate with
+//! the `ffi` feature and sets `crate-type = ["cdylib"]`.
+//!
+//! Both functions write a NUL-terminated string to the output buffer and
+//! return its length excluding the NUL terminator, like `snprintf`. If the
+//! return value is not less than the buffer length, nothing is written and
+//! the call should be repeated with a larger buffer. Negative return values
+//! are errors.
+//!
+//! ```c
+//! char key[64];
+//! intptr_t len = strkey_encode("[\"str\", \"u32\"]", "[\"account\", 1234]", key, sizeof(key));
+//! ```
+use std::{
+    ffi::CStr,
+    os::raw::{c_char, c_int},
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::error::Error;
+
+/// An argument was null or not valid UTF-8.
+pub const STRKEY_ERROR_ARGUMENT: isize = -1;
+
+/// The schema, values, or key could not be encoded or decoded.
+pub const STRKEY_ERROR_DATA: isize = -2;
+
+/// Encodes a JSON array of values into a key using a JSON schema.
+///
+/// # Safety
+///
+/// `schema` and `json_in` must be null or point to NUL-terminated strings.
+/// `buf_out` must be null or valid for writes of `buf_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn strkey_encode(
+    schema: *const c_char,
+    json_in: *const c_char,
+    buf_out: *mut c_char,
+    buf_len: usize,
+) -> isize {
+    call(schema, json_in, buf_out, buf_len, |schema, values| {
+        crate::json::encode_json_with_schema(values, schema)
+    })
+}
+
+/// Decodes a key into a JSON array of values using a JSON schema.
+///
+/// # Safety
+///
+/// `schema` and `key` must be null or point to NUL-terminated strings.
+/// `buf_out` must be null or valid for writes of `buf_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn strkey_decode(
+    schema: *const c_char,
+    key: *const c_char,
+    buf_out: *mut c_char,
+    buf_len: usize,
+) -> isize {
+    call(schema, key, buf_out, buf_len, |schema, key| {
+        crate::json::decode_to_json(key, schema)
+    })
+}
+
+/// Returns the version of the ABI.
+///
+/// The version is incremented when a function signature or the meaning of a
+/// return value changes.
+#[no_mangle]
+pub extern "C" fn strkey_abi_version() -> c_int {
+    1
+}
+
+unsafe fn call<F>(
+    schema: *const c_char,
+    input: *const c_char,
+    buf_out: *mut c_char,
+    buf_len: usize,
+    f: F,
+) -> isize
+where
+    F: FnOnce(&str, &str) -> Result<String, Error>,
+{
+    let (schema, input) = match (to_str(schema), to_str(input)) {
+        (Some(schema), Some(input)) => (schema, input),
+        _ => return STRKEY_ERROR_ARGUMENT,
+    };
+
+    let output = match panic::catch_unwind(AssertUnwindSafe(|| f(schema, input))) {
+        Ok(Ok(output)) => output,
+        _ => return STRKEY_ERROR_DATA,
+    };
+
+    if output.len() < buf_len && !buf_out.is_null() {
+        std::ptr::copy_nonoverlapping(output.as_ptr(), buf_out as *mut u8, output.len());
+        *buf_out.add(output.len()) = 0;
+    }
+
+    output.len() as isize
+}
+
+unsafe fn to_str<'a>(text: *const c_char) -> Option<&'a str> {
+    if text.is_null() {
+        None
+    } else {
+        CStr::from_ptr(text).to_str().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use super::*;
+
+    #[test]
+    fn test_encode_decode() {
+        let schema = CString::new(r#"["str", "u32"]"#).unwrap();
+        let values = CString::new(r#"["account",1234]"#).unwrap();
+        let mut buffer = [0 as c_char; 64];
+
+        let len = unsafe {
+            strkey_encode(
+                schema.as_ptr(),
+                values.as_ptr(),
+                buffer.as_mut_ptr(),
+                buffer.len(),
+            )
+        };
+        assert_eq!(len, 16);
+
+        let key = unsafe { CStr::from_ptr(buffer.as_ptr()) }.to_owned();
+        assert_eq!(key.to_str().unwrap(), "account:000004d2");
+
+        let len = unsafe {
+            strkey_decode(
+                schema.as_ptr(),
+                key.as_ptr(),
+                buffer.as_mut_ptr(),
+                buffer.len(),
+            )
+        };
+        assert_eq!(len, 16);
+
+        let output = unsafe { CStr::from_ptr(buffer.as_ptr()) };
+        assert_eq!(output, values.as_c_str());
+    }
+
+    #[test]
+    fn test_small_buffer() {
+        let schema = CString::new(r#"["u32"]"#).unwrap();
+        let values = CString::new("[1]").unwrap();
+        let mut buffer = [1 as c_char; 8];
+
+        let len = unsafe {
+            strkey_encode(
+                schema.as_ptr(),
+                values.as_ptr(),
+                buffer.as_mut_ptr(),
+                buffer.len(),
+            )
+        };
+        assert_eq!(len, 8);
+        assert_eq!(buffer, [1; 8]);
+
+        let len =
+            unsafe { strkey_encode(schema.as_ptr(), values.as_ptr(), std::ptr::null_mut(), 0) };
+        assert_eq!(len, 8);
+    }
+
+    #[test]
+    fn test_errors() {
+        let schema = CString::new(r#"["u8"]"#).unwrap();
+        let values = CString::new("[256]").unwrap();
+
+        let len =
+            unsafe { strkey_encode(std::ptr::null(), values.as_ptr(), std::ptr::null_mut(), 0) };
+        assert_eq!(len, STRKEY_ERROR_ARGUMENT);
+
+        let len =
+            unsafe { strkey_encode(schema.as_ptr(), values.as_ptr(), std::ptr::null_mut(), 0) };
+        assert_eq!(len, STRKEY_ERROR_DATA);
+        assert_eq!(strkey_abi_version(), 1);
+    }
+}
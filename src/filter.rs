@@ -0,0 +1,171 @@
+//! Prefix filters
+//!
+//! Helpers for building prefix bloom filters, such as RocksDB's prefix
+//! extractor, from encoded keys. The prefix consisting of the first N
+//! components is found by scanning for deliminators without decoding the
+//! key.
+//!
+//! Example:
+//!
+//! ```rust
+//! use std::collections::hash_map::DefaultHasher;
+//!
+//! use strkey::filter::PrefixExtractor;
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let extractor = PrefixExtractor::new(2);
+//! let key = strkey::to_vec(&("account", 1u8, "name"))?;
+//!
+//! assert_eq!(extractor.extract(&key), Some(&b"account:01"[..]));
+//! assert_eq!(extractor.extract(b"account"), None);
+//!
+//! let mut hasher = DefaultHasher::new();
+//! assert!(extractor.hash(&key, &mut hasher));
+//! # Ok(())
+//! # }
+//! ```
+use std::hash::Hasher;
+
+use crate::de::find_subslice;
+
+/// Extracts the prefix consisting of the first N components of a key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrefixExtractor {
+    components: usize,
+    deliminator: Vec<u8>,
+}
+
+impl PrefixExtractor {
+    /// Construct an extractor for the given number of components.
+    pub fn new(components: usize) -> Self {
+        Self {
+            components,
+            deliminator: b":".to_vec(),
+        }
+    }
+
+    /// Returns the number of components in the prefix.
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// Returns the deliminator.
+    pub fn deliminator(&self) -> &[u8] {
+        &self.deliminator
+    }
+
+    /// Sets the deliminator.
+    pub fn set_deliminator<D: Into<Vec<u8>>>(&mut self, deliminator: D) {
+        self.deliminator = deliminator.into();
+    }
+
+    /// Sets the deliminator and returns the extractor.
+    pub fn with_deliminator<D: Into<Vec<u8>>>(mut self, deliminator: D) -> Self {
+        self.set_deliminator(deliminator);
+        self
+    }
+
+    /// Returns the first N components of the key, without the following
+    /// deliminator.
+    ///
+    /// Returns `None` if the key has fewer than N components, which
+    /// corresponds to the key being outside the extractor's domain. Keys
+    /// with exactly N components are returned whole.
+    pub fn extract<'a>(&self, key: &'a [u8]) -> Option<&'a [u8]> {
+        component_prefix(key, self.components, &self.deliminator)
+    }
+
+    /// Returns whether the key is in the extractor's domain.
+    pub fn in_domain(&self, key: &[u8]) -> bool {
+        self.extract(key).is_some()
+    }
+
+    /// Feeds the prefix of the key to the hasher.
+    ///
+    /// Returns `false` without writing to the hasher if the key has fewer
+    /// than N components.
+    pub fn hash<H: Hasher>(&self, key: &[u8], hasher: &mut H) -> bool {
+        match self.extract(key) {
+            Some(prefix) => {
+                hasher.write(prefix);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Returns the first N components of the key using the given deliminator.
+///
+/// Returns `None` if the key has fewer than N components. A count of zero
+/// returns an empty slice.
+pub fn component_prefix<'a>(
+    key: &'a [u8],
+    components: usize,
+    deliminator: &[u8],
+) -> Option<&'a [u8]> {
+    if components == 0 {
+        return Some(&key[..0]);
+    }
+
+    if key.is_empty() || deliminator.is_empty() {
+        return if components == 1 && !key.is_empty() {
+            Some(key)
+        } else {
+            None
+        };
+    }
+
+    let mut end = 0;
+
+    for count in 1..=components {
+        match find_subslice(&key[end..], deliminator) {
+            Some(index) if count < components => end += index + deliminator.len(),
+            Some(index) => return Some(&key[..end + index]),
+            None if count == components => return Some(key),
+            None => return None,
+        }
+    }
+
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    #[test]
+    fn test_component_prefix() {
+        assert_eq!(component_prefix(b"a:b:c", 0, b":"), Some(&b""[..]));
+        assert_eq!(component_prefix(b"a:b:c", 1, b":"), Some(&b"a"[..]));
+        assert_eq!(component_prefix(b"a:b:c", 2, b":"), Some(&b"a:b"[..]));
+        assert_eq!(component_prefix(b"a:b:c", 3, b":"), Some(&b"a:b:c"[..]));
+        assert_eq!(component_prefix(b"a:b:c", 4, b":"), None);
+        assert_eq!(component_prefix(b"a::c", 2, b":"), Some(&b"a:"[..]));
+        assert_eq!(component_prefix(b"a||b||c", 2, b"||"), Some(&b"a||b"[..]));
+        assert_eq!(component_prefix(b"", 1, b":"), None);
+        assert_eq!(component_prefix(b"a:b", 1, b""), Some(&b"a:b"[..]));
+    }
+
+    #[test]
+    fn test_hash() {
+        let extractor = PrefixExtractor::new(1).with_deliminator(&b"\0"[..]);
+        let key1 = crate::config::Config::strict()
+            .to_vec(&("account", 1u8))
+            .unwrap();
+        let key2 = crate::config::Config::strict()
+            .to_vec(&("account", 2u8))
+            .unwrap();
+
+        let mut hasher1 = DefaultHasher::new();
+        let mut hasher2 = DefaultHasher::new();
+
+        assert!(extractor.hash(&key1, &mut hasher1));
+        assert!(extractor.hash(&key2, &mut hasher2));
+        assert_eq!(hasher1.finish(), hasher2.finish());
+        assert!(!PrefixExtractor::new(3).hash(&key1, &mut hasher1));
+        assert!(!PrefixExtractor::new(3).in_domain(&key1));
+    }
+}
@@ -0,0 +1,210 @@
+//! Graph edge keys
+//!
+//! Each edge of a graph is stored as two keys so that both the outgoing and
+//! the incoming edges of a node can be found with a prefix scan:
+//!
+//! * The forward key `src:out:edge_type:dst`.
+//! * The reverse key `dst:in:edge_type:src`.
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::graph::Edge;
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let edge = Edge::new("alice", "follows", "bob");
+//! let (forward, reverse) = edge.keys()?;
+//!
+//! assert_eq!(&forward, b"alice:out:follows:bob");
+//! assert_eq!(&reverse, b"bob:in:follows:alice");
+//! assert!(forward.starts_with(&strkey::graph::outgoing_prefix(&"alice")?));
+//!
+//! let decoded = Edge::<String, String>::from_key(&reverse)?;
+//! assert_eq!(decoded.src, "alice");
+//! assert_eq!(decoded.dst, "bob");
+//! # Ok(())
+//! # }
+//! ```
+use serde::{
+    de::{DeserializeOwned, EnumAccess, VariantAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{error::Error, serde_impl::VariantSeed};
+
+/// Direction of an edge key relative to its leading node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    /// The leading node is the source of the edge.
+    Out,
+
+    /// The leading node is the destination of the edge.
+    In,
+}
+
+impl Direction {
+    const VARIANTS: &'static [&'static str] = &["out", "in"];
+}
+
+impl Serialize for Direction {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let index = *self as u32;
+
+        serializer.serialize_unit_variant("Direction", index, Self::VARIANTS[index as usize])
+    }
+}
+
+impl<'de> Deserialize<'de> for Direction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct DirectionVisitor;
+
+        impl<'de> Visitor<'de> for DirectionVisitor {
+            type Value = Direction;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("enum Direction")
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: EnumAccess<'de>,
+            {
+                let (index, variant) = data.variant_seed(VariantSeed(Direction::VARIANTS))?;
+                variant.unit_variant()?;
+
+                Ok(if index == 0 {
+                    Direction::Out
+                } else {
+                    Direction::In
+                })
+            }
+        }
+
+        deserializer.deserialize_enum("Direction", Self::VARIANTS, DirectionVisitor)
+    }
+}
+
+/// Directed edge between two nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edge<N, E> {
+    /// The source node.
+    pub src: N,
+
+    /// The type of the edge.
+    pub edge_type: E,
+
+    /// The destination node.
+    pub dst: N,
+}
+
+impl<N, E> Edge<N, E> {
+    /// Construct an edge.
+    pub fn new(src: N, edge_type: E, dst: N) -> Self {
+        Self {
+            src,
+            edge_type,
+            dst,
+        }
+    }
+}
+
+impl<N: Serialize, E: Serialize> Edge<N, E> {
+    /// Returns the encoded forward key.
+    pub fn forward_key(&self) -> Result<Vec<u8>, Error> {
+        crate::to_vec(&(&self.src, Direction::Out, &self.edge_type, &self.dst))
+    }
+
+    /// Returns the encoded reverse key.
+    pub fn reverse_key(&self) -> Result<Vec<u8>, Error> {
+        crate::to_vec(&(&self.dst, Direction::In, &self.edge_type, &self.src))
+    }
+
+    /// Returns the encoded forward and reverse keys.
+    pub fn keys(&self) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        Ok((self.forward_key()?, self.reverse_key()?))
+    }
+}
+
+impl<N: DeserializeOwned, E: DeserializeOwned> Edge<N, E> {
+    /// Decodes an edge from either its forward or reverse key.
+    pub fn from_key(key: &[u8]) -> Result<Self, Error> {
+        let (node, direction, edge_type, other) = crate::from_slice::<(N, Direction, E, N)>(key)?;
+
+        Ok(match direction {
+            Direction::Out => Self::new(node, edge_type, other),
+            Direction::In => Self::new(other, edge_type, node),
+        })
+    }
+}
+
+/// Returns the encoded prefix of the forward keys of all edges from the node.
+pub fn outgoing_prefix<N: ?Sized + Serialize>(src: &N) -> Result<Vec<u8>, Error> {
+    crate::to_prefix(&(src, Direction::Out))
+}
+
+/// Returns the encoded prefix of the forward keys of edges of the given type from the node.
+pub fn outgoing_prefix_of_type<N, E>(src: &N, edge_type: &E) -> Result<Vec<u8>, Error>
+where
+    N: ?Sized + Serialize,
+    E: ?Sized + Serialize,
+{
+    crate::to_prefix(&(src, Direction::Out, edge_type))
+}
+
+/// Returns the encoded prefix of the reverse keys of all edges to the node.
+pub fn incoming_prefix<N: ?Sized + Serialize>(dst: &N) -> Result<Vec<u8>, Error> {
+    crate::to_prefix(&(dst, Direction::In))
+}
+
+/// Returns the encoded prefix of the reverse keys of edges of the given type to the node.
+pub fn incoming_prefix_of_type<N, E>(dst: &N, edge_type: &E) -> Result<Vec<u8>, Error>
+where
+    N: ?Sized + Serialize,
+    E: ?Sized + Serialize,
+{
+    crate::to_prefix(&(dst, Direction::In, edge_type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys() {
+        let edge = Edge::new(1u32, "likes", 2u32);
+
+        assert_eq!(&edge.forward_key().unwrap(), b"00000001:out:likes:00000002");
+        assert_eq!(&edge.reverse_key().unwrap(), b"00000002:in:likes:00000001");
+    }
+
+    #[test]
+    fn test_from_key() {
+        let edge = Edge::new(1u32, "likes".to_string(), 2u32);
+        let (forward, reverse) = edge.keys().unwrap();
+
+        assert_eq!(Edge::<u32, String>::from_key(&forward).unwrap(), edge);
+        assert_eq!(Edge::<u32, String>::from_key(&reverse).unwrap(), edge);
+        assert!(Edge::<u32, String>::from_key(b"00000001:up:likes:00000002").is_err());
+    }
+
+    #[test]
+    fn test_prefixes() {
+        let edge = Edge::new(1u32, "likes", 2u32);
+        let (forward, reverse) = edge.keys().unwrap();
+
+        assert!(forward.starts_with(&outgoing_prefix(&1u32).unwrap()));
+        assert!(forward.starts_with(&outgoing_prefix_of_type(&1u32, "likes").unwrap()));
+        assert!(!forward.starts_with(&outgoing_prefix_of_type(&1u32, "like").unwrap()));
+        assert!(!forward.starts_with(&incoming_prefix(&1u32).unwrap()));
+
+        assert!(reverse.starts_with(&incoming_prefix(&2u32).unwrap()));
+        assert!(reverse.starts_with(&incoming_prefix_of_type(&2u32, "likes").unwrap()));
+        assert!(!reverse.starts_with(&outgoing_prefix(&2u32).unwrap()));
+    }
+}
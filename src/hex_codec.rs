@@ -0,0 +1,139 @@
+//! Hexadecimal encoding backends
+//!
+//! The serializer and deserializer encode bytes through [`Backend`] so that
+//! the implementation can be swapped without touching the format code. The
+//! `hex` crate is used by default and the SIMD-accelerated `faster-hex` crate
+//! is used when the `faster-hex` feature is enabled.
+
+use crate::error::HexError;
+
+/// Encodes and decodes lowercase hexadecimal digits.
+pub(crate) trait HexCodec {
+    /// Writes `input` as hex digits into `output`, which must be exactly
+    /// twice as long as `input`.
+    fn encode_to_slice(input: &[u8], output: &mut [u8]);
+
+    /// Reads hex digits from `input` into `output`, which must be exactly
+    /// half as long as `input`.
+    fn decode_to_slice(input: &[u8], output: &mut [u8]) -> Result<(), HexError>;
+}
+
+/// Backend using the `hex` crate.
+#[cfg(any(not(feature = "faster-hex"), test))]
+pub(crate) struct HexCrate;
+
+#[cfg(any(not(feature = "faster-hex"), test))]
+impl HexCodec for HexCrate {
+    fn encode_to_slice(input: &[u8], output: &mut [u8]) {
+        hex::encode_to_slice(input, output).unwrap();
+    }
+
+    fn decode_to_slice(input: &[u8], output: &mut [u8]) -> Result<(), HexError> {
+        hex::decode_to_slice(input, output).map_err(|error| match error {
+            hex::FromHexError::InvalidHexCharacter { index, .. } => HexError::InvalidDigit(index),
+            hex::FromHexError::OddLength => HexError::OddLength,
+            hex::FromHexError::InvalidStringLength => HexError::InvalidLength,
+        })
+    }
+}
+
+/// Backend using the `faster-hex` crate.
+#[cfg(feature = "faster-hex")]
+pub(crate) struct FasterHex;
+
+#[cfg(feature = "faster-hex")]
+impl HexCodec for FasterHex {
+    fn encode_to_slice(input: &[u8], output: &mut [u8]) {
+        faster_hex::hex_encode(input, output).unwrap();
+    }
+
+    fn decode_to_slice(input: &[u8], output: &mut [u8]) -> Result<(), HexError> {
+        if input.len().is_multiple_of(2) && input.len() / 2 != output.len() {
+            return Err(HexError::InvalidLength);
+        }
+
+        faster_hex::hex_decode(input, output)
+            .map(|_| ())
+            .map_err(|error| match error {
+                faster_hex::Error::InvalidChar { index, .. } => HexError::InvalidDigit(index),
+                faster_hex::Error::OddLength => HexError::OddLength,
+                _ => HexError::InvalidLength,
+            })
+    }
+}
+
+/// The backend used by the crate.
+#[cfg(not(feature = "faster-hex"))]
+pub(crate) type Backend = HexCrate;
+
+/// The backend used by the crate.
+#[cfg(feature = "faster-hex")]
+pub(crate) type Backend = FasterHex;
+
+/// Appends `input` as hex digits to `output`.
+pub(crate) fn encode_extend(input: &[u8], output: &mut Vec<u8>) {
+    let start = output.len();
+    output.resize(start + input.len() * 2, 0);
+    Backend::encode_to_slice(input, &mut output[start..]);
+}
+
+/// Appends the bytes decoded from the hex digits in `input` to `output`.
+pub(crate) fn decode_extend(input: &[u8], output: &mut Vec<u8>) -> Result<(), HexError> {
+    let start = output.len();
+    output.resize(start + input.len() / 2, 0);
+
+    if let Err(error) = Backend::decode_to_slice(input, &mut output[start..]) {
+        output.truncate(start);
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_codec() {
+        let mut output = b"a:".to_vec();
+        encode_extend(b"\xca\xfe", &mut output);
+        assert_eq!(output, b"a:cafe");
+
+        let mut output = vec![1];
+        decode_extend(b"cafe", &mut output).unwrap();
+        assert_eq!(output, b"\x01\xca\xfe");
+
+        assert!(decode_extend(b"caf", &mut output).is_err());
+        assert!(decode_extend(b"cafg", &mut output).is_err());
+        assert_eq!(output, b"\x01\xca\xfe");
+    }
+
+    #[cfg(feature = "faster-hex")]
+    #[test]
+    fn test_backends_agree() {
+        let input = (0..=255u8).collect::<Vec<_>>();
+        let mut expected = vec![0; input.len() * 2];
+        let mut output = vec![0; input.len() * 2];
+
+        HexCrate::encode_to_slice(&input, &mut expected);
+        FasterHex::encode_to_slice(&input, &mut output);
+        assert_eq!(output, expected);
+
+        let encoded = expected;
+        let mut expected = vec![0; input.len()];
+        let mut output = vec![0; input.len()];
+
+        HexCrate::decode_to_slice(&encoded, &mut expected).unwrap();
+        FasterHex::decode_to_slice(&encoded, &mut output).unwrap();
+        assert_eq!(output, input);
+        assert_eq!(output, expected);
+
+        for input in [&b"caf"[..], b"cafg", b"CAFE", b"ca"] {
+            assert_eq!(
+                FasterHex::decode_to_slice(input, &mut [0; 2]),
+                HexCrate::decode_to_slice(input, &mut [0; 2])
+            );
+        }
+    }
+}
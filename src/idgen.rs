@@ -0,0 +1,214 @@
+//! Sortable unique ID generation
+//!
+//! [`SortableId`] is a 128-bit ID in the style of ULID consisting of a
+//! 48-bit timestamp in milliseconds since the Unix epoch followed by 80
+//! random bits. IDs are encoded as `u128` components, so IDs generated later
+//! sort after IDs generated earlier.
+//!
+//! Within a generator, IDs are strictly increasing: if the clock has not
+//! advanced (or has moved backwards), the previous ID is incremented instead
+//! of drawing new random bits.
+//!
+//! Requires the `idgen` feature. When combined with the `wasm` feature,
+//! random bytes are drawn from the JavaScript `crypto` API on
+//! `wasm32-unknown-unknown`.
+//!
+//! Example:
+//!
+//! ```rust
+//! # fn main() -> Result<(), strkey::Error> {
+//! let id1 = strkey::idgen::next_id();
+//! let id2 = strkey::idgen::next_id();
+//!
+//! assert!(id1 < id2);
+//! assert!(strkey::to_vec(&("order", id1))? < strkey::to_vec(&("order", id2))?);
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    fmt::{self, Display},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Time-ordered unique ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortableId(u128);
+
+impl SortableId {
+    /// Maximum value of the timestamp.
+    pub const MAX_TIMESTAMP: u64 = (1 << 48) - 1;
+
+    /// Maximum value of the random part.
+    pub const MAX_RANDOM: u128 = (1 << 80) - 1;
+
+    /// Construct an ID from a timestamp in milliseconds and random bits.
+    ///
+    /// Bits beyond [`Self::MAX_TIMESTAMP`] and [`Self::MAX_RANDOM`] are ignored.
+    pub fn from_parts(timestamp: u64, random: u128) -> Self {
+        Self(((timestamp & Self::MAX_TIMESTAMP) as u128) << 80 | (random & Self::MAX_RANDOM))
+    }
+
+    /// Construct from the raw 128-bit value.
+    pub fn from_u128(value: u128) -> Self {
+        Self(value)
+    }
+
+    /// Returns the raw 128-bit value.
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+
+    /// Returns the timestamp in milliseconds since the Unix epoch.
+    pub fn timestamp(&self) -> u64 {
+        (self.0 >> 80) as u64
+    }
+
+    /// Returns the random part.
+    pub fn random(&self) -> u128 {
+        self.0 & Self::MAX_RANDOM
+    }
+
+    /// Returns the smallest ID with the given timestamp.
+    ///
+    /// Useful as a range bound for finding IDs generated after a time.
+    pub fn min_for_timestamp(timestamp: u64) -> Self {
+        Self::from_parts(timestamp, 0)
+    }
+
+    /// Returns the largest ID with the given timestamp.
+    pub fn max_for_timestamp(timestamp: u64) -> Self {
+        Self::from_parts(timestamp, Self::MAX_RANDOM)
+    }
+}
+
+impl Serialize for SortableId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SortableId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u128::deserialize(deserializer).map(Self)
+    }
+}
+
+impl Display for SortableId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
+
+/// Generator of strictly increasing IDs.
+#[derive(Debug, Default)]
+pub struct IdGenerator {
+    last: Mutex<u128>,
+}
+
+impl IdGenerator {
+    /// Construct a generator.
+    pub const fn new() -> Self {
+        Self {
+            last: Mutex::new(0),
+        }
+    }
+
+    /// Returns a new ID using the current system time.
+    pub fn generate(&self) -> SortableId {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+            .min(SortableId::MAX_TIMESTAMP as u128) as u64;
+
+        self.generate_at(timestamp)
+    }
+
+    /// Returns a new ID using the given timestamp in milliseconds.
+    pub fn generate_at(&self, timestamp: u64) -> SortableId {
+        let mut last = self.last.lock().unwrap_or_else(|error| error.into_inner());
+        let mut id = SortableId::from_parts(timestamp, random_bits());
+
+        if id.0 <= *last {
+            id = SortableId(last.wrapping_add(1));
+        }
+
+        *last = id.0;
+
+        id
+    }
+}
+
+static GENERATOR: IdGenerator = IdGenerator::new();
+
+/// Returns a new ID from the process-wide generator.
+pub fn next_id() -> SortableId {
+    GENERATOR.generate()
+}
+
+fn random_bits() -> u128 {
+    let mut buffer = [0u8; 16];
+    getrandom::getrandom(&mut buffer).expect("failed to get random bytes");
+
+    u128::from_be_bytes(buffer) & SortableId::MAX_RANDOM
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parts() {
+        let id = SortableId::from_parts(0x0123456789ab, 0xffff);
+
+        assert_eq!(id.timestamp(), 0x0123456789ab);
+        assert_eq!(id.random(), 0xffff);
+        assert_eq!(
+            &crate::to_vec(&id).unwrap(),
+            b"0123456789ab0000000000000000ffff"
+        );
+        assert_eq!(id.to_string(), "0123456789ab0000000000000000ffff");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let id = next_id();
+        let key = crate::to_vec(&id).unwrap();
+
+        assert_eq!(crate::from_slice::<SortableId>(&key).unwrap(), id);
+    }
+
+    #[test]
+    fn test_monotonic() {
+        let generator = IdGenerator::new();
+        let mut ids = Vec::new();
+
+        for _ in 0..1000 {
+            ids.push(generator.generate_at(5));
+        }
+
+        ids.push(generator.generate_at(4));
+        ids.push(generator.generate_at(6));
+
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(ids.last().unwrap().timestamp(), 6);
+    }
+
+    #[test]
+    fn test_timestamp_bounds() {
+        let id = IdGenerator::new().generate_at(100);
+
+        assert!(SortableId::min_for_timestamp(100) <= id);
+        assert!(id <= SortableId::max_for_timestamp(100));
+        assert!(SortableId::max_for_timestamp(99) < id);
+    }
+}
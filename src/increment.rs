@@ -0,0 +1,140 @@
+//! Arithmetic on encoded integer components
+//!
+//! These functions change a fixed-width integer component of an encoded key
+//! in place without decoding and encoding the whole key. They work on the
+//! encoded hexadecimal digits, so incrementing a component yields the next
+//! value in sort order for both signed and unsigned integers. For a
+//! descending component, the next value in sort order is the previous
+//! integer.
+//!
+//! The key type is not known, so any component of an even number of
+//! lowercase hexadecimal digits is accepted as an integer. A byte array
+//! component such as `cafe`, or a string that happens to look like hex, is
+//! stepped as if it were an integer. Only pass the index of a component
+//! that the key type encodes as an integer.
+//!
+//! Example:
+//!
+//! ```rust
+//! # fn main() -> Result<(), strkey::Error> {
+//! let mut key = strkey::to_vec(&("counter", -1i8, "x"))?;
+//! strkey::increment_component(&mut key, 1)?;
+//!
+//! assert_eq!(strkey::from_slice::<(&str, i8, &str)>(&key)?, ("counter", 0, "x"));
+//! # Ok(())
+//! # }
+//! ```
+use crate::error::Error;
+
+/// Increments the integer component at the given index of a key using the
+/// `:` deliminator.
+///
+/// Returns [`Error::Data`] if the component does not look like a
+/// fixed-width integer or is already the maximum value, and [`Error::Other`]
+/// if the key has no component at the index. The component's type is not
+/// checked; see the [module documentation](self).
+pub fn increment_component(key: &mut [u8], index: usize) -> Result<(), Error> {
+    step_component(key, index, b':', true)
+}
+
+/// Decrements the integer component at the given index of a key using the
+/// `:` deliminator.
+///
+/// Returns [`Error::Data`] if the component does not look like a
+/// fixed-width integer or is already the minimum value, and [`Error::Other`]
+/// if the key has no component at the index. The component's type is not
+/// checked; see the [module documentation](self).
+pub fn decrement_component(key: &mut [u8], index: usize) -> Result<(), Error> {
+    step_component(key, index, b':', false)
+}
+
+fn step_component(key: &mut [u8], index: usize, deliminator: u8, up: bool) -> Result<(), Error> {
+    let component = key
+        .split_mut(|&byte| byte == deliminator)
+        .nth(index)
+        .ok_or_else(|| Error::Other(format!("component {} not found", index)))?;
+
+    let valid = !component.is_empty()
+        && component.len() % 2 == 0
+        && component.len() <= 32
+        && component
+            .iter()
+            .all(|&byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte));
+
+    if !valid {
+        return Err(Error::Data(String::from_utf8_lossy(component).into_owned()));
+    }
+
+    let (limit, wrap) = if up { (b'f', b'0') } else { (b'0', b'f') };
+
+    if component.iter().all(|&byte| byte == limit) {
+        return Err(Error::Data(format!("component {} overflow", index)));
+    }
+
+    for digit in component.iter_mut().rev() {
+        if *digit == limit {
+            *digit = wrap;
+        } else {
+            *digit = match (*digit, up) {
+                (b'9', true) => b'a',
+                (b'a', false) => b'9',
+                (digit, true) => digit + 1,
+                (digit, false) => digit - 1,
+            };
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_increment_component() {
+        let mut key = b"a:00ff:b".to_vec();
+        increment_component(&mut key, 1).unwrap();
+        assert_eq!(key, b"a:0100:b");
+
+        let mut key = b"09".to_vec();
+        increment_component(&mut key, 0).unwrap();
+        assert_eq!(key, b"0a");
+
+        let mut key = crate::to_vec(&i16::MAX).unwrap();
+        assert!(matches!(
+            increment_component(&mut key, 0),
+            Err(Error::Data(_))
+        ));
+
+        // Not distinguishable from an integer without the key type.
+        let mut key = b"a:cafe".to_vec();
+        increment_component(&mut key, 1).unwrap();
+        assert_eq!(key, b"a:caff");
+
+        assert!(increment_component(&mut b"a:01".to_vec(), 0).is_err());
+        assert!(increment_component(&mut b"a:0F".to_vec(), 1).is_err());
+        assert!(matches!(
+            increment_component(&mut b"a:01".to_vec(), 2),
+            Err(Error::Other(_))
+        ));
+    }
+
+    #[test]
+    fn test_decrement_component() {
+        let mut key = b"a:0100".to_vec();
+        decrement_component(&mut key, 1).unwrap();
+        assert_eq!(key, b"a:00ff");
+
+        let mut key = b"a0".to_vec();
+        decrement_component(&mut key, 0).unwrap();
+        assert_eq!(key, b"9f");
+
+        let mut key = crate::to_vec(&0i32).unwrap();
+        decrement_component(&mut key, 0).unwrap();
+        assert_eq!(crate::from_slice::<i32>(&key).unwrap(), -1);
+
+        assert!(decrement_component(&mut b"0000".to_vec(), 0).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! JSON interchange
+//!
+//! Converts between encoded keys and JSON values using the key's type. Structs
+//! become JSON objects keyed by field name, which is convenient for debugging
+//! and for services that construct keys without this crate.
+//!
+//! Keys can also be converted using a [`Schema`] given as JSON, for callers
+//! that have no Rust types, such as the `ffi` and `wasm` bindings. The
+//! schema is a JSON array of component type names: `u8`, `u16`, `u32`,
+//! `u64`, `i8`, `i16`, `i32`, `i64`, `f32`, `f64`, `str`, `bytes`, `bool`,
+//! `unit`, `mac`, and `eui64`. Values are a JSON array with one item per
+//! component. Integers and floats are numbers, byte arrays and hardware
+//! addresses are hexadecimal strings, and units are `null`. Integers may
+//! also be given as decimal strings for values that cannot be represented
+//! exactly by a JavaScript number, and decoded integers outside
+//! ±(2<sup>53</sup> - 1) are returned as decimal strings.
+//!
+//! Example:
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct AccountKey {
+//!     kind: String,
+//!     id: u32,
+//! }
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let json = strkey::json::to_json::<AccountKey>(b"account:000004d2")?;
+//! assert_eq!(json, serde_json::json!({"kind": "account", "id": 1234}));
+//!
+//! let key = strkey::json::from_json::<AccountKey>(&json)?;
+//! assert_eq!(&key, b"account:000004d2");
+//!
+//! let schema = r#"["str", "u32"]"#;
+//! let values = strkey::json::decode_to_json("account:000004d2", schema)?;
+//! assert_eq!(values, r#"["account",1234]"#);
+//! # Ok(())
+//! # }
+//! ```
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    error::Error,
+    value::{FieldType, Schema, Value},
+};
+
+/// Decodes a key as `T` and returns it as a JSON value.
+pub fn to_json<'a, T>(key: &'a [u8]) -> Result<serde_json::Value, Error>
+where
+    T: Deserialize<'a> + Serialize,
+{
+    let value = crate::from_slice::<T>(key)?;
+
+    Ok(serde_json::to_value(&value)?)
+}
+
+/// Reads a JSON value as `T` and returns it encoded as a key.
+pub fn from_json<T>(json: &serde_json::Value) -> Result<Vec<u8>, Error>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let value = T::deserialize(json)?;
+
+    crate::to_vec(&value)
+}
+
+/// Encodes a JSON array of values into a key using a JSON schema.
+pub fn encode_json_with_schema(values: &str, schema: &str) -> Result<String, Error> {
+    let schema = parse_schema(schema)?;
+    let values: Vec<JsonValue> = serde_json::from_str(values)?;
+
+    if values.len() != schema.fields().len() {
+        return Err(Error::Data(format!(
+            "expected {} values, got {}",
+            schema.fields().len(),
+            values.len()
+        )));
+    }
+
+    let values = values
+        .iter()
+        .zip(schema.fields())
+        .map(|(value, field)| from_json_value(value, *field))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let key = crate::encode_with_schema(&values, &schema)?;
+
+    String::from_utf8(key).map_err(|error| error.utf8_error().into())
+}
+
+/// Decodes a key into a JSON array of values using a JSON schema.
+pub fn decode_to_json(key: &str, schema: &str) -> Result<String, Error> {
+    let schema = parse_schema(schema)?;
+    let values = crate::decode_with_schema(key.as_bytes(), &schema)?;
+    let values = values.into_iter().map(to_json_value).collect::<Vec<_>>();
+
+    Ok(serde_json::to_string(&values)?)
+}
+
+fn parse_schema(schema: &str) -> Result<Schema, Error> {
+    let names: Vec<String> = serde_json::from_str(schema)?;
+
+    names
+        .iter()
+        .map(|name| name.parse::<FieldType>())
+        .collect::<Result<Vec<_>, _>>()
+        .map(Schema::new)
+}
+
+fn from_json_value(value: &JsonValue, field: FieldType) -> Result<Value, Error> {
+    let unexpected = || Error::Data(format!("expected {}, got {}", field, value));
+
+    Ok(match (field, value) {
+        (_, JsonValue::String(text)) if field != FieldType::Str => Value::parse(text, field)?,
+        (FieldType::Str, JsonValue::String(text)) => Value::Str(text.clone()),
+        (FieldType::U8 | FieldType::U16 | FieldType::U32 | FieldType::U64, _) => {
+            Value::U64(value.as_u64().ok_or_else(unexpected)?)
+        }
+        (FieldType::I8 | FieldType::I16 | FieldType::I32 | FieldType::I64, _) => {
+            Value::I64(value.as_i64().ok_or_else(unexpected)?)
+        }
+        (FieldType::F32 | FieldType::F64, _) => Value::F64(value.as_f64().ok_or_else(unexpected)?),
+        (FieldType::Bool, JsonValue::Bool(value)) => Value::Bool(*value),
+        (FieldType::Unit, JsonValue::Null) => Value::Unit,
+        _ => return Err(unexpected()),
+    })
+}
+
+/// Largest integer that a JavaScript number represents exactly.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+fn to_json_value(value: Value) -> JsonValue {
+    match value {
+        Value::Str(value) => JsonValue::String(value),
+        Value::U64(value) if value > MAX_SAFE_INTEGER as u64 => {
+            JsonValue::String(value.to_string())
+        }
+        Value::U64(value) => value.into(),
+        Value::I64(value) if !(-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&value) => {
+            JsonValue::String(value.to_string())
+        }
+        Value::I64(value) => value.into(),
+        Value::F64(value) => value.into(),
+        Value::Bytes(value) => JsonValue::String(hex::encode(value)),
+        Value::Bool(value) => JsonValue::Bool(value),
+        Value::Unit => JsonValue::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct MyKey {
+        name: String,
+        value: i8,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let json = to_json::<MyKey>(b"abc:7f").unwrap();
+
+        assert_eq!(json, json!({"name": "abc", "value": -1}));
+        assert_eq!(from_json::<MyKey>(&json).unwrap(), b"abc:7f");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(to_json::<MyKey>(b"abc").is_err());
+        assert!(from_json::<MyKey>(&json!({"name": "abc"})).is_err());
+        assert!(from_json::<MyKey>(&json!({"name": "abc", "value": 1000})).is_err());
+    }
+
+    #[test]
+    fn test_schema_round_trip() {
+        let schema = r#"["str", "u64", "i8", "f64", "bytes", "bool", "unit"]"#;
+        let values = r#"["a","18446744073709551615",-1,0.5,"abcd",true,null]"#;
+
+        let key = encode_json_with_schema(values, schema).unwrap();
+        assert_eq!(key, "a:ffffffffffffffff:7f:bfe0000000000000:abcd:true");
+        assert_eq!(decode_to_json(&key, schema).unwrap(), values);
+    }
+
+    #[test]
+    fn test_string_integers() {
+        let key = encode_json_with_schema(r#"["9007199254740993"]"#, r#"["u64"]"#).unwrap();
+        assert_eq!(key, "0020000000000001");
+        assert_eq!(
+            decode_to_json(&key, r#"["u64"]"#).unwrap(),
+            r#"["9007199254740993"]"#
+        );
+
+        let values = r#"[9007199254740991,-9007199254740991,"-9007199254740993"]"#;
+        let key = encode_json_with_schema(values, r#"["u64", "i64", "i64"]"#).unwrap();
+        assert_eq!(
+            decode_to_json(&key, r#"["u64", "i64", "i64"]"#).unwrap(),
+            values
+        );
+    }
+
+    #[test]
+    fn test_schema_errors() {
+        assert!(encode_json_with_schema("[1]", r#"["u128"]"#).is_err());
+        assert!(encode_json_with_schema("[1, 2]", r#"["u8"]"#).is_err());
+        assert!(encode_json_with_schema("[true]", r#"["u8"]"#).is_err());
+        assert!(encode_json_with_schema("[1]", r#"["str"]"#).is_err());
+        assert!(decode_to_json("zz", r#"["u8"]"#).is_err());
+    }
+}
@@ -0,0 +1,383 @@
+//! Typed keys
+//!
+//! [`Key<T>`] holds an encoded key together with the type it encodes so
+//! that keys of different types cannot be mixed up while being passed
+//! around as bytes.
+//!
+//! Example:
+//!
+//! ```rust
+//! use std::convert::TryFrom;
+//!
+//! use strkey::key::Key;
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let key = Key::new(&("account".to_string(), 1234u32))?;
+//! assert_eq!(key.as_bytes(), b"account:000004d2");
+//!
+//! let key = Key::<(String, u32)>::try_from(key.into_vec())?;
+//! assert_eq!(key.decode()?, ("account".to_string(), 1234));
+//!
+//! assert!(Key::<(String, u32)>::try_from(&b"account"[..]).is_err());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Keys can also be constructed from a [`KeyTemplate`], a pattern of
+//! components where placeholders such as `{u32}` are filled from text
+//! arguments:
+//!
+//! ```rust
+//! use strkey::key::Key;
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let key = Key::<(String, u32)>::from_template("account:{u32}", &["1234"])?;
+//! assert_eq!(key.as_bytes(), b"account:000004d2");
+//!
+//! assert!(Key::<(String, u32)>::from_template("account:{u32}", &["x"]).is_err());
+//! assert!(Key::<(String, u32)>::from_template("account:{u8}", &["1"]).is_err());
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    error::Error,
+    shard::fnv1a_64,
+    value::{FieldType, Schema, Value},
+};
+
+/// Encoded key of a value of type `T`.
+///
+/// Keys compare and hash by their encoded bytes.
+pub struct Key<T> {
+    bytes: Vec<u8>,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> Key<T> {
+    /// Returns the encoded bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Unwraps and returns the encoded bytes.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns a hash of the encoded bytes.
+    ///
+    /// See [`stable_hash64`].
+    pub fn stable_hash64(&self) -> u64 {
+        fnv1a_64(&self.bytes)
+    }
+
+    fn from_vec_unchecked(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T: Serialize> Key<T> {
+    /// Encodes the value.
+    ///
+    /// This is fallible because not every value can be encoded, such as a
+    /// map or a verbatim string containing the deliminator, so there is no
+    /// `From<&T>` implementation.
+    pub fn new(value: &T) -> Result<Self, Error> {
+        Ok(Self::from_vec_unchecked(crate::to_vec(value)?))
+    }
+}
+
+impl<T: DeserializeOwned> Key<T> {
+    /// Decodes the value.
+    pub fn decode(&self) -> Result<T, Error> {
+        crate::from_slice(&self.bytes)
+    }
+
+    /// Renders the template with the arguments and validates that the key
+    /// decodes to a value of type `T`.
+    ///
+    /// See [`KeyTemplate`] for the syntax.
+    pub fn from_template(template: &str, args: &[&str]) -> Result<Self, Error> {
+        template.parse::<KeyTemplate>()?.render_key(args)
+    }
+}
+
+impl<T> Clone for Key<T> {
+    fn clone(&self) -> Self {
+        Self::from_vec_unchecked(self.bytes.clone())
+    }
+}
+
+impl<T> std::fmt::Debug for Key<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Key")
+            .field(&String::from_utf8_lossy(&self.bytes))
+            .finish()
+    }
+}
+
+impl<T> PartialEq for Key<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes == other.bytes
+    }
+}
+
+impl<T> Eq for Key<T> {}
+
+impl<T> PartialOrd for Key<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Key<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bytes.cmp(&other.bytes)
+    }
+}
+
+impl<T> Hash for Key<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bytes.hash(state);
+    }
+}
+
+impl<T> AsRef<[u8]> for Key<T> {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<T> From<Key<T>> for Vec<u8> {
+    fn from(key: Key<T>) -> Self {
+        key.into_vec()
+    }
+}
+
+/// Validates that the bytes decode to a value of type `T`.
+impl<T: DeserializeOwned> TryFrom<&[u8]> for Key<T> {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::try_from(bytes.to_vec())
+    }
+}
+
+/// Validates that the bytes decode to a value of type `T`.
+impl<T: DeserializeOwned> TryFrom<Vec<u8>> for Key<T> {
+    type Error = Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        crate::from_slice::<T>(&bytes)?;
+
+        Ok(Self::from_vec_unchecked(bytes))
+    }
+}
+
+/// Returns a hash of the value's default encoding.
+///
+/// The hash is the 64-bit FNV-1a hash of the encoded bytes, which is pinned
+/// and will not change across releases or platforms, so it is suitable for
+/// partitioning. Because it hashes the key rather than the Rust value, other
+/// languages producing the same key bytes compute the same hash. This is the
+/// hash used by [`crate::shard::bucket_of`].
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::key::{stable_hash64, Key};
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let value = ("account".to_string(), 1234u32);
+/// assert_eq!(stable_hash64(&value)?, 0xabd1829bef5d866c);
+/// assert_eq!(Key::new(&value)?.stable_hash64(), 0xabd1829bef5d866c);
+/// # Ok(())
+/// # }
+/// ```
+pub fn stable_hash64<T>(value: &T) -> Result<u64, Error>
+where
+    T: ?Sized + Serialize,
+{
+    Ok(fnv1a_64(&crate::to_vec(value)?))
+}
+
+/// Pattern of key components with typed placeholders.
+///
+/// Components are separated by the default deliminator. A component is
+/// either literal text, which is encoded as a string, or a placeholder: `{}`
+/// for a string or `{type}` where `type` is a [`FieldType`] name such as
+/// `u32` or `bytes`. Literal text cannot contain braces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TemplatePart {
+    Literal(String),
+    Placeholder(FieldType),
+}
+
+impl KeyTemplate {
+    /// Returns the types of the placeholders in order.
+    pub fn placeholders(&self) -> impl Iterator<Item = FieldType> + '_ {
+        self.parts.iter().filter_map(|part| match part {
+            TemplatePart::Placeholder(field) => Some(*field),
+            TemplatePart::Literal(_) => None,
+        })
+    }
+
+    /// Returns the schema of the components.
+    ///
+    /// Literal components are strings.
+    pub fn schema(&self) -> Schema {
+        Schema::new(
+            self.parts
+                .iter()
+                .map(|part| match part {
+                    TemplatePart::Placeholder(field) => *field,
+                    TemplatePart::Literal(_) => FieldType::Str,
+                })
+                .collect(),
+        )
+    }
+
+    /// Encodes the key with the placeholders filled by the arguments.
+    ///
+    /// Each argument is parsed as the placeholder's type as described by
+    /// [`Value::parse`].
+    pub fn render(&self, args: &[&str]) -> Result<Vec<u8>, Error> {
+        let count = self.placeholders().count();
+
+        if args.len() != count {
+            return Err(Error::Data(format!(
+                "expected {} arguments, got {}",
+                count,
+                args.len()
+            )));
+        }
+
+        let mut args = args.iter();
+        let values = self
+            .parts
+            .iter()
+            .map(|part| match part {
+                TemplatePart::Literal(text) => Ok(Value::Str(text.clone())),
+                TemplatePart::Placeholder(field) => Value::parse(args.next().unwrap(), *field),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        crate::encode_with_schema(&values, &self.schema())
+    }
+
+    /// Encodes the key and validates that it decodes to a value of type `T`.
+    pub fn render_key<T: DeserializeOwned>(&self, args: &[&str]) -> Result<Key<T>, Error> {
+        Key::try_from(self.render(args)?)
+    }
+}
+
+impl FromStr for KeyTemplate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s
+            .split(':')
+            .map(|component| {
+                if let Some(name) = component
+                    .strip_prefix('{')
+                    .and_then(|component| component.strip_suffix('}'))
+                {
+                    if name.is_empty() {
+                        Ok(TemplatePart::Placeholder(FieldType::Str))
+                    } else {
+                        name.parse().map(TemplatePart::Placeholder)
+                    }
+                } else if component.contains(['{', '}']) {
+                    Err(Error::Data(format!(
+                        "invalid template component {}",
+                        component
+                    )))
+                } else {
+                    Ok(TemplatePart::Literal(component.to_string()))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { parts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let key = Key::new(&(1u8, 2u8)).unwrap();
+        assert_eq!(key.as_bytes(), b"01:02");
+        assert_eq!(format!("{:?}", key), r#"Key("01:02")"#);
+
+        let bytes: Vec<u8> = key.clone().into();
+        assert_eq!(Key::<(u8, u8)>::try_from(bytes).unwrap(), key);
+        assert!(Key::<(u8, u8)>::try_from(&b"01:zz"[..]).is_err());
+        assert!(Key::<(u8, u8)>::try_from(&b"01:02:03"[..]).is_err());
+        assert!(Key::new(&HashMap::<u8, u8>::new()).is_err());
+    }
+
+    #[test]
+    fn test_ord() {
+        let key1 = Key::new(&(1u8, "b")).unwrap();
+        let key2 = Key::new(&(2u8, "a")).unwrap();
+
+        assert!(key1 < key2);
+    }
+
+    #[test]
+    fn test_stable_hash64() {
+        let key = Key::new(&("a".to_string(), 1u8)).unwrap();
+
+        assert_eq!(key.stable_hash64(), fnv1a_64(b"a:01"));
+        assert_eq!(stable_hash64(&("a", 1u8)).unwrap(), key.stable_hash64());
+        assert_ne!(stable_hash64(&("a", 2u8)).unwrap(), key.stable_hash64());
+        assert_eq!(stable_hash64(&"a").unwrap(), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_template() {
+        let template = "user:{}:{i8}:{bytes}".parse::<KeyTemplate>().unwrap();
+
+        assert_eq!(
+            template.placeholders().collect::<Vec<_>>(),
+            vec![FieldType::Str, FieldType::I8, FieldType::Bytes]
+        );
+        assert_eq!(
+            template.render(&["alice", "-1", "abcd"]).unwrap(),
+            b"user:alice:7f:abcd"
+        );
+        assert!(template.render(&["alice", "-1"]).is_err());
+        assert!(template.render(&["alice", "x", "abcd"]).is_err());
+
+        let key = template
+            .render_key::<(String, String, i8, serde_bytes::ByteBuf)>(&["alice", "-1", "abcd"])
+            .unwrap();
+        assert_eq!(key.decode().unwrap().2, -1);
+
+        assert!("a:{u128}".parse::<KeyTemplate>().is_err());
+        assert!("a:{u8".parse::<KeyTemplate>().is_err());
+    }
+}
@@ -20,13 +20,80 @@
 //!
 //! For details, see the [`ser`] module.
 
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "ffi"), forbid(unsafe_code))]
+#![cfg_attr(feature = "ffi", deny(unsafe_code))]
 #![warn(missing_docs)]
 
+#[macro_use]
+mod serde_impl;
+
+pub mod canonical;
+#[cfg(feature = "bytekey")]
+pub mod compat;
+pub mod compress;
+pub mod config;
+pub mod const_encode;
+#[cfg(feature = "csv")]
+pub mod csv;
 pub mod de;
+pub mod display;
+pub mod dump;
+pub mod dynamodb;
 pub mod error;
+pub mod expiry;
+#[cfg(feature = "ffi")]
+#[allow(unsafe_code)]
+pub mod ffi;
+pub mod filter;
+pub mod graph;
+mod hex_codec;
+#[cfg(feature = "idgen")]
+pub mod idgen;
+pub mod increment;
+#[cfg(feature = "serde_json")]
+pub mod json;
+pub mod key;
+pub mod mem;
+pub mod merge;
+pub mod metrics;
+pub mod object_store;
+pub mod partition;
+pub mod query;
+pub mod range;
+pub mod runs;
 pub mod ser;
+pub mod shard;
+pub mod split;
+pub mod tables;
+pub mod tenant;
+pub mod testing;
+pub mod timeseries;
+pub mod tree;
+pub mod types;
+pub mod value;
+pub mod versioned;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Version of the encoding format.
+///
+/// The version is incremented when the encoding of any value with the default
+/// options changes. Keys written by an earlier version can be read with
+/// [`config::Config::compat`].
+pub const FORMAT_VERSION: u32 = 1;
 
-pub use crate::de::{from_reader, from_slice, Deserializer};
+pub use crate::canonical::canonicalize;
+pub use crate::de::{find_component, from_reader, from_slice, kind_of, raw_kind_of, Deserializer};
+pub use crate::display::{sniff_deliminator, truncate_display};
 pub use crate::error::{Error, Result};
-pub use crate::ser::{to_vec, to_writer, Serializer};
+pub use crate::increment::{decrement_component, increment_component};
+#[cfg(feature = "heapless")]
+pub use crate::ser::to_heapless;
+#[cfg(feature = "smallvec")]
+pub use crate::ser::to_smallvec;
+pub use crate::ser::{
+    encode_sorted, fixed_len, to_array, to_prefix, to_vec, to_writer, to_writer_flush, Serializer,
+};
+pub use crate::value::{
+    decode_with_schema, encode_with_schema, replace_component, FieldType, Schema, Value,
+};
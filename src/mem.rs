@@ -0,0 +1,607 @@
+//! In-memory collections of encoded keys
+//!
+//! [`KeySet`] stores encoded keys compactly for duplicate detection.
+//! [`TypedBTreeMap`] is an ordered map with typed keys stored in encoded
+//! form, for use as a test double for key-value stores.
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::mem::KeySet;
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let mut keys = KeySet::new();
+//!
+//! assert!(keys.insert(&strkey::to_vec(&("user", 1u8))?));
+//! assert!(!keys.insert(&strkey::to_vec(&("user", 1u8))?));
+//! assert!(keys.contains(b"user:01"));
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    iter::FromIterator,
+    marker::PhantomData,
+    ops::{Bound, Range, RangeBounds},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    compress::{compress_key_into, decompress_key, read_varint, shared_prefix_len, write_varint},
+    error::Error,
+    range::{ByteRange, KeyRange},
+};
+
+const BLOCK_SIZE: usize = 32;
+
+/// Set of encoded keys stored in prefix-compressed blocks.
+///
+/// Keys are kept in sorted order in blocks of up to 32 keys. Each key in a
+/// block is prefix-compressed against the key before it, as in
+/// [`crate::compress`], so keys sharing long prefixes take much less memory
+/// than in a `HashSet<Vec<u8>>`. Lookups binary search the first key of
+/// each block and then scan one block. The set is intended for detecting
+/// duplicates during bulk loads.
+#[derive(Debug, Clone, Default)]
+pub struct KeySet {
+    blocks: Vec<Block>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Block {
+    first: Vec<u8>,
+    data: Vec<u8>,
+    len: usize,
+}
+
+/// Result of scanning a block for a key.
+struct Search {
+    /// Index of the first key not less than the key.
+    index: usize,
+    /// Byte range of that key's entry, or an empty range at the end.
+    entry: Range<usize>,
+    /// Whether that key is equal to the key.
+    found: bool,
+    /// Length of the prefix shared by the key and the key before the index.
+    previous_shared: usize,
+}
+
+impl Block {
+    fn new(keys: &[Vec<u8>]) -> Self {
+        let mut data = Vec::new();
+        let mut previous: &[u8] = &[];
+
+        for key in keys {
+            write_entry(previous, key, &mut data);
+            previous = key;
+        }
+
+        Self {
+            first: keys[0].clone(),
+            data,
+            len: keys.len(),
+        }
+    }
+
+    fn keys(&self) -> Vec<Vec<u8>> {
+        let mut keys: Vec<Vec<u8>> = Vec::with_capacity(self.len);
+        let mut remaining = self.data.as_slice();
+
+        while !remaining.is_empty() {
+            let (len, rest) = read_varint(remaining).expect("valid block");
+            let (compressed, rest) = rest.split_at(len as usize);
+            let previous = keys.last().map(Vec::as_slice).unwrap_or_default();
+
+            keys.push(decompress_key(previous, compressed).expect("valid block"));
+            remaining = rest;
+        }
+
+        keys
+    }
+
+    /// Scans the compressed entries for the first key not less than the key.
+    ///
+    /// Each key is decompressed into `current`, reusing its allocation, which
+    /// is left holding the key at the returned index.
+    fn search(&self, key: &[u8], current: &mut Vec<u8>) -> Search {
+        let mut offset = 0;
+        let mut previous_shared = 0;
+
+        current.clear();
+
+        for index in 0..self.len {
+            let (len, rest) = read_varint(&self.data[offset..]).expect("valid block");
+            let start = self.data.len() - rest.len();
+            let end = start + len as usize;
+            let (shared, suffix) = read_varint(&self.data[start..end]).expect("valid block");
+
+            current.truncate(shared as usize);
+            current.extend_from_slice(suffix);
+
+            match current.as_slice().cmp(key) {
+                Ordering::Less => {
+                    previous_shared = shared_prefix_len(current, key);
+                    offset = end;
+                }
+                ordering => {
+                    return Search {
+                        index,
+                        entry: offset..end,
+                        found: ordering == Ordering::Equal,
+                        previous_shared,
+                    }
+                }
+            }
+        }
+
+        Search {
+            index: self.len,
+            entry: offset..offset,
+            found: false,
+            previous_shared,
+        }
+    }
+
+    /// Inserts the key into the compressed entries and returns whether it
+    /// was not already present.
+    fn insert(&mut self, key: &[u8], current: &mut Vec<u8>) -> bool {
+        let search = self.search(key, current);
+
+        if search.found {
+            return false;
+        }
+
+        let mut entries = Vec::new();
+        write_varint(
+            (varint_len(search.previous_shared) + key.len() - search.previous_shared) as u64,
+            &mut entries,
+        );
+        write_varint(search.previous_shared as u64, &mut entries);
+        entries.extend_from_slice(&key[search.previous_shared..]);
+
+        if search.index < self.len {
+            write_entry(key, current, &mut entries);
+        }
+
+        self.data.splice(search.entry, entries);
+        self.len += 1;
+
+        if search.index == 0 {
+            self.first = key.to_vec();
+        }
+
+        true
+    }
+}
+
+/// Appends the key compressed against the previous key and prefixed by its
+/// length.
+fn write_entry(previous: &[u8], key: &[u8], output: &mut Vec<u8>) {
+    let shared = shared_prefix_len(previous, key);
+
+    write_varint((varint_len(shared) + key.len() - shared) as u64, output);
+    compress_key_into(previous, key, output);
+}
+
+fn varint_len(value: usize) -> usize {
+    let bits = usize::BITS - value.leading_zeros();
+
+    (bits.max(1) as usize).div_ceil(7)
+}
+
+impl KeySet {
+    /// Construct an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the set has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds the key and returns whether it was not already present.
+    pub fn insert(&mut self, key: &[u8]) -> bool {
+        if self.blocks.is_empty() {
+            self.blocks.push(Block::new(&[key.to_vec()]));
+            self.len += 1;
+            return true;
+        }
+
+        let index = self.block_index(key).unwrap_or(0);
+        let mut current = Vec::with_capacity(key.len());
+
+        if !self.blocks[index].insert(key, &mut current) {
+            return false;
+        }
+
+        self.len += 1;
+
+        if self.blocks[index].len > BLOCK_SIZE * 2 {
+            let mut keys = self.blocks[index].keys();
+            let second = keys.split_off(BLOCK_SIZE);
+            self.blocks[index] = Block::new(&keys);
+            self.blocks.insert(index + 1, Block::new(&second));
+        }
+
+        true
+    }
+
+    /// Returns whether the set contains the key.
+    pub fn contains(&self, key: &[u8]) -> bool {
+        match self.block_index(key) {
+            Some(index) => {
+                let mut current = Vec::with_capacity(key.len());
+                self.blocks[index].search(key, &mut current).found
+            }
+            None => false,
+        }
+    }
+
+    /// Returns an iterator over the keys in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.blocks.iter().flat_map(Block::keys)
+    }
+
+    /// Returns an iterator over the keys within the range in sorted order.
+    ///
+    /// Example:
+    ///
+    /// ```rust
+    /// use strkey::mem::KeySet;
+    ///
+    /// let keys = vec![b"a:01", b"a:02", b"b:01"].into_iter().collect::<KeySet>();
+    /// let range = keys.range(&b"a:02"[..]..).collect::<Vec<_>>();
+    ///
+    /// assert_eq!(range, vec![b"a:02".to_vec(), b"b:01".to_vec()]);
+    /// ```
+    pub fn range<R, K>(&self, range: R) -> impl Iterator<Item = Vec<u8>> + '_
+    where
+        R: RangeBounds<K>,
+        K: AsRef<[u8]>,
+    {
+        let start = map_bound(range.start_bound());
+        let end = map_bound(range.end_bound());
+        let first_block = match &start {
+            Bound::Included(key) | Bound::Excluded(key) => self.block_index(key).unwrap_or(0),
+            Bound::Unbounded => 0,
+        };
+
+        self.blocks[first_block.min(self.blocks.len())..]
+            .iter()
+            .flat_map(Block::keys)
+            .skip_while(move |key| match &start {
+                Bound::Included(start) => key < start,
+                Bound::Excluded(start) => key <= start,
+                Bound::Unbounded => false,
+            })
+            .take_while(move |key| match &end {
+                Bound::Included(end) => key <= end,
+                Bound::Excluded(end) => key < end,
+                Bound::Unbounded => true,
+            })
+    }
+
+    /// Returns the index of the last block whose first key is not greater
+    /// than the key.
+    fn block_index(&self, key: &[u8]) -> Option<usize> {
+        let position = self
+            .blocks
+            .partition_point(|block| block.first.as_slice() <= key);
+
+        position.checked_sub(1)
+    }
+}
+
+impl<K: AsRef<[u8]>> Extend<K> for KeySet {
+    fn extend<T: IntoIterator<Item = K>>(&mut self, iter: T) {
+        for key in iter {
+            self.insert(key.as_ref());
+        }
+    }
+}
+
+impl<K: AsRef<[u8]>> FromIterator<K> for KeySet {
+    fn from_iter<T: IntoIterator<Item = K>>(iter: T) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+/// Ordered map with typed keys stored as encoded keys.
+///
+/// Entries are kept in a `BTreeMap<Vec<u8>, V>` in the order of their
+/// encoded keys, the same order a key-value store iterates them in. Keys
+/// are encoded on insert and lookup and decoded when iterating. This is
+/// intended as an in-memory test double for store integrations.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::mem::TypedBTreeMap;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let mut map = TypedBTreeMap::new();
+/// map.insert(&("acme".to_string(), 2u32), "b")?;
+/// map.insert(&("acme".to_string(), 1u32), "a")?;
+/// map.insert(&("other".to_string(), 1u32), "c")?;
+///
+/// assert_eq!(map.get(&("acme".to_string(), 1))?, Some(&"a"));
+///
+/// let values = map
+///     .prefix(&"acme")?
+///     .map(|entry| entry.map(|(_, value)| *value))
+///     .collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(values, vec!["a", "b"]);
+/// # Ok(())
+/// # }
+/// ```
+pub struct TypedBTreeMap<K, V> {
+    map: BTreeMap<Vec<u8>, V>,
+    _key: PhantomData<fn() -> K>,
+}
+
+impl<K, V> TypedBTreeMap<K, V> {
+    /// Construct an empty map.
+    pub fn new() -> Self {
+        Self {
+            map: BTreeMap::new(),
+            _key: PhantomData,
+        }
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns whether the map has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Returns the underlying map of encoded keys.
+    pub fn as_bytes_map(&self) -> &BTreeMap<Vec<u8>, V> {
+        &self.map
+    }
+
+    /// Unwrap and return the underlying map of encoded keys.
+    pub fn into_bytes_map(self) -> BTreeMap<Vec<u8>, V> {
+        self.map
+    }
+
+    fn byte_range(&self, range: ByteRange) -> impl Iterator<Item = (&Vec<u8>, &V)> {
+        let valid = match (&range.0, &range.1) {
+            (Bound::Included(start), Bound::Included(end)) => start <= end,
+            (Bound::Included(start), Bound::Excluded(end))
+            | (Bound::Excluded(start), Bound::Included(end)) => start <= end,
+            (Bound::Excluded(start), Bound::Excluded(end)) => start < end,
+            _ => true,
+        };
+
+        valid.then(|| self.map.range(range)).into_iter().flatten()
+    }
+}
+
+impl<K: Serialize, V> TypedBTreeMap<K, V> {
+    /// Inserts the value and returns the previous value of the key.
+    pub fn insert(&mut self, key: &K, value: V) -> Result<Option<V>, Error> {
+        Ok(self.map.insert(crate::to_vec(key)?, value))
+    }
+
+    /// Returns the value of the key.
+    pub fn get(&self, key: &K) -> Result<Option<&V>, Error> {
+        Ok(self.map.get(&crate::to_vec(key)?))
+    }
+
+    /// Returns a mutable reference to the value of the key.
+    pub fn get_mut(&mut self, key: &K) -> Result<Option<&mut V>, Error> {
+        Ok(self.map.get_mut(&crate::to_vec(key)?))
+    }
+
+    /// Returns whether the map contains the key.
+    pub fn contains_key(&self, key: &K) -> Result<bool, Error> {
+        Ok(self.map.contains_key(&crate::to_vec(key)?))
+    }
+
+    /// Removes the key and returns its value.
+    pub fn remove(&mut self, key: &K) -> Result<Option<V>, Error> {
+        Ok(self.map.remove(&crate::to_vec(key)?))
+    }
+}
+
+impl<K: Serialize + DeserializeOwned, V> TypedBTreeMap<K, V> {
+    /// Returns an iterator over the entries in key order.
+    pub fn iter(&self) -> impl Iterator<Item = Result<(K, &V), Error>> {
+        self.map.iter().map(decode_entry)
+    }
+
+    /// Returns an iterator over the entries within the range in key order.
+    ///
+    /// The range bounds are encoded as in [`KeyRange::encode`].
+    pub fn range<R>(&self, range: R) -> Result<impl Iterator<Item = Result<(K, &V), Error>>, Error>
+    where
+        R: RangeBounds<K>,
+    {
+        let range = KeyRange::new(range.start_bound(), range.end_bound()).encode()?;
+
+        Ok(self.byte_range(range).map(decode_entry))
+    }
+
+    /// Returns an iterator over the entries whose keys begin with the given
+    /// leading components in key order.
+    pub fn prefix<P>(
+        &self,
+        prefix: &P,
+    ) -> Result<impl Iterator<Item = Result<(K, &V), Error>>, Error>
+    where
+        P: ?Sized + Serialize,
+    {
+        let range = crate::range::prefix_range(&crate::to_prefix(prefix)?);
+
+        Ok(self.byte_range(range).map(decode_entry))
+    }
+}
+
+impl<K, V> Default for TypedBTreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V: Clone> Clone for TypedBTreeMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<K, V: std::fmt::Debug> std::fmt::Debug for TypedBTreeMap<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.map
+                    .iter()
+                    .map(|(key, value)| (String::from_utf8_lossy(key), value)),
+            )
+            .finish()
+    }
+}
+
+fn decode_entry<'a, K, V>((key, value): (&Vec<u8>, &'a V)) -> Result<(K, &'a V), Error>
+where
+    K: DeserializeOwned,
+{
+    Ok((crate::from_slice(key)?, value))
+}
+
+fn map_bound<K: AsRef<[u8]>>(bound: Bound<&K>) -> Bound<Vec<u8>> {
+    match bound {
+        Bound::Included(key) => Bound::Included(key.as_ref().to_vec()),
+        Bound::Excluded(key) => Bound::Excluded(key.as_ref().to_vec()),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn test_key_set() {
+        let mut set = KeySet::new();
+        let mut expected = BTreeSet::new();
+
+        assert!(set.is_empty());
+        assert!(!set.contains(b"a"));
+
+        for index in (0..500u32)
+            .rev()
+            .chain(0..500u32)
+            .map(|index| index * 7 % 300)
+        {
+            let key = crate::to_vec(&("item", index)).unwrap();
+            assert_eq!(set.insert(&key), expected.insert(key));
+        }
+
+        assert_eq!(set.len(), expected.len());
+        assert!(set.blocks.len() > 1);
+        assert!(set.iter().eq(expected.iter().cloned()));
+        assert!(expected.iter().all(|key| set.contains(key)));
+        assert!(!set.contains(b"item"));
+        assert!(!set.contains(b"item:0000012c"));
+        assert!(set
+            .blocks
+            .iter()
+            .all(|block| block.keys().len() == block.len && block.keys()[0] == block.first));
+
+        let mut set = KeySet::new();
+        let long = "x".repeat(200);
+
+        for key in ["b", "a", long.as_str(), "ab", "", "b"] {
+            set.insert(key.as_bytes());
+        }
+
+        assert!(set.iter().eq(["", "a", "ab", "b", long.as_str()]
+            .iter()
+            .map(|key| key.as_bytes().to_vec())));
+        assert!(set.contains(long.as_bytes()));
+        assert!(!set.contains(b"aa"));
+    }
+
+    #[test]
+    fn test_typed_btree_map() {
+        let mut map = TypedBTreeMap::<(String, u16), u32>::new();
+
+        for (tenant, id) in [("b", 1), ("a", 2), ("a", 1), ("c", 1)] {
+            map.insert(&(tenant.to_string(), id), u32::from(id) * 10)
+                .unwrap();
+        }
+
+        assert_eq!(map.len(), 4);
+        assert_eq!(map.insert(&("a".to_string(), 1), 11).unwrap(), Some(10));
+        assert_eq!(map.get(&("a".to_string(), 1)).unwrap(), Some(&11));
+        *map.get_mut(&("c".to_string(), 1)).unwrap().unwrap() += 1;
+        assert!(map.contains_key(&("c".to_string(), 1)).unwrap());
+        assert_eq!(map.remove(&("b".to_string(), 1)).unwrap(), Some(10));
+        assert!(!map.contains_key(&("b".to_string(), 1)).unwrap());
+
+        let entries = map.iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                (("a".to_string(), 1), &11),
+                (("a".to_string(), 2), &20),
+                (("c".to_string(), 1), &11)
+            ]
+        );
+
+        let keys = map
+            .prefix(&"a")
+            .unwrap()
+            .map(|entry| entry.unwrap().0 .1)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec![1, 2]);
+
+        let start = ("a".to_string(), 2);
+        let end = ("c".to_string(), 1);
+        assert_eq!(map.range(start.clone()..end.clone()).unwrap().count(), 1);
+        assert_eq!(map.range(start.clone()..=end.clone()).unwrap().count(), 2);
+        assert_eq!(map.range(end..start).unwrap().count(), 0);
+        assert_eq!(map.as_bytes_map().len(), 3);
+    }
+
+    #[test]
+    fn test_range() {
+        let set = (0..100u8)
+            .map(|index| crate::to_vec(&index).unwrap())
+            .collect::<KeySet>();
+        let key = |index: u8| crate::to_vec(&index).unwrap();
+
+        assert!(set.range(key(10)..key(20)).eq((10..20).map(key)));
+        assert!(set
+            .range((Bound::Excluded(key(90)), Bound::Unbounded))
+            .eq((91..100).map(key)));
+        assert!(set.range(..=key(3)).eq((0..=3).map(key)));
+        assert!(set.range(&b"z"[..]..).next().is_none());
+        assert_eq!(KeySet::new().range::<_, Vec<u8>>(..).count(), 0);
+    }
+}
@@ -0,0 +1,152 @@
+//! Merging sorted key streams
+//!
+//! Combines several iterators of encoded keys, each already sorted, into a
+//! single sorted iterator. Because encoded keys sort in the same order as
+//! their values, this can merge snapshots from several shards without
+//! decoding them first.
+//!
+//! Example:
+//!
+//! ```rust
+//! # fn main() -> Result<(), strkey::Error> {
+//! let shard1 = strkey::encode_sorted([1u32, 4, 5])?;
+//! let shard2 = strkey::encode_sorted([2u32, 3, 6])?;
+//!
+//! let values = strkey::merge::merge([shard1, shard2])
+//!     .decode::<u32>()
+//!     .collect::<Result<Vec<_>, _>>()?;
+//!
+//! assert_eq!(values, vec![1, 2, 3, 4, 5, 6]);
+//! # Ok(())
+//! # }
+//! ```
+use std::{cmp::Reverse, collections::BinaryHeap, iter::FusedIterator};
+
+use serde::de::DeserializeOwned;
+
+use crate::error::Error;
+
+/// Iterator returned by [`merge`].
+#[derive(Debug)]
+pub struct Merge<I> {
+    iters: Vec<I>,
+    heap: BinaryHeap<Reverse<(Vec<u8>, usize)>>,
+}
+
+impl<I> Merge<I>
+where
+    I: Iterator<Item = Vec<u8>>,
+{
+    /// Decodes each key into a value of the given type.
+    pub fn decode<T>(self) -> impl Iterator<Item = Result<T, Error>>
+    where
+        T: DeserializeOwned,
+    {
+        self.map(|key| crate::from_slice(&key))
+    }
+
+    fn fill(&mut self, index: usize) {
+        if let Some(key) = self.iters[index].next() {
+            self.heap.push(Reverse((key, index)));
+        }
+    }
+}
+
+impl<I> Iterator for Merge<I>
+where
+    I: Iterator<Item = Vec<u8>>,
+{
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((key, index)) = self.heap.pop()?;
+        self.fill(index);
+
+        Some(key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iters.iter().fold(
+            (self.heap.len(), Some(self.heap.len())),
+            |(lower, upper), iter| {
+                let (iter_lower, iter_upper) = iter.size_hint();
+                (
+                    lower.saturating_add(iter_lower),
+                    upper.zip(iter_upper).and_then(|(a, b)| a.checked_add(b)),
+                )
+            },
+        )
+    }
+}
+
+impl<I> FusedIterator for Merge<I> where I: FusedIterator<Item = Vec<u8>> {}
+
+/// Merges iterators of sorted keys into a single sorted iterator.
+///
+/// Each iterator must yield keys in ascending byte order. Duplicate keys are
+/// not removed; keys that are equal are yielded in the order of the
+/// iterators they came from, so a compaction job can keep the first of each
+/// run of equal keys to prefer the earliest iterator.
+pub fn merge<I>(iters: I) -> Merge<<I::Item as IntoIterator>::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator<Item = Vec<u8>>,
+{
+    let iters = iters
+        .into_iter()
+        .map(IntoIterator::into_iter)
+        .collect::<Vec<_>>();
+    let mut merge = Merge {
+        heap: BinaryHeap::with_capacity(iters.len()),
+        iters,
+    };
+
+    for index in 0..merge.iters.len() {
+        merge.fill(index);
+    }
+
+    merge
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let shard1 = crate::encode_sorted([("a", 1u8), ("b", 1)]).unwrap();
+        let shard2 = crate::encode_sorted([("a", 2u8), ("a", 1)]).unwrap();
+        let shard3 = Vec::new();
+
+        let merged = merge([shard1, shard2, shard3]);
+        assert_eq!(merged.size_hint(), (4, Some(4)));
+
+        let values = merged
+            .decode::<(String, u8)>()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            values,
+            vec![
+                ("a".to_string(), 1),
+                ("a".to_string(), 1),
+                ("a".to_string(), 2),
+                ("b".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_ties() {
+        let first = vec![b"a".to_vec(), b"b".to_vec()];
+        let second = vec![b"b".to_vec()];
+
+        let mut merged = merge([first.into_iter(), second.into_iter()]);
+
+        assert_eq!(merged.next(), Some(b"a".to_vec()));
+        assert_eq!(merged.next(), Some(b"b".to_vec()));
+        assert_eq!(merged.next(), Some(b"b".to_vec()));
+        assert_eq!(merged.next(), None);
+    }
+}
@@ -0,0 +1,166 @@
+//! Instrumentation
+//!
+//! The top-level encoding and decoding functions, such as [`crate::to_vec`]
+//! and [`crate::from_slice`], report each operation to an [`Observer`] set
+//! with [`set_observer`]. With the `tracing` feature, they also emit a
+//! `tracing` span and event with the type name, byte length, and error kind.
+//! The error message is not logged since it can contain key contents.
+//!
+//! Example:
+//!
+//! ```rust
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use strkey::metrics::{Observer, Operation};
+//!
+//! struct DecodeFailures(AtomicUsize);
+//!
+//! impl Observer for DecodeFailures {
+//!     fn on_error(&self, operation: Operation, _type_name: &'static str, _error: &strkey::Error) {
+//!         if operation == Operation::Decode {
+//!             self.0.fetch_add(1, Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//!
+//! static FAILURES: DecodeFailures = DecodeFailures(AtomicUsize::new(0));
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! strkey::metrics::set_observer(&FAILURES)?;
+//! assert!(strkey::from_slice::<u8>(b"xyz").is_err());
+//! assert_eq!(FAILURES.0.load(Ordering::Relaxed), 1);
+//! # Ok(())
+//! # }
+//! ```
+use std::sync::OnceLock;
+
+use crate::error::Error;
+
+/// Kind of operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Serializing a value into a key.
+    Encode,
+    /// Deserializing a value from a key.
+    Decode,
+}
+
+impl Operation {
+    /// Returns the name of the operation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Encode => "encode",
+            Operation::Decode => "decode",
+        }
+    }
+}
+
+/// Callbacks for encoding and decoding operations.
+///
+/// All methods do nothing by default.
+pub trait Observer: Send + Sync {
+    /// Called when an operation succeeds.
+    ///
+    /// The length is the length of the key in bytes if known.
+    fn on_success(&self, operation: Operation, type_name: &'static str, len: Option<usize>) {
+        let _ = (operation, type_name, len);
+    }
+
+    /// Called when an operation fails.
+    fn on_error(&self, operation: Operation, type_name: &'static str, error: &Error) {
+        let _ = (operation, type_name, error);
+    }
+}
+
+static OBSERVER: OnceLock<&'static dyn Observer> = OnceLock::new();
+
+/// Sets the global observer.
+///
+/// The observer can only be set once. Returns an error if it was already set.
+pub fn set_observer(observer: &'static dyn Observer) -> Result<(), Error> {
+    OBSERVER
+        .set(observer)
+        .map_err(|_| Error::Other("observer already set".to_string()))
+}
+
+/// Returns a short name for the kind of error.
+pub fn error_kind(error: &Error) -> &'static str {
+    match error {
+        Error::UnsupportedType => "unsupported_type",
+        Error::Utf8StringDecode(_) | Error::ComponentUtf8(..) => "utf8",
+        Error::Data(_) => "data",
+        Error::Hex(..) => "hex",
+        Error::WrongWidth(..) => "wrong_width",
+        Error::Syntax => "syntax",
+        Error::KeyTooLong(..) => "key_too_long",
+        Error::KeyTooShort(..) => "key_too_short",
+        Error::RecursionLimitExceeded(_) => "recursion_limit",
+        Error::NoLayoutMatched(_) => "no_layout_matched",
+        Error::TenantMismatch(_) => "tenant_mismatch",
+        Error::Config(_) => "config",
+        Error::Io(_) => "io",
+        #[cfg(feature = "bytekey")]
+        Error::Bytekey(_) => "bytekey",
+        #[cfg(feature = "csv")]
+        Error::Csv(_) => "csv",
+        #[cfg(feature = "serde_json")]
+        Error::Json(_) => "json",
+        Error::Other(_) => "other",
+    }
+}
+
+/// Runs the operation and reports its result.
+///
+/// The operation returns its output and the key length if known.
+pub(crate) fn observe<T, R, F>(operation: Operation, f: F) -> Result<R, Error>
+where
+    T: ?Sized,
+    F: FnOnce() -> Result<(R, Option<usize>), Error>,
+{
+    let type_name = std::any::type_name::<T>();
+
+    #[cfg(feature = "tracing")]
+    let _span = tracing::trace_span!("strkey", operation = operation.as_str(), type_name).entered();
+
+    match f() {
+        Ok((output, len)) => {
+            #[cfg(feature = "tracing")]
+            tracing::trace!(operation = operation.as_str(), type_name, len, "ok");
+
+            if let Some(observer) = OBSERVER.get() {
+                observer.on_success(operation, type_name, len);
+            }
+
+            Ok(output)
+        }
+        Err(error) => {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                operation = operation.as_str(),
+                type_name,
+                error_kind = error_kind(&error),
+                "error"
+            );
+
+            if let Some(observer) = OBSERVER.get() {
+                observer.on_error(operation, type_name, &error);
+            }
+
+            Err(error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe() {
+        let output = observe::<u8, _, _>(Operation::Encode, || Ok((5, Some(2)))).unwrap();
+        assert_eq!(output, 5);
+
+        let error = observe::<u8, u8, _>(Operation::Decode, || Err(Error::Syntax)).unwrap_err();
+        assert_eq!(error_kind(&error), "syntax");
+        assert_eq!(Operation::Decode.as_str(), "decode");
+    }
+}
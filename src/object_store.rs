@@ -0,0 +1,106 @@
+//! Object store keys
+//!
+//! Object stores such as S3 treat keys as paths separated by `/`. The
+//! functions in this module serialize values using a profile suited for them:
+//!
+//! * Values are separated by `/`.
+//! * String components are percent-encoded (see [`Serializer::set_percent_encoding`]).
+//! * Keys longer than [`MAX_KEY_LENGTH`] bytes are rejected.
+//!
+//! Example:
+//!
+//! ```rust
+//! # fn main() -> Result<(), strkey::Error> {
+//! let key = strkey::object_store::to_object_key(&("logs", "web/1", 2021u16))?;
+//! assert_eq!(key, "logs/web%2F1/07e5");
+//!
+//! let prefix = strkey::object_store::list_prefix(&("logs", "web/1"))?;
+//! assert_eq!(prefix, "logs/web%2F1/");
+//! # Ok(())
+//! # }
+//! ```
+use serde::Serialize;
+
+use crate::{error::Error, ser::Serializer};
+
+/// Maximum length of an object key in bytes.
+pub const MAX_KEY_LENGTH: usize = 1024;
+
+/// Deliminator used to separate values.
+pub const DELIMINATOR: &str = "/";
+
+/// Constructs a serializer using the object store profile.
+pub fn serializer<W: std::io::Write>(writer: W) -> Serializer<W> {
+    Serializer::new(writer)
+        .with_deliminator(DELIMINATOR)
+        .with_percent_encoding(true)
+}
+
+/// Serializes the given value to an object key.
+pub fn to_object_key<T>(value: &T) -> Result<String, Error>
+where
+    T: ?Sized + Serialize,
+{
+    let mut buffer = Vec::new();
+    value.serialize(&mut serializer(&mut buffer))?;
+
+    if buffer.len() > MAX_KEY_LENGTH {
+        return Err(Error::KeyTooLong(buffer.len(), MAX_KEY_LENGTH));
+    }
+
+    Ok(String::from_utf8(buffer).map_err(|error| error.utf8_error())?)
+}
+
+/// Serializes the given prefix of a key to a prefix for listing objects.
+///
+/// The returned prefix ends with the deliminator so that only objects
+/// whose leading values are equal to the given values are matched. An empty
+/// prefix, such as the unit type, returns an empty string which matches all
+/// objects.
+pub fn list_prefix<T>(prefix: &T) -> Result<String, Error>
+where
+    T: ?Sized + Serialize,
+{
+    let mut key = to_object_key(prefix)?;
+
+    if !key.is_empty() {
+        key.push_str(DELIMINATOR);
+
+        if key.len() > MAX_KEY_LENGTH {
+            return Err(Error::KeyTooLong(key.len(), MAX_KEY_LENGTH));
+        }
+    }
+
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_object_key() {
+        let key = to_object_key(&("data lake", 1u8, "a&b")).unwrap();
+        assert_eq!(key, "data%20lake/01/a%26b");
+    }
+
+    #[test]
+    fn test_max_length() {
+        let value = "a".repeat(MAX_KEY_LENGTH);
+        assert!(to_object_key(&value).is_ok());
+        assert!(list_prefix(&value).is_err());
+
+        let value = "a".repeat(MAX_KEY_LENGTH + 1);
+        assert!(matches!(
+            to_object_key(&value),
+            Err(Error::KeyTooLong(1025, 1024))
+        ));
+    }
+
+    #[test]
+    fn test_list_prefix() {
+        assert_eq!(list_prefix(&()).unwrap(), "");
+        assert_eq!(list_prefix(&"events").unwrap(), "events/");
+        assert_eq!(list_prefix(&("events", 3u8)).unwrap(), "events/03/");
+    }
+}
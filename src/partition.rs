@@ -0,0 +1,152 @@
+//! Partition assignment
+//!
+//! Assigns encoded keys to a fixed number of partitions, either by hash,
+//! which spreads keys evenly regardless of their order, or by range, which
+//! keeps neighbouring keys in the same partition so that each partition can
+//! be scanned. Both work on the encoded bytes so that any system producing
+//! the same keys assigns them to the same partitions.
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::partition::{partition_of, partition_ranges, range_partition_of};
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let key = strkey::to_vec(&("events", 42u32))?;
+//! assert!(partition_of(&key, 8) < 8);
+//!
+//! let ranges = partition_ranges(&"events", 0u32..=99, 4)?;
+//! assert_eq!(ranges.len(), 4);
+//!
+//! let splits = strkey::split::split_keys(&"events", 0u32..=99, 4)?;
+//! assert_eq!(range_partition_of(&key, &splits), 1);
+//! # Ok(())
+//! # }
+//! ```
+use std::ops::{Bound, RangeBounds};
+
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    range::{prefix_range, ByteRange},
+    shard::fnv1a_64,
+    split::{split_keys, Bisect},
+};
+
+/// Returns the hash partition of the encoded key.
+///
+/// The partition is [`crate::key::stable_hash64`] of the key modulo the
+/// number of partitions.
+///
+/// Panics if `partitions` is zero.
+pub fn partition_of<K>(key: &K, partitions: u32) -> u32
+where
+    K: ?Sized + AsRef<[u8]>,
+{
+    assert!(partitions > 0, "number of partitions must not be zero");
+
+    (fnv1a_64(key.as_ref()) % partitions as u64) as u32
+}
+
+/// Returns the range partition of the encoded key given the split keys.
+///
+/// The split keys must be sorted, such as those returned by
+/// [`crate::split::split_keys`]. A key equal to a split key belongs to the
+/// partition that the split key starts.
+pub fn range_partition_of<K, S>(key: &K, splits: &[S]) -> usize
+where
+    K: ?Sized + AsRef<[u8]>,
+    S: AsRef<[u8]>,
+{
+    let key = key.as_ref();
+
+    splits.partition_point(|split| split.as_ref() <= key)
+}
+
+/// Returns the key ranges of up to `partitions` range partitions of the keys
+/// with the given leading components.
+///
+/// The boundaries between partitions are the split keys of
+/// [`crate::split::split_keys`] over the integer component's range. The
+/// first and last ranges extend to the start and end of the prefix, so
+/// together the ranges cover every key with the prefix without overlapping,
+/// and [`range_partition_of`] agrees with them. Fewer ranges are returned if
+/// the integer range is too small.
+///
+/// Panics if `partitions` is zero.
+pub fn partition_ranges<P, V, R>(
+    prefix: &P,
+    range: R,
+    partitions: usize,
+) -> Result<Vec<ByteRange>, Error>
+where
+    P: ?Sized + Serialize,
+    V: Bisect,
+    R: RangeBounds<V>,
+{
+    assert!(partitions > 0, "number of partitions must not be zero");
+
+    let (start, end) = prefix_range(&crate::to_prefix(prefix)?);
+    let splits = split_keys(prefix, range, partitions)?;
+    let mut ranges = Vec::with_capacity(splits.len() + 1);
+    let mut current = start;
+
+    for split in splits {
+        ranges.push((current, Bound::Excluded(split.clone())));
+        current = Bound::Included(split);
+    }
+
+    ranges.push((current, end));
+
+    Ok(ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_of() {
+        let mut counts = [0; 4];
+
+        for value in 0..1000u32 {
+            let key = crate::to_vec(&("user", value)).unwrap();
+            let partition = partition_of(&key, 4);
+
+            assert_eq!(
+                partition as u64,
+                crate::key::stable_hash64(&("user", value)).unwrap() % 4
+            );
+            counts[partition as usize] += 1;
+        }
+
+        assert!(counts.iter().all(|count| *count > 150));
+        assert_eq!(partition_of(b"anything", 1), 0);
+    }
+
+    #[test]
+    fn test_partition_ranges() {
+        let ranges = partition_ranges(&"a", 0u8..=255, 4).unwrap();
+        let splits = split_keys(&"a", 0u8..=255, 4).unwrap();
+
+        assert_eq!(ranges.len(), 4);
+        assert_eq!(ranges[0].0, Bound::Included(b"a:".to_vec()));
+        assert_eq!(ranges[0].1, Bound::Excluded(b"a:40".to_vec()));
+        assert_eq!(ranges[3].1, Bound::Excluded(b"a;".to_vec()));
+
+        for value in 0..=255u8 {
+            let key = crate::to_vec(&("a", value, "x")).unwrap();
+            let matching = ranges
+                .iter()
+                .enumerate()
+                .filter(|(_, range)| range.contains(&key))
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>();
+
+            assert_eq!(matching, vec![range_partition_of(&key, &splits)]);
+        }
+
+        assert_eq!(partition_ranges(&"a", 0u8..=0, 4).unwrap().len(), 1);
+    }
+}
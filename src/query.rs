@@ -0,0 +1,415 @@
+//! Range queries over struct fields
+//!
+//! A builder that produces scan bounds for keys of a struct type by
+//! constraining its fields in declaration order. Leading fields are
+//! constrained to equal a value and at most one following field to a range,
+//! which is the leftmost prefix rule of sorted keys: a range on a field
+//! makes the order of the fields after it unusable for the scan.
+//!
+//! The field names are taken from the type's derived `Deserialize`
+//! implementation and are used to check the number of constraints. Each
+//! value is also checked against the type of its field by decoding it, so a
+//! value of the wrong type, such as a `u8` for a `u32` field, is rejected
+//! instead of producing bounds that match no keys. Fields whose layout
+//! cannot be determined, such as a sequence and the fields after it, are not
+//! checked.
+//!
+//! Example:
+//!
+//! ```rust
+//! use std::ops::RangeBounds;
+//!
+//! use serde::{Deserialize, Serialize};
+//! use strkey::query::Query;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct AccountKey {
+//!     tenant: String,
+//!     timestamp: u32,
+//!     id: u32,
+//! }
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let range = Query::on::<AccountKey>()
+//!     .eq("acme")
+//!     .between(100u32, 200u32)
+//!     .encode()?;
+//!
+//! let key = strkey::to_vec(&AccountKey {
+//!     tenant: "acme".to_string(),
+//!     timestamp: 150,
+//!     id: 1,
+//! })?;
+//! assert!(range.contains(&key));
+//! # Ok(())
+//! # }
+//! ```
+use std::ops::{Bound, RangeBounds};
+
+use serde::{de::DeserializeOwned, de::Visitor, Serialize};
+
+use crate::{
+    error::Error,
+    range::{prefix_range, prefix_successor, ByteRange, KeyRange},
+    value::{FieldType, Schema},
+};
+
+/// Builder of scan bounds for keys of a type.
+#[derive(Debug)]
+pub struct Query {
+    fields: Option<&'static [&'static str]>,
+    layouts: Vec<Vec<FieldType>>,
+    prefix: Vec<u8>,
+    eq_count: usize,
+    range: Option<ByteRange>,
+    error: Option<Error>,
+}
+
+impl Query {
+    /// Construct a query on the given type.
+    ///
+    /// If the type is a struct, the number of constraints is limited to the
+    /// number of its fields.
+    pub fn on<T: DeserializeOwned>() -> Self {
+        Self {
+            fields: struct_fields::<T>(),
+            layouts: crate::value::probe_fields::<T>().unwrap_or_default(),
+            prefix: Vec::new(),
+            eq_count: 0,
+            range: None,
+            error: None,
+        }
+    }
+
+    /// Returns the field names of the type, if it is a struct.
+    pub fn fields(&self) -> Option<&'static [&'static str]> {
+        self.fields
+    }
+
+    /// Returns the name of the next field to be constrained, if known.
+    pub fn next_field(&self) -> Option<&'static str> {
+        self.fields?.get(self.eq_count).copied()
+    }
+
+    /// Constrains the next field to equal the value.
+    pub fn eq<V: Serialize>(mut self, value: V) -> Self {
+        if self.check_next("eq") {
+            match crate::to_vec(&value).and_then(|encoded| {
+                self.check_value(&encoded)?;
+                Ok(encoded)
+            }) {
+                Ok(encoded) => {
+                    if self.eq_count != 0 {
+                        self.prefix.push(b':');
+                    }
+                    self.prefix.extend_from_slice(&encoded);
+                    self.eq_count += 1;
+                }
+                Err(error) => self.error = Some(error),
+            }
+        }
+
+        self
+    }
+
+    /// Constrains the next field to be between the values inclusive.
+    pub fn between<V: Serialize>(self, start: V, end: V) -> Self {
+        self.range_bounds(KeyRange::new(Bound::Included(start), Bound::Included(end)))
+    }
+
+    /// Constrains the next field to be in the range.
+    pub fn range<V, R>(self, range: R) -> Self
+    where
+        V: Serialize + Clone,
+        R: RangeBounds<V>,
+    {
+        self.range_bounds(KeyRange::from_range(&range))
+    }
+
+    /// Returns the range of encoded keys matching the constraints.
+    ///
+    /// Returns [`Error::Other`] if the constraints are invalid, such as a
+    /// constraint following a range, and the encoding or decoding error if
+    /// a value could not be encoded or does not have the type of its field.
+    pub fn encode(self) -> Result<ByteRange, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        if let Some(range) = self.range {
+            return Ok(range);
+        }
+
+        if self.eq_count == 0 {
+            return Ok((Bound::Unbounded, Bound::Unbounded));
+        }
+
+        if self.fields.map(<[_]>::len) == Some(self.eq_count) {
+            return Ok((
+                Bound::Included(self.prefix.clone()),
+                Bound::Included(self.prefix.clone()),
+            ));
+        }
+
+        let mut prefix = self.prefix.clone();
+        prefix.push(b':');
+
+        Ok(prefix_range(&prefix))
+    }
+
+    fn range_bounds<V: Serialize>(mut self, range: KeyRange<V>) -> Self {
+        if !self.check_next("range") {
+            return self;
+        }
+
+        let checked = [range.start(), range.end()]
+            .iter()
+            .try_for_each(|bound| match bound {
+                Bound::Included(value) | Bound::Excluded(value) => {
+                    self.check_value(&crate::to_vec(value)?)
+                }
+                Bound::Unbounded => Ok(()),
+            });
+
+        let (start, end) = match checked.and_then(|_| range.encode()) {
+            Ok(range) => range,
+            Err(error) => {
+                self.error = Some(error);
+                return self;
+            }
+        };
+
+        if self.eq_count == 0 {
+            self.range = Some((start, end));
+            return self;
+        }
+
+        let mut prefix = self.prefix.clone();
+        prefix.push(b':');
+
+        let with_prefix = |bound: Vec<u8>| {
+            let mut key = prefix.clone();
+            key.extend_from_slice(&bound);
+            key
+        };
+
+        let start = match start {
+            Bound::Included(bound) => Bound::Included(with_prefix(bound)),
+            Bound::Excluded(bound) => Bound::Excluded(with_prefix(bound)),
+            Bound::Unbounded => Bound::Included(prefix.clone()),
+        };
+        let end = match end {
+            Bound::Included(bound) => Bound::Included(with_prefix(bound)),
+            Bound::Excluded(bound) => Bound::Excluded(with_prefix(bound)),
+            Bound::Unbounded => match prefix_successor(&prefix) {
+                Some(successor) => Bound::Excluded(successor),
+                None => Bound::Unbounded,
+            },
+        };
+
+        self.range = Some((start, end));
+        self
+    }
+
+    fn check_next(&mut self, operation: &str) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+
+        if self.range.is_some() {
+            self.error = Some(Error::Other(format!("{} cannot follow a range", operation)));
+            return false;
+        }
+
+        if let Some(fields) = self.fields {
+            if self.eq_count >= fields.len() {
+                self.error = Some(Error::Other(format!(
+                    "{} exceeds the {} fields of the key",
+                    operation,
+                    fields.len()
+                )));
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Checks that the encoded value decodes as the next field.
+    fn check_value(&self, encoded: &[u8]) -> Result<(), Error> {
+        let layout = match self.layouts.get(self.eq_count) {
+            Some(layout) => layout,
+            None => return Ok(()),
+        };
+
+        // An empty key has no components, but is also the encoding of an
+        // empty string or byte array.
+        if encoded.is_empty() {
+            return match layout.as_slice() {
+                [] | [FieldType::Str] | [FieldType::Bytes] => Ok(()),
+                _ => Err(Error::Syntax),
+            };
+        }
+
+        crate::value::decode_with_schema(encoded, &Schema::new(layout.clone()))?;
+
+        Ok(())
+    }
+}
+
+fn struct_fields<T: DeserializeOwned>() -> Option<&'static [&'static str]> {
+    let mut fields = None;
+    let _ = T::deserialize(FieldsDeserializer {
+        fields: &mut fields,
+    });
+
+    fields
+}
+
+struct FieldsDeserializer<'a> {
+    fields: &'a mut Option<&'static [&'static str]>,
+}
+
+impl<'de, 'a> serde::Deserializer<'de> for FieldsDeserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(Error::UnsupportedType)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.fields = Some(fields);
+        Err(Error::UnsupportedType)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct AccountKey {
+        tenant: String,
+        timestamp: u32,
+        id: u8,
+    }
+
+    fn key(tenant: &str, timestamp: u32, id: u8) -> Vec<u8> {
+        crate::to_vec(&AccountKey {
+            tenant: tenant.to_string(),
+            timestamp,
+            id,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fields() {
+        let query = Query::on::<AccountKey>();
+
+        assert_eq!(query.fields(), Some(&["tenant", "timestamp", "id"][..]));
+        assert_eq!(query.eq("a").next_field(), Some("timestamp"));
+        assert_eq!(Query::on::<(u8, u8)>().fields(), None);
+    }
+
+    #[test]
+    fn test_eq() {
+        let range = Query::on::<AccountKey>().eq("a").encode().unwrap();
+
+        assert!(range.contains(&key("a", 1, 1)));
+        assert!(!range.contains(&key("ab", 1, 1)));
+
+        let range = Query::on::<AccountKey>()
+            .eq("a")
+            .eq(1u32)
+            .eq(2u8)
+            .encode()
+            .unwrap();
+
+        assert!(range.contains(&key("a", 1, 2)));
+        assert!(!range.contains(&key("a", 1, 3)));
+    }
+
+    #[test]
+    fn test_range() {
+        let range = Query::on::<AccountKey>()
+            .eq("a")
+            .between(10u32, 20u32)
+            .encode()
+            .unwrap();
+
+        assert!(!range.contains(&key("a", 9, 255)));
+        assert!(range.contains(&key("a", 10, 0)));
+        assert!(range.contains(&key("a", 20, 255)));
+        assert!(!range.contains(&key("a", 21, 0)));
+        assert!(!range.contains(&key("b", 15, 0)));
+
+        let range = Query::on::<AccountKey>()
+            .eq("a")
+            .range(10u32..)
+            .encode()
+            .unwrap();
+
+        assert!(range.contains(&key("a", u32::MAX, 255)));
+        assert!(!range.contains(&key("b", 0, 0)));
+
+        let range = Query::on::<AccountKey>().range(.."b").encode().unwrap();
+
+        assert!(range.contains(&key("a", 1, 1)));
+        assert!(!range.contains(&key("b", 1, 1)));
+    }
+
+    #[test]
+    fn test_types() {
+        assert!(matches!(
+            Query::on::<AccountKey>().eq("a").eq(1u8).encode(),
+            Err(Error::WrongWidth(0, 1, 4))
+        ));
+        assert!(matches!(
+            Query::on::<AccountKey>().eq("a").between(1u8, 2u8).encode(),
+            Err(Error::WrongWidth(..))
+        ));
+        assert!(Query::on::<AccountKey>().eq(1u32).encode().is_ok());
+        assert!(Query::on::<AccountKey>().eq("").encode().is_ok());
+        assert!(Query::on::<AccountKey>()
+            .eq("a")
+            .range(..1u32)
+            .encode()
+            .is_ok());
+        assert!(Query::on::<AccountKey>()
+            .eq("a")
+            .eq(1u32)
+            .eq("")
+            .encode()
+            .is_err());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Query::on::<AccountKey>()
+            .between(1u32, 2u32)
+            .eq(1u8)
+            .encode()
+            .is_err());
+        assert!(Query::on::<AccountKey>()
+            .eq("a")
+            .eq(1u32)
+            .eq(1u8)
+            .eq(1u8)
+            .encode()
+            .is_err());
+    }
+}
@@ -0,0 +1,219 @@
+//! Key ranges
+//!
+//! Helpers for computing ranges of encoded keys. Ranges are returned as a
+//! pair of [`Bound`]s which implements [`std::ops::RangeBounds`] and can be
+//! passed to functions such as [`std::collections::BTreeMap::range`].
+//!
+//! Example:
+//!
+//! ```rust
+//! use std::collections::BTreeMap;
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let mut map = BTreeMap::new();
+//! map.insert(strkey::to_vec(&("account", 1u8))?, "a");
+//! map.insert(strkey::to_vec(&("accounts", 2u8))?, "b");
+//! map.insert(strkey::to_vec(&("user", 3u8))?, "c");
+//!
+//! let prefix = strkey::to_prefix(&"account")?;
+//! let values = map
+//!     .range(strkey::range::prefix_range(&prefix))
+//!     .map(|(_key, value)| *value)
+//!     .collect::<Vec<_>>();
+//!
+//! assert_eq!(values, vec!["a"]);
+//! # Ok(())
+//! # }
+//! ```
+use std::ops::{Bound, RangeBounds};
+
+use serde::Serialize;
+
+use crate::error::Error;
+
+/// Range of encoded keys as a pair of start and end bounds.
+pub type ByteRange = (Bound<Vec<u8>>, Bound<Vec<u8>>);
+
+/// Returns the smallest byte string greater than every byte string that
+/// begins with the given prefix.
+///
+/// Returns `None` if there is no such byte string, which occurs when the
+/// prefix is empty or consists only of `0xff` bytes.
+pub fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let index = prefix.iter().rposition(|&byte| byte != 0xff)?;
+    let mut successor = prefix[..=index].to_vec();
+    successor[index] += 1;
+
+    Some(successor)
+}
+
+/// Returns the range of byte strings that begin with the given prefix.
+pub fn prefix_range(prefix: &[u8]) -> ByteRange {
+    let end = match prefix_successor(prefix) {
+        Some(successor) => Bound::Excluded(successor),
+        None => Bound::Unbounded,
+    };
+
+    (Bound::Included(prefix.to_vec()), end)
+}
+
+/// Range of keys with typed start and end bounds.
+///
+/// The bounds apply to the leading components of keys, so a key is in the
+/// range if its leading components are in the range, regardless of any
+/// components that follow. An included end bound includes every key that
+/// begins with the bound's value.
+///
+/// Example:
+///
+/// ```rust
+/// use std::ops::RangeBounds;
+///
+/// use strkey::range::KeyRange;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let range = KeyRange::from_range(&(1u8..=2)).encode()?;
+///
+/// assert!(range.contains(&strkey::to_vec(&(1u8, "a"))?));
+/// assert!(range.contains(&strkey::to_vec(&(2u8, "b"))?));
+/// assert!(!range.contains(&strkey::to_vec(&(3u8, "a"))?));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyRange<T> {
+    start: Bound<T>,
+    end: Bound<T>,
+}
+
+impl<T> KeyRange<T> {
+    /// Construct a range with the given bounds.
+    pub fn new(start: Bound<T>, end: Bound<T>) -> Self {
+        Self { start, end }
+    }
+
+    /// Construct a range from a standard range such as `a..b`.
+    pub fn from_range<R>(range: &R) -> Self
+    where
+        R: RangeBounds<T>,
+        T: Clone,
+    {
+        Self::new(range.start_bound().cloned(), range.end_bound().cloned())
+    }
+
+    /// Returns the start bound.
+    pub fn start(&self) -> Bound<&T> {
+        self.start.as_ref()
+    }
+
+    /// Returns the end bound.
+    pub fn end(&self) -> Bound<&T> {
+        self.end.as_ref()
+    }
+}
+
+impl<T: Serialize> KeyRange<T> {
+    /// Returns the range of encoded keys.
+    ///
+    /// Excluded start bounds and included end bounds are converted to the
+    /// successor of the bound's prefix so that keys with additional
+    /// components are excluded or included respectively. Returns
+    /// [`Error::Data`] for an excluded start bound whose value encodes to an
+    /// empty key, as it would exclude every key.
+    pub fn encode(&self) -> Result<ByteRange, Error> {
+        let start = match &self.start {
+            Bound::Included(value) => Bound::Included(crate::to_vec(value)?),
+            Bound::Excluded(value) => match prefix_successor(&crate::to_prefix(value)?) {
+                Some(successor) => Bound::Included(successor),
+                None => {
+                    return Err(Error::Data(
+                        "excluded start bound encodes to an empty key".to_string(),
+                    ))
+                }
+            },
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        let end = match &self.end {
+            Bound::Included(value) => match prefix_successor(&crate::to_prefix(value)?) {
+                Some(successor) => Bound::Excluded(successor),
+                None => Bound::Unbounded,
+            },
+            Bound::Excluded(value) => Bound::Excluded(crate::to_vec(value)?),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        Ok((start, end))
+    }
+}
+
+impl<T> RangeBounds<T> for KeyRange<T> {
+    fn start_bound(&self) -> Bound<&T> {
+        self.start()
+    }
+
+    fn end_bound(&self) -> Bound<&T> {
+        self.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeBounds;
+
+    use super::*;
+
+    #[test]
+    fn test_prefix_successor() {
+        assert_eq!(prefix_successor(b"abc"), Some(b"abd".to_vec()));
+        assert_eq!(prefix_successor(b"ab\xff"), Some(b"ac".to_vec()));
+        assert_eq!(prefix_successor(b"a:"), Some(b"a;".to_vec()));
+        assert_eq!(prefix_successor(b"\xff\xff"), None);
+        assert_eq!(prefix_successor(b""), None);
+    }
+
+    #[test]
+    fn test_prefix_range() {
+        let range = prefix_range(b"ab:");
+
+        assert!(range.contains(&b"ab:".to_vec()));
+        assert!(range.contains(&b"ab:cd".to_vec()));
+        assert!(!range.contains(&b"ab".to_vec()));
+        assert!(!range.contains(&b"abc".to_vec()));
+        assert!(!range.contains(&b"ab;".to_vec()));
+
+        let range = prefix_range(b"");
+        assert_eq!(range.1, Bound::Unbounded);
+        assert!(range.contains(&b"anything".to_vec()));
+    }
+
+    #[test]
+    fn test_key_range() {
+        let key = |value: u8, suffix: &str| crate::to_vec(&(value, suffix)).unwrap();
+
+        let range = KeyRange::new(Bound::Excluded(1u8), Bound::Included(3u8))
+            .encode()
+            .unwrap();
+        assert!(!range.contains(&key(1, "")));
+        assert!(!range.contains(&key(1, "zz")));
+        assert!(range.contains(&key(2, "")));
+        assert!(range.contains(&key(3, "zz")));
+        assert!(!range.contains(&key(4, "")));
+
+        let range = KeyRange::new(Bound::Included(1u8), Bound::Excluded(3u8))
+            .encode()
+            .unwrap();
+        assert!(range.contains(&key(1, "")));
+        assert!(range.contains(&key(2, "zz")));
+        assert!(!range.contains(&key(3, "")));
+
+        let range = KeyRange::<u8>::from_range(&(..)).encode().unwrap();
+        assert_eq!(range, (Bound::Unbounded, Bound::Unbounded));
+
+        let range = KeyRange::from_range(&(..=())).encode().unwrap();
+        assert_eq!(range.1, Bound::Unbounded);
+        assert!(KeyRange::new(Bound::Excluded(()), Bound::Unbounded)
+            .encode()
+            .is_err());
+    }
+}
@@ -0,0 +1,211 @@
+//! Sorted runs
+//!
+//! A sorted run stores key-value entries in key order with each key
+//! prefix-compressed against the key before it, as in [`crate::compress`].
+//! Each entry is the LEB128 length of the compressed key, the compressed
+//! key, the LEB128 length of the value, and the value. The format is
+//! intended for spill files and backups of strkey-keyed data.
+//!
+//! Example:
+//!
+//! ```rust
+//! # fn main() -> Result<(), strkey::Error> {
+//! let entries = vec![
+//!     (strkey::to_vec(&("user", 1u8))?, b"alice".to_vec()),
+//!     (strkey::to_vec(&("user", 2u8))?, b"bob".to_vec()),
+//! ];
+//! let mut output = Vec::new();
+//! strkey::runs::write_run(&mut output, entries.iter().cloned())?;
+//!
+//! let read = strkey::runs::read_run(output.as_slice()).collect::<Result<Vec<_>, _>>()?;
+//! assert_eq!(read, entries);
+//! # Ok(())
+//! # }
+//! ```
+use std::io::{BufRead, Read, Write};
+
+use crate::{
+    compress::{write_varint, PrefixCompressor, PrefixDecompressor},
+    error::Error,
+};
+
+/// Key and value of an entry.
+pub type Entry = (Vec<u8>, Vec<u8>);
+
+/// Writes the key-value entries as a sorted run.
+///
+/// Returns [`Error::Data`] if a key sorts before the key preceding it.
+pub fn write_run<W, I, K, V>(mut writer: W, entries: I) -> Result<(), Error>
+where
+    W: Write,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+{
+    let mut compressor = PrefixCompressor::new();
+    let mut buffer = Vec::new();
+
+    for (index, (key, value)) in entries.into_iter().enumerate() {
+        let key = key.as_ref();
+        let value = value.as_ref();
+
+        if index > 0 && key < compressor.previous() {
+            return Err(Error::Data(String::from_utf8_lossy(key).into_owned()));
+        }
+
+        let compressed = compressor.compress(key);
+
+        buffer.clear();
+        write_varint(compressed.len() as u64, &mut buffer);
+        buffer.extend_from_slice(&compressed);
+        write_varint(value.len() as u64, &mut buffer);
+        buffer.extend_from_slice(value);
+
+        writer.write_all(&buffer)?;
+    }
+
+    Ok(())
+}
+
+/// Returns an iterator of the key-value entries of the sorted run in the reader.
+pub fn read_run<R: BufRead>(reader: R) -> RunIter<R> {
+    RunIter {
+        reader,
+        decompressor: PrefixDecompressor::new(),
+        buffer: Vec::new(),
+        done: false,
+    }
+}
+
+/// Iterator over key-value entries returned by [`read_run`].
+pub struct RunIter<R> {
+    reader: R,
+    decompressor: PrefixDecompressor,
+    buffer: Vec<u8>,
+    done: bool,
+}
+
+impl<R: BufRead> RunIter<R> {
+    fn read_entry(&mut self) -> Result<Option<Entry>, Error> {
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+
+        self.read_field()?;
+        let key = self.decompressor.decompress(&self.buffer)?;
+        self.read_field()?;
+
+        Ok(Some((key, std::mem::take(&mut self.buffer))))
+    }
+
+    fn read_field(&mut self) -> Result<(), Error> {
+        let len = self.read_varint()?;
+
+        self.buffer.clear();
+        (&mut self.reader).take(len).read_to_end(&mut self.buffer)?;
+
+        if (self.buffer.len() as u64) < len {
+            return Err(Error::Syntax);
+        }
+
+        Ok(())
+    }
+
+    fn read_varint(&mut self) -> Result<u64, Error> {
+        let mut value = 0u64;
+
+        for index in 0..10 {
+            let mut byte = [0u8];
+
+            if self.reader.read(&mut byte)? == 0 {
+                return Err(Error::Syntax);
+            }
+
+            let bits = u64::from(byte[0] & 0x7f);
+
+            if index == 9 && bits > 1 {
+                return Err(Error::Syntax);
+            }
+
+            value |= bits << (7 * index);
+
+            if byte[0] & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(Error::Syntax)
+    }
+}
+
+impl<R: BufRead> Iterator for RunIter<R> {
+    type Item = Result<Entry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let result = self.read_entry().transpose();
+
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+
+        result
+    }
+}
+
+impl<R: BufRead> std::iter::FusedIterator for RunIter<R> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let entries: Vec<(&[u8], &[u8])> = vec![
+            (b"a:01", b""),
+            (b"a:02", b"x"),
+            (b"a:02", b"y"),
+            (b"b", b"z"),
+        ];
+        let mut output = Vec::new();
+        write_run(&mut output, entries.clone()).unwrap();
+
+        assert_eq!(&output[..7], b"\x05\x00a:01\x00");
+        assert_eq!(&output[7..11], b"\x02\x032\x01");
+
+        let read = read_run(output.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let expected = entries
+            .iter()
+            .map(|(key, value)| (key.to_vec(), value.to_vec()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(read, expected);
+        assert_eq!(read_run(&b""[..]).count(), 0);
+    }
+
+    #[test]
+    fn test_unsorted() {
+        let mut output = Vec::new();
+
+        assert!(matches!(
+            write_run(&mut output, vec![(b"b", b""), (b"a", b"")]),
+            Err(Error::Data(_))
+        ));
+    }
+
+    #[test]
+    fn test_truncated() {
+        let mut output = Vec::new();
+        write_run(&mut output, vec![(b"abc", b"def")]).unwrap();
+        output.pop();
+
+        let mut iter = read_run(output.as_slice());
+        assert!(matches!(iter.next(), Some(Err(Error::Syntax))));
+        assert!(iter.next().is_none());
+    }
+}
@@ -2,29 +2,43 @@
 //!
 //! ## Details
 //!
-//! Most data types in the [Serde data model](https://serde.rs/data-model.html) are supported. The encoding consists of each encoded value separated by a deliminator (a colon `:` by default). Note that the encoding is not self-describing:
+//! Most data types in the [Serde data model](https://serde.rs/data-model.html) are supported. The encoding consists of each encoded value separated by a deliminator (a colon `:` by default). The deliminator can be any byte sequence. A NUL byte (`\0`) sorts before every other byte, so a string component followed by other components sorts before longer strings that start with it. Note that the encoding is not self-describing:
 //!
 //! * For unit type, it's not considered a value and no encoding action happens.
 //! * For booleans, they are encoded as literals "true" or "false".
 //! * For integers, they are encoded as fixed-width hexadecimal of their big-endian representations. Signed integers are preprocessed with some bit manipulation, as in the bytekey crate, so that negative numbers sort first.
-//! * For floating point numbers, they're preprocessed with some bit manipulation, as in the bytekey crate, so that negative numbers sort first. Then encoded as hexadecimal.
-//! * For strings, no special encoding is done since they are already UTF-8 encoded.
-//! * For byte arrays (requires [serde_bytes](https://crates.io/crates/serde_bytes)), they are encoded as hexadecimal.
+//! * For floating point numbers, they're preprocessed with some bit manipulation, as in the bytekey crate, so that negative numbers sort first. Then encoded as hexadecimal. The `OrderedFloat` and `NotNan` wrappers from the [ordered-float](https://crates.io/crates/ordered-float) crate are encoded the same way (requires `ordered-float` feature).
+//! * For strings, no special encoding is done since they are already UTF-8 encoded. If percent-encoding is enabled, bytes other than ASCII alphanumerics and `-._~` are escaped.
+//! * For byte arrays (requires [serde_bytes](https://crates.io/crates/serde_bytes)), they are encoded as hexadecimal. `Bytes` and `BytesMut` from the [bytes](https://crates.io/crates/bytes) crate are also supported (requires `bytes` feature).
 //! * For tuples, each encoded value is separated by the configured deliminator. Note that deliminator are emitted along values; the data structure itself doesn't cause deliminators to be emitted.
 //! * For structs, the field names are *not* encoded. Only the values are encoded as it were a tuple. This can be useful for labeling each part of the database key without encoding the schema itself.
 //! * For enums with unit variants, only the name of the enum's variant is encoded. The name of the enum itself is not encoded.
 //! * For option, maps, sequences, and enums with tuple or struct variants are not supported and return an error.
-use std::io::Write;
+use std::{
+    io::{IoSlice, Write},
+    sync::Arc,
+};
 
 use serde::{
     ser::{
-        SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
-        SerializeTupleStruct, SerializeTupleVariant,
+        Impossible, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+        SerializeTuple, SerializeTupleStruct, SerializeTupleVariant,
     },
     Serialize,
 };
 
-use crate::error::Error;
+use crate::{
+    config::{
+        Encoding, EncodingOverrides, Mask, Redaction, SignedEncoding, SortSpec, VariantCodes,
+    },
+    de::find_subslice,
+    error::{ConfigError, Error},
+    hex_codec::{encode_extend, Backend as HexBackend, HexCodec},
+    metrics::Operation,
+};
+
+/// Newtype name that marks the value as a verbatim component.
+pub(crate) const VERBATIM_NAME: &str = "$strkey::private::Verbatim";
 
 /// Serializer for encoding values into strkey encoding.
 ///
@@ -43,9 +57,20 @@ use crate::error::Error;
 /// # }
 /// ```
 pub struct Serializer<W: Write> {
-    output: W,
-    deliminator: String,
+    output: CountingWriter<W>,
+    deliminator: Vec<u8>,
     first_part_written: bool,
+    percent_encoding: bool,
+    strict_ordering: bool,
+    verbatim: bool,
+    overrides: Option<Arc<EncodingOverrides>>,
+    encoding: Encoding,
+    signed_encoding: SignedEncoding,
+    sort_spec: Option<Arc<SortSpec>>,
+    variant_codes: Option<Arc<VariantCodes>>,
+    redaction: Option<Arc<Redaction>>,
+    components_written: usize,
+    validated: bool,
     buffer: Vec<u8>,
 }
 
@@ -53,52 +78,390 @@ impl<W: Write> Serializer<W> {
     /// Serialize the value into the given writer using the default options.
     pub fn new(writer: W) -> Self {
         Self {
-            output: writer,
-            deliminator: ":".to_string(),
+            output: CountingWriter::new(writer),
+            deliminator: b":".to_vec(),
             first_part_written: false,
+            percent_encoding: false,
+            strict_ordering: false,
+            verbatim: false,
+            overrides: None,
+            encoding: Encoding::Hex,
+            signed_encoding: SignedEncoding::Offset,
+            sort_spec: None,
+            variant_codes: None,
+            redaction: None,
+            components_written: 0,
+            validated: false,
             buffer: Vec::new(),
         }
     }
 
     /// Unwrap and return the wrapped writer.
     pub fn into_inner(self) -> W {
-        self.output
+        self.output.into_inner()
+    }
+
+    /// Returns the number of bytes written to the writer.
+    pub fn bytes_written(&self) -> u64 {
+        self.output.count()
     }
 
     /// Returns the deliminator used to separate values.
-    pub fn deliminator(&self) -> &str {
+    pub fn deliminator(&self) -> &[u8] {
         &self.deliminator
     }
 
     /// Sets the deliminator used to separate values.
-    pub fn set_deliminator<S: Into<String>>(&mut self, deliminator: S) {
+    ///
+    /// The deliminator can be any byte sequence, such as `":"` or `b"\0"`.
+    /// A deliminator that can occur within a component returns
+    /// [`Error::Config`] when the first component is written. See
+    /// [`Self::validate`].
+    pub fn set_deliminator<D: Into<Vec<u8>>>(&mut self, deliminator: D) {
         self.deliminator = deliminator.into();
+        self.validated = false;
     }
 
     /// Sets the deliminator used to separate values and returns a new serializer.
-    pub fn with_deliminator<S: Into<String>>(mut self, deliminator: S) -> Self {
+    pub fn with_deliminator<D: Into<Vec<u8>>>(mut self, deliminator: D) -> Self {
         self.set_deliminator(deliminator);
         self
     }
 
+    /// Returns whether string components are percent-encoded.
+    pub fn percent_encoding(&self) -> bool {
+        self.percent_encoding
+    }
+
+    /// Sets whether string components are percent-encoded.
+    ///
+    /// When enabled, bytes in strings, chars, and variant names other than
+    /// ASCII alphanumerics and `-._~` are written as `%XX`. This makes the
+    /// output safe for URLs and object store keys.
+    ///
+    /// **Keys no longer sort in the order of their values.** An escape
+    /// begins with `%`, which sorts before every unescaped character, so
+    /// `"a/"` is written as `a%2F` and sorts before `"a."` even though `/`
+    /// sorts after `.`. No URL-safe escape can avoid this because escaped
+    /// bytes such as `:` and `@` sort between unescaped characters with no
+    /// URL-safe character in between. Unit variant names are affected in
+    /// the same way. Writing a string with both this and
+    /// [`Self::set_strict_ordering`] enabled returns
+    /// [`ConfigError::UnorderedEscapes`].
+    pub fn set_percent_encoding(&mut self, value: bool) {
+        self.percent_encoding = value;
+        self.validated = false;
+    }
+
+    /// Sets whether string components are percent-encoded and returns a new serializer.
+    pub fn with_percent_encoding(mut self, value: bool) -> Self {
+        self.set_percent_encoding(value);
+        self
+    }
+
+    /// Returns whether strict ordering is enabled.
+    pub fn strict_ordering(&self) -> bool {
+        self.strict_ordering
+    }
+
+    /// Sets whether strict ordering is enabled.
+    ///
+    /// When enabled, unit variants are written as their `u32` index instead
+    /// of their name so they sort in declaration order, and strings
+    /// containing the deliminator return an error instead of producing a key
+    /// that cannot be decoded. See [`crate::config::Config::strict`].
+    pub fn set_strict_ordering(&mut self, value: bool) {
+        self.strict_ordering = value;
+        self.validated = false;
+    }
+
+    /// Sets whether strict ordering is enabled and returns a new serializer.
+    pub fn with_strict_ordering(mut self, value: bool) -> Self {
+        self.set_strict_ordering(value);
+        self
+    }
+
+    /// Returns the encoding of signed integers.
+    pub fn signed_encoding(&self) -> SignedEncoding {
+        self.signed_encoding
+    }
+
+    /// Sets the encoding of signed integers.
+    ///
+    /// The default flips the sign bit. See [`SignedEncoding`] for
+    /// alternatives compatible with other key schemes.
+    pub fn set_signed_encoding(&mut self, value: SignedEncoding) {
+        self.signed_encoding = value;
+    }
+
+    /// Sets the encoding of signed integers and returns a new serializer.
+    pub fn with_signed_encoding(mut self, value: SignedEncoding) -> Self {
+        self.set_signed_encoding(value);
+        self
+    }
+
+    /// Returns the encoding overrides.
+    pub fn overrides(&self) -> Option<&EncodingOverrides> {
+        self.overrides.as_deref()
+    }
+
+    /// Sets the encoding overrides.
+    ///
+    /// See [`crate::config::EncodingOverrides`].
+    pub fn set_overrides(&mut self, overrides: Arc<EncodingOverrides>) {
+        self.overrides = Some(overrides);
+        self.validated = false;
+    }
+
+    /// Sets the encoding overrides and returns a new serializer.
+    pub fn with_overrides(mut self, overrides: Arc<EncodingOverrides>) -> Self {
+        self.set_overrides(overrides);
+        self
+    }
+
+    /// Returns the sort specification.
+    pub fn sort_spec(&self) -> Option<&SortSpec> {
+        self.sort_spec.as_deref()
+    }
+
+    /// Sets the sort specification.
+    ///
+    /// See [`crate::config::SortSpec`].
+    pub fn set_sort_spec(&mut self, sort_spec: Arc<SortSpec>) {
+        self.sort_spec = Some(sort_spec);
+    }
+
+    /// Sets the sort specification and returns a new serializer.
+    pub fn with_sort_spec(mut self, sort_spec: Arc<SortSpec>) -> Self {
+        self.set_sort_spec(sort_spec);
+        self
+    }
+
+    /// Checks that the deliminator cannot occur within a component and that
+    /// the options do not conflict.
+    ///
+    /// The check also runs before the first component is written after the
+    /// options change, so an invalid configuration cannot produce a key.
+    /// See [`crate::config::Config::validate`].
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let encodings = self
+            .overrides
+            .iter()
+            .flat_map(|overrides| overrides.encodings());
+
+        crate::config::validate_deliminator(&self.deliminator, self.percent_encoding, encodings)?;
+
+        if self.strict_ordering && self.percent_encoding {
+            return Err(ConfigError::UnorderedEscapes);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the enum variant codes.
+    pub fn variant_codes(&self) -> Option<&VariantCodes> {
+        self.variant_codes.as_deref()
+    }
+
+    /// Sets the enum variant codes.
+    ///
+    /// Codes are not used when strict ordering is enabled as variants are
+    /// encoded by index. Returns [`Error::Data`] on encode if a code contains
+    /// the deliminator. See [`crate::config::VariantCodes`].
+    pub fn set_variant_codes(&mut self, variant_codes: Arc<VariantCodes>) {
+        self.variant_codes = Some(variant_codes);
+    }
+
+    /// Sets the enum variant codes and returns a new serializer.
+    pub fn with_variant_codes(mut self, variant_codes: Arc<VariantCodes>) -> Self {
+        self.set_variant_codes(variant_codes);
+        self
+    }
+
+    /// Returns the components replaced with a mask.
+    pub fn redaction(&self) -> Option<&Redaction> {
+        self.redaction.as_deref()
+    }
+
+    /// Sets the components replaced with a mask.
+    ///
+    /// Each masked component is replaced as it is written, so a string
+    /// component containing the deliminator is replaced entirely. See
+    /// [`crate::config::Redaction`].
+    pub fn set_redaction(&mut self, redaction: Arc<Redaction>) {
+        self.redaction = Some(redaction);
+    }
+
+    /// Sets the components replaced with a mask and returns a new serializer.
+    pub fn with_redaction(mut self, redaction: Arc<Redaction>) -> Self {
+        self.set_redaction(redaction);
+        self
+    }
+
+    /// Numbers the next component as if `index` components were already
+    /// written, without writing a deliminator before it, and encodes it
+    /// with the given encoding, or the default encoding if none.
+    ///
+    /// This lets a single component be encoded with the options that apply
+    /// at its index.
+    pub(crate) fn begin_component(&mut self, index: usize, encoding: Option<Encoding>) {
+        self.components_written = index;
+        self.encoding = encoding.unwrap_or_default();
+    }
+
+    /// Returns the number of components written, including those numbered
+    /// by [`Self::begin_component`].
+    pub(crate) fn components_written(&self) -> usize {
+        self.components_written
+    }
+
+    fn is_descending(&self) -> bool {
+        match &self.sort_spec {
+            Some(sort_spec) => sort_spec.is_descending(self.components_written - 1),
+            None => false,
+        }
+    }
+
     fn maybe_write_deliminator(&mut self) -> Result<(), Error> {
+        if !self.validated {
+            self.validate()?;
+            self.validated = true;
+        }
+
+        self.components_written += 1;
+
         if self.first_part_written {
-            self.output.write_all(&self.deliminator.as_bytes())?;
+            self.output.write_all(&self.deliminator)?;
         } else {
             self.first_part_written = true;
         }
         Ok(())
     }
 
+    fn write_component(&mut self, data: &[u8]) -> Result<(), Error> {
+        let mask = match &self.redaction {
+            Some(redaction) => redaction.mask(self.components_written - 1),
+            None => None,
+        };
+
+        match mask {
+            Some(Mask::Fixed(text)) => {
+                if find_subslice(text.as_bytes(), &self.deliminator).is_some() {
+                    return Err(Error::Data(text.clone()));
+                }
+
+                self.output.write_all(text.as_bytes())?;
+            }
+            Some(Mask::Hash(function)) => {
+                let mut digest = Vec::new();
+                encode_extend(&function(data), &mut digest);
+                self.output.write_all(&digest)?;
+            }
+            None => self.output.write_all(data)?,
+        }
+
+        Ok(())
+    }
+
+    fn write_buffer(&mut self) -> Result<(), Error> {
+        let buffer = std::mem::take(&mut self.buffer);
+        let result = self.write_component(&buffer);
+        self.buffer = buffer;
+
+        result
+    }
+
     fn write_encode_hex(&mut self, data: &[u8]) -> Result<(), Error> {
         self.buffer.resize(data.len() * 2, 0);
-        hex::encode_to_slice(data, &mut self.buffer).unwrap();
-        self.output.write_all(&self.buffer)?;
-        Ok(())
+        HexBackend::encode_to_slice(data, &mut self.buffer);
+        self.write_buffer()
+    }
+
+    fn write_encoded(&mut self, data: &[u8], fixed: bool) -> Result<(), Error> {
+        let inverted;
+        let data = if self.is_descending() {
+            if !fixed {
+                return Err(Error::UnsupportedType);
+            }
+
+            inverted = data.iter().map(|byte| !byte).collect::<Vec<u8>>();
+            &inverted
+        } else {
+            data
+        };
+
+        if self.encoding == Encoding::Hex {
+            return self.write_encode_hex(data);
+        }
+
+        self.buffer.clear();
+        crate::config::encode(self.encoding, data, fixed, &mut self.buffer)?;
+        self.write_buffer()
+    }
+
+    fn write_signed(&mut self, value: i128, bits: u32) -> Result<(), Error> {
+        let buf = self.signed_encoding.encode(value, bits)?.to_be_bytes();
+
+        self.maybe_write_deliminator()?;
+        self.write_encoded(&buf[16 - bits as usize / 8..], true)
+    }
+
+    fn serialize_with_encoding<T>(
+        &mut self,
+        encoding: Option<Encoding>,
+        value: &T,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let previous = self.encoding;
+
+        if let Some(encoding) = encoding {
+            self.encoding = encoding;
+        }
+
+        let result = value.serialize(&mut *self);
+        self.encoding = previous;
+
+        result
+    }
+
+    fn write_str(&mut self, value: &str) -> Result<(), Error> {
+        if self.is_descending() {
+            return Err(Error::UnsupportedType);
+        }
+
+        if (self.verbatim || self.strict_ordering)
+            && find_subslice(value.as_bytes(), &self.deliminator).is_some()
+        {
+            return Err(Error::Data(value.to_string()));
+        }
+
+        if self.verbatim || !self.percent_encoding {
+            return self.write_component(value.as_bytes());
+        }
+
+        if self.strict_ordering {
+            return Err(ConfigError::UnorderedEscapes.into());
+        }
+
+        self.buffer.clear();
+
+        for &byte in value.as_bytes() {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                self.buffer.push(byte);
+            } else {
+                self.buffer.push(b'%');
+                self.buffer.push(b"0123456789ABCDEF"[(byte >> 4) as usize]);
+                self.buffer.push(b"0123456789ABCDEF"[(byte & 0xf) as usize]);
+            }
+        }
+
+        self.write_buffer()
     }
 }
 
-impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
+impl<W: Write> serde::Serializer for &mut Serializer<W> {
     type Ok = ();
     type Error = Error;
     type SerializeSeq = Self;
@@ -112,62 +475,41 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
         self.maybe_write_deliminator()?;
 
-        self.output.write_all(if v { b"true" } else { b"false" })?;
+        if self.is_descending() {
+            return Err(Error::UnsupportedType);
+        }
+
+        self.write_component(if v { b"true" } else { b"false" })?;
         Ok(())
     }
 
     // signed integer magic https://github.com/danburkert/bytekey/blob/6980b9e33281d875f03f4c9a953b93a384eac085/src/encoder.rs#L322
 
     fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
-        self.maybe_write_deliminator()?;
-
-        let buf = (v ^ i8::MIN).to_be_bytes();
-        self.write_encode_hex(&buf)?;
-
-        Ok(())
+        self.write_signed(v.into(), 8)
     }
 
     fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
-        self.maybe_write_deliminator()?;
-
-        let buf = (v ^ i16::MIN).to_be_bytes();
-        self.write_encode_hex(&buf)?;
-
-        Ok(())
+        self.write_signed(v.into(), 16)
     }
 
     fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
-        self.maybe_write_deliminator()?;
-
-        let buf = (v ^ i32::MIN).to_be_bytes();
-        self.write_encode_hex(&buf)?;
-
-        Ok(())
+        self.write_signed(v.into(), 32)
     }
 
     fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
-        self.maybe_write_deliminator()?;
-
-        let buf = (v ^ i64::MIN).to_be_bytes();
-        self.write_encode_hex(&buf)?;
-
-        Ok(())
+        self.write_signed(v.into(), 64)
     }
 
     fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
-        self.maybe_write_deliminator()?;
-
-        let buf = (v ^ i128::MIN).to_be_bytes();
-        self.write_encode_hex(&buf)?;
-
-        Ok(())
+        self.write_signed(v, 128)
     }
 
     fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
         self.maybe_write_deliminator()?;
 
         let buf = v.to_be_bytes();
-        self.write_encode_hex(&buf)?;
+        self.write_encoded(&buf, true)?;
 
         Ok(())
     }
@@ -176,7 +518,7 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
         self.maybe_write_deliminator()?;
 
         let buf = v.to_be_bytes();
-        self.write_encode_hex(&buf)?;
+        self.write_encoded(&buf, true)?;
 
         Ok(())
     }
@@ -185,7 +527,7 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
         self.maybe_write_deliminator()?;
 
         let buf = v.to_be_bytes();
-        self.write_encode_hex(&buf)?;
+        self.write_encoded(&buf, true)?;
 
         Ok(())
     }
@@ -194,7 +536,7 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
         self.maybe_write_deliminator()?;
 
         let buf = v.to_be_bytes();
-        self.write_encode_hex(&buf)?;
+        self.write_encoded(&buf, true)?;
 
         Ok(())
     }
@@ -203,7 +545,7 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
         self.maybe_write_deliminator()?;
 
         let buf = v.to_be_bytes();
-        self.write_encode_hex(&buf)?;
+        self.write_encoded(&buf, true)?;
 
         Ok(())
     }
@@ -217,7 +559,7 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
         let t = (val >> 31) | i32::MIN;
         let val = val ^ t;
         let buf = val.to_be_bytes();
-        self.write_encode_hex(&buf)?;
+        self.write_encoded(&buf, true)?;
 
         Ok(())
     }
@@ -229,7 +571,7 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
         let t = (val >> 63) | i64::MIN;
         let val = val ^ t;
         let buf = val.to_be_bytes();
-        self.write_encode_hex(&buf)?;
+        self.write_encoded(&buf, true)?;
 
         Ok(())
     }
@@ -238,7 +580,7 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
         self.maybe_write_deliminator()?;
 
         let mut buf = [0u8; 4];
-        self.output.write_all(v.encode_utf8(&mut buf).as_bytes())?;
+        self.write_str(v.encode_utf8(&mut buf))?;
 
         Ok(())
     }
@@ -246,7 +588,7 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
         self.maybe_write_deliminator()?;
 
-        self.output.write_all(v.as_bytes())?;
+        self.write_str(v)?;
 
         Ok(())
     }
@@ -254,7 +596,11 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
         self.maybe_write_deliminator()?;
 
-        self.write_encode_hex(v)?;
+        if self.verbatim {
+            self.write_str(std::str::from_utf8(v)?)?;
+        } else {
+            self.write_encoded(v, false)?;
+        }
 
         Ok(())
     }
@@ -263,9 +609,9 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
         Err(Error::UnsupportedType)
     }
 
-    fn serialize_some<T: ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         Err(Error::UnsupportedType)
     }
@@ -280,29 +626,54 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
 
     fn serialize_unit_variant(
         self,
-        _name: &'static str,
-        _variant_index: u32,
+        name: &'static str,
+        variant_index: u32,
         variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
+        if self.strict_ordering {
+            return self.serialize_u32(variant_index);
+        }
+
         self.maybe_write_deliminator()?;
 
-        self.output.write_all(variant.as_bytes())?;
+        let codes = self.variant_codes.clone();
+
+        match codes.as_ref().and_then(|codes| codes.code(name, variant)) {
+            Some(code) => {
+                if find_subslice(code.as_bytes(), &self.deliminator).is_some() {
+                    return Err(Error::Data(code.to_string()));
+                }
+
+                self.write_str(code)?;
+            }
+            None => self.write_str(variant)?,
+        }
 
         Ok(())
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        value.serialize(self)
+        if name == VERBATIM_NAME {
+            self.verbatim = true;
+            let result = value.serialize(&mut *self);
+            self.verbatim = false;
+
+            result
+        } else if let Some(overrides) = self.overrides.clone() {
+            self.serialize_with_encoding(overrides.newtype(name), value)
+        } else {
+            value.serialize(self)
+        }
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
@@ -310,7 +681,7 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
         _value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         Err(Error::UnsupportedType)
     }
@@ -364,13 +735,13 @@ impl<'a, W: Write> serde::Serializer for &'a mut Serializer<W> {
     }
 }
 
-impl<'a, W: Write> SerializeSeq for &'a mut Serializer<W> {
+impl<W: Write> SerializeSeq for &mut Serializer<W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(&mut **self)
     }
@@ -380,13 +751,13 @@ impl<'a, W: Write> SerializeSeq for &'a mut Serializer<W> {
     }
 }
 
-impl<'a, W: Write> SerializeTuple for &'a mut Serializer<W> {
+impl<W: Write> SerializeTuple for &mut Serializer<W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(&mut **self)
     }
@@ -396,13 +767,13 @@ impl<'a, W: Write> SerializeTuple for &'a mut Serializer<W> {
     }
 }
 
-impl<'a, W: Write> SerializeTupleStruct for &'a mut Serializer<W> {
+impl<W: Write> SerializeTupleStruct for &mut Serializer<W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(&mut **self)
     }
@@ -412,13 +783,13 @@ impl<'a, W: Write> SerializeTupleStruct for &'a mut Serializer<W> {
     }
 }
 
-impl<'a, W: Write> SerializeTupleVariant for &'a mut Serializer<W> {
+impl<W: Write> SerializeTupleVariant for &mut Serializer<W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         value.serialize(&mut **self)
     }
@@ -428,20 +799,20 @@ impl<'a, W: Write> SerializeTupleVariant for &'a mut Serializer<W> {
     }
 }
 
-impl<'a, W: Write> SerializeMap for &'a mut Serializer<W> {
+impl<W: Write> SerializeMap for &mut Serializer<W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_key<T: ?Sized>(&mut self, _key: &T) -> Result<(), Self::Error>
+    fn serialize_key<T>(&mut self, _key: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         unreachable!()
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_value<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         unreachable!()
     }
@@ -451,19 +822,19 @@ impl<'a, W: Write> SerializeMap for &'a mut Serializer<W> {
     }
 }
 
-impl<'a, W: Write> SerializeStruct for &'a mut Serializer<W> {
+impl<W: Write> SerializeStruct for &mut Serializer<W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        _key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        value.serialize(&mut **self)
+        if let Some(overrides) = self.overrides.clone() {
+            self.serialize_with_encoding(overrides.field(key), value)
+        } else {
+            value.serialize(&mut **self)
+        }
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
@@ -471,17 +842,13 @@ impl<'a, W: Write> SerializeStruct for &'a mut Serializer<W> {
     }
 }
 
-impl<'a, W: Write> SerializeStructVariant for &'a mut Serializer<W> {
+impl<W: Write> SerializeStructVariant for &mut Serializer<W> {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        _key: &'static str,
-        _value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _key: &'static str, _value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         unreachable!()
     }
@@ -491,10 +858,135 @@ impl<'a, W: Write> SerializeStructVariant for &'a mut Serializer<W> {
     }
 }
 
+/// Writer that counts the number of bytes written to the wrapped writer.
+///
+/// Example:
+///
+/// ```
+/// use std::io::Write;
+/// use strkey::ser::CountingWriter;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let mut writer = CountingWriter::new(Vec::new());
+/// strkey::to_writer(&mut writer, &("account", 1234u32))?;
+/// writer.write_all(b"\n")?;
+///
+/// assert_eq!(writer.count(), 17);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CountingWriter<W> {
+    inner: W,
+    count: u64,
+}
+
+impl<W> CountingWriter<W> {
+    /// Wrap the given writer.
+    pub fn new(inner: W) -> Self {
+        Self { inner, count: 0 }
+    }
+
+    /// Returns the number of bytes written.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Resets the number of bytes written to zero.
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+
+    /// Returns a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped writer.
+    ///
+    /// Bytes written directly to the wrapped writer are not counted.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap and return the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.count += len as u64;
+
+        Ok(len)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> std::io::Result<usize> {
+        let len = self.inner.write_vectored(bufs)?;
+        self.count += len as u64;
+
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// Serializes the given value to a vector.
 pub fn to_vec<T>(value: &T) -> Result<Vec<u8>, Error>
 where
+    T: ?Sized + Serialize,
+{
+    crate::metrics::observe::<T, _, _>(Operation::Encode, || {
+        let mut buffer = Vec::new();
+        let mut serializer = Serializer::new(&mut buffer).with_deliminator(":");
+        value.serialize(&mut serializer)?;
+        let len = buffer.len();
+
+        Ok((buffer, Some(len)))
+    })
+}
+
+/// Serializes the given value to the writer.
+///
+/// The key is encoded into a buffer and written with a single call to
+/// [`Write::write_all`] so keys written concurrently to a shared writer,
+/// such as a file opened in append mode, are not interleaved. This also
+/// means a key with many or large components is not split into many small
+/// writes when the writer is a pipe or socket.
+pub fn to_writer<W, T>(mut writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize,
+{
+    crate::metrics::observe::<T, _, _>(Operation::Encode, || {
+        let buffer = to_buffer(value)?;
+        writer.write_all(&buffer)?;
+
+        Ok(((), Some(buffer.len())))
+    })
+}
+
+/// Serializes the given value to the writer and flushes the writer.
+///
+/// See [`to_writer`].
+pub fn to_writer_flush<W, T>(mut writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
     T: Serialize,
+{
+    to_writer(&mut writer, value)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn to_buffer<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+    T: ?Sized + Serialize,
 {
     let mut buffer = Vec::new();
     let mut serializer = Serializer::new(&mut buffer).with_deliminator(":");
@@ -503,16 +995,369 @@ where
     Ok(buffer)
 }
 
-/// Serializes the given value to the writer.
-pub fn to_writer<W, T>(mut writer: W, value: &T) -> Result<(), Error>
+/// Returns the exact encoded length of the value with the default
+/// deliminator if it consists only of fixed-width components.
+///
+/// Integers, floats, bools, and unit are fixed-width; tuples and structs of
+/// them are too. `None` is returned for values containing chars, strings,
+/// byte arrays, sequences, or enums, whose length depends on their contents.
+/// The value is walked but not encoded, which is useful for checking a key
+/// against a store's size limit or preallocating a buffer.
+///
+/// Example:
+///
+/// ```rust
+/// assert_eq!(strkey::fixed_len(&(1u8, 2u32)), Some(11));
+/// assert_eq!(strkey::fixed_len(&("a", 1u8)), None);
+/// ```
+pub fn fixed_len<T>(value: &T) -> Option<usize>
 where
-    W: Write,
+    T: ?Sized + Serialize,
+{
+    let mut probe = FixedLenProbe {
+        len: 0,
+        components: 0,
+    };
+    value.serialize(&mut probe).ok()?;
+
+    Some(probe.len + probe.components.saturating_sub(1))
+}
+
+struct FixedLenProbe {
+    len: usize,
+    components: usize,
+}
+
+impl FixedLenProbe {
+    fn add(&mut self, len: usize) -> Result<(), Error> {
+        self.len += len;
+        self.components += 1;
+        Ok(())
+    }
+}
+
+macro_rules! fixed_len_unsupported {
+    ($($name:ident($($arg:ty),*) -> $ret:ty;)*) => {
+        $(
+            fn $name(self, $(_: $arg),*) -> Result<$ret, Self::Error> {
+                Err(Error::UnsupportedType)
+            }
+        )*
+    };
+}
+
+impl serde::Serializer for &mut FixedLenProbe {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.add(if v { 4 } else { 5 })
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<Self::Ok, Self::Error> {
+        self.add(2)
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<Self::Ok, Self::Error> {
+        self.add(4)
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<Self::Ok, Self::Error> {
+        self.add(8)
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<Self::Ok, Self::Error> {
+        self.add(16)
+    }
+
+    fn serialize_i128(self, _v: i128) -> Result<Self::Ok, Self::Error> {
+        self.add(32)
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<Self::Ok, Self::Error> {
+        self.add(2)
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<Self::Ok, Self::Error> {
+        self.add(4)
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<Self::Ok, Self::Error> {
+        self.add(8)
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<Self::Ok, Self::Error> {
+        self.add(16)
+    }
+
+    fn serialize_u128(self, _v: u128) -> Result<Self::Ok, Self::Error> {
+        self.add(32)
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
+        self.add(8)
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<Self::Ok, Self::Error> {
+        self.add(16)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+
+    fixed_len_unsupported! {
+        serialize_char(char) -> ();
+        serialize_str(&str) -> ();
+        serialize_bytes(&[u8]) -> ();
+        serialize_none() -> ();
+        serialize_unit_variant(&'static str, u32, &'static str) -> ();
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::UnsupportedType)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        if name == VERBATIM_NAME {
+            Err(Error::UnsupportedType)
+        } else {
+            value.serialize(self)
+        }
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::UnsupportedType)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(self)
+    }
+}
+
+impl SerializeTuple for &mut FixedLenProbe {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+}
+
+impl SerializeTupleStruct for &mut FixedLenProbe {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+}
+
+impl SerializeStruct for &mut FixedLenProbe {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(())
+    }
+}
+
+/// Serializes the given value to a vector for use as a key prefix.
+///
+/// The deliminator is appended so that the prefix only matches keys whose
+/// leading values are equal to the given value, rather than keys whose
+/// last leading value merely starts with it. A value that encodes to
+/// nothing, such as the unit type, returns an empty vector.
+pub fn to_prefix<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+    T: ?Sized + Serialize,
+{
+    let mut buffer = Vec::new();
+    let mut serializer = Serializer::new(&mut buffer).with_deliminator(":");
+    value.serialize(&mut serializer)?;
+
+    if !buffer.is_empty() {
+        buffer.push(b':');
+    }
+
+    Ok(buffer)
+}
+
+/// Serializes each value and returns the keys sorted and deduplicated.
+///
+/// The keys are sorted by their encoded bytes, which is the order a store
+/// expects for a multi-get or batched range. This is not necessarily the
+/// order of the values, such as when a string component is followed by
+/// another component.
+pub fn encode_sorted<I, T>(values: I) -> Result<Vec<Vec<u8>>, Error>
+where
+    I: IntoIterator<Item = T>,
     T: Serialize,
 {
+    let mut keys = values
+        .into_iter()
+        .map(|value| to_vec(&value))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    keys.sort_unstable();
+    keys.dedup();
+
+    Ok(keys)
+}
+
+/// Serializes the given value to an array of exactly `N` bytes.
+///
+/// This avoids allocating for keys consisting only of fixed-width
+/// components. Returns [`Error::KeyTooLong`] if the encoded value is longer
+/// than `N` and [`Error::KeyTooShort`] if it is shorter.
+pub fn to_array<T, const N: usize>(value: &T) -> Result<[u8; N], Error>
+where
+    T: ?Sized + Serialize,
+{
+    let mut writer = ArrayWriter {
+        buffer: [0u8; N],
+        len: 0,
+    };
     let mut serializer = Serializer::new(&mut writer).with_deliminator(":");
     value.serialize(&mut serializer)?;
 
-    Ok(())
+    if writer.len > N {
+        Err(Error::KeyTooLong(writer.len, N))
+    } else if writer.len < N {
+        Err(Error::KeyTooShort(writer.len, N))
+    } else {
+        Ok(writer.buffer)
+    }
+}
+
+/// Serializes the given value to a `heapless::Vec` with capacity `N`.
+///
+/// Returns [`Error::KeyTooLong`] if the encoded value is longer than `N`.
+#[cfg(feature = "heapless")]
+pub fn to_heapless<T, const N: usize>(value: &T) -> Result<heapless::Vec<u8, N>, Error>
+where
+    T: ?Sized + Serialize,
+{
+    let mut writer = ArrayWriter {
+        buffer: [0u8; N],
+        len: 0,
+    };
+    let mut serializer = Serializer::new(&mut writer).with_deliminator(":");
+    value.serialize(&mut serializer)?;
+
+    if writer.len > N {
+        Err(Error::KeyTooLong(writer.len, N))
+    } else {
+        Ok(heapless::Vec::from_slice(&writer.buffer[..writer.len]).unwrap())
+    }
+}
+
+/// Serializes the given value to a `SmallVec`.
+///
+/// Keys no longer than the inline capacity of `A` are kept on the stack;
+/// longer keys spill to the heap.
+#[cfg(feature = "smallvec")]
+pub fn to_smallvec<A, T>(value: &T) -> Result<smallvec::SmallVec<A>, Error>
+where
+    A: smallvec::Array<Item = u8>,
+    T: ?Sized + Serialize,
+{
+    let mut buffer = smallvec::SmallVec::new();
+    let mut serializer = Serializer::new(&mut buffer).with_deliminator(":");
+    value.serialize(&mut serializer)?;
+    Ok(buffer)
+}
+
+struct ArrayWriter<const N: usize> {
+    buffer: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Write for ArrayWriter<N> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(target) = self.buffer.get_mut(self.len..self.len + buf.len()) {
+            target.copy_from_slice(buf);
+        }
+
+        self.len += buf.len();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -589,6 +1434,66 @@ mod tests {
         assert!(key1 < key2);
     }
 
+    #[cfg(feature = "ordered-float")]
+    #[test]
+    fn test_ordered_float() {
+        use ordered_float::{NotNan, OrderedFloat};
+
+        let key = to_vec(&OrderedFloat(1234.56f32)).unwrap();
+        assert_eq!(&key, b"c49a51ec");
+
+        let key = to_vec(&NotNan::new(1234.5678f64).unwrap()).unwrap();
+        assert_eq!(&key, b"c0934a456d5cfaad");
+    }
+
+    #[test]
+    fn test_encode_sorted() {
+        let keys = encode_sorted([("a", 2u8), ("a!", 1), ("a", 2), ("a", 1)]).unwrap();
+
+        assert_eq!(
+            keys,
+            vec![b"a!:01".to_vec(), b"a:01".to_vec(), b"a:02".to_vec()]
+        );
+        assert!(encode_sorted(Vec::<u8>::new()).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn test_to_heapless() {
+        let key = to_heapless::<_, 16>(&("abc", 1u16)).unwrap();
+        assert_eq!(&key[..], b"abc:0001");
+
+        let key = to_heapless::<_, 8>(&("abc", 1u16)).unwrap();
+        assert_eq!(&key[..], b"abc:0001");
+
+        assert!(matches!(
+            to_heapless::<_, 4>(&("abc", 1u16)),
+            Err(Error::KeyTooLong(8, 4))
+        ));
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn test_to_smallvec() {
+        let key = to_smallvec::<[u8; 16], _>(&("abc", 1u16)).unwrap();
+        assert_eq!(&key[..], b"abc:0001");
+        assert!(!key.spilled());
+
+        let key = to_smallvec::<[u8; 4], _>(&("abc", 1u16)).unwrap();
+        assert_eq!(&key[..], b"abc:0001");
+        assert!(key.spilled());
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_bytes_crate() {
+        let key = to_vec(&("a", bytes::Bytes::from_static(b"\xca\xfe"))).unwrap();
+        assert_eq!(&key, b"a:cafe");
+
+        let key = to_vec(&bytes::BytesMut::from(&b"\x01"[..])).unwrap();
+        assert_eq!(&key, b"01");
+    }
+
     #[test]
     fn test_char() {
         let key = to_vec(&'🐺').unwrap();
@@ -733,12 +1638,21 @@ mod tests {
         assert_eq!(&key, b"hello");
     }
 
+    #[test]
+    fn test_prefix() {
+        let key = to_prefix(&("hello", 1u8)).unwrap();
+        assert_eq!(&key, b"hello:01:");
+
+        let key = to_prefix(&()).unwrap();
+        assert_eq!(&key, b"");
+    }
+
     #[test]
     fn test_deliminator() {
         let mut key = Vec::new();
         let mut serializer = Serializer::new(&mut key).with_deliminator("/");
 
-        assert_eq!(serializer.deliminator(), "/");
+        assert_eq!(serializer.deliminator(), b"/");
 
         ("hello", "world").serialize(&mut serializer).unwrap();
 
@@ -747,6 +1661,79 @@ mod tests {
         assert_eq!(&key, b"hello/world");
     }
 
+    #[test]
+    fn test_binary_deliminator() {
+        let encode = |value: &(&str, u8)| {
+            let mut key = Vec::new();
+            let mut serializer = Serializer::new(&mut key).with_deliminator(&b"\0"[..]);
+            value.serialize(&mut serializer).unwrap();
+            key
+        };
+
+        assert_eq!(encode(&("a", 1)), b"a\x0001");
+        assert!(encode(&("a", 1)) < encode(&("a!", 0)));
+
+        let mut key = Vec::new();
+        let mut serializer = Serializer::new(&mut key).with_deliminator(vec![0xff, 0xfe]);
+        ("a", 1u8).serialize(&mut serializer).unwrap();
+        assert_eq!(&key, b"a\xff\xfe01");
+    }
+
+    #[test]
+    fn test_strict_ordering() {
+        #[derive(Serialize)]
+        enum Level {
+            Low,
+            High,
+        }
+
+        let mut key = Vec::new();
+        let mut serializer = Serializer::new(&mut key).with_strict_ordering(true);
+
+        assert!(serializer.strict_ordering());
+        (Level::High, Level::Low, "a")
+            .serialize(&mut serializer)
+            .unwrap();
+        assert_eq!(&key, b"00000001:00000000:a");
+
+        let mut serializer = Serializer::new(Vec::new()).with_strict_ordering(true);
+        assert!("a:b".serialize(&mut serializer).is_err());
+    }
+
+    #[test]
+    fn test_percent_encoding() {
+        let mut key = Vec::new();
+        let mut serializer = Serializer::new(&mut key).with_percent_encoding(true);
+
+        assert!(serializer.percent_encoding());
+
+        ("a/b c%", 'é', 1u8).serialize(&mut serializer).unwrap();
+
+        assert_eq!(&key, b"a%2Fb%20c%25:%C3%A9:01");
+
+        let escaped = to_vec_with(&("a/", 1u8), true).unwrap();
+        let unescaped = to_vec_with(&("a.", 1u8), true).unwrap();
+        assert!("a/" > "a." && escaped < unescaped);
+
+        let mut serializer = Serializer::new(Vec::new())
+            .with_percent_encoding(true)
+            .with_strict_ordering(true);
+
+        assert_eq!(serializer.validate(), Err(ConfigError::UnorderedEscapes));
+        assert!(matches!(
+            "a".serialize(&mut serializer),
+            Err(Error::Config(ConfigError::UnorderedEscapes))
+        ));
+
+        fn to_vec_with<T: Serialize>(value: &T, percent_encoding: bool) -> Result<Vec<u8>, Error> {
+            let mut key = Vec::new();
+            value.serialize(
+                &mut Serializer::new(&mut key).with_percent_encoding(percent_encoding),
+            )?;
+            Ok(key)
+        }
+    }
+
     #[test]
     fn test_deliminator_and_tuple_nesting() {
         let key = to_vec(&(("hello", "world"), (1u8, 2u8), ((), ()))).unwrap();
@@ -760,4 +1747,81 @@ mod tests {
     {
         data.windows(2).all(|w| w[0] <= w[1])
     }
+
+    #[test]
+    fn test_array() {
+        let key = to_array::<_, 12>(&(0xabu8, 0x1234u16, true)).unwrap();
+        assert_eq!(&key, b"ab:1234:true");
+
+        assert!(matches!(
+            to_array::<_, 4>(&(0xabu8, 0x1234u16)),
+            Err(Error::KeyTooLong(7, 4))
+        ));
+        assert!(matches!(
+            to_array::<_, 8>(&(0xabu8, 0x1234u16)),
+            Err(Error::KeyTooShort(7, 8))
+        ));
+    }
+
+    #[test]
+    fn test_fixed_len() {
+        assert_eq!(
+            fixed_len(&(1u8, -1i32, 1.5f64, true, ())),
+            Some(2 + 8 + 16 + 4 + 3)
+        );
+        assert_eq!(fixed_len(&()), Some(0));
+        assert_eq!(fixed_len(&("a", 1u8)), None);
+        assert_eq!(fixed_len(&'a'), None);
+    }
+
+    #[test]
+    fn test_to_writer_single_write() {
+        struct RecordingWriter {
+            writes: usize,
+            flushes: usize,
+            data: Vec<u8>,
+        }
+
+        impl Write for RecordingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.writes += 1;
+                self.data.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                self.flushes += 1;
+                Ok(())
+            }
+        }
+
+        let mut writer = RecordingWriter {
+            writes: 0,
+            flushes: 0,
+            data: Vec::new(),
+        };
+        to_writer(&mut writer, &("abc", 1u8, "def")).unwrap();
+        to_writer_flush(&mut writer, &("g", 2u8)).unwrap();
+
+        assert_eq!(writer.data, b"abc:01:defg:02");
+        assert_eq!(writer.writes, 2);
+        assert_eq!(writer.flushes, 1);
+    }
+
+    #[test]
+    fn test_bytes_written() {
+        let mut serializer = Serializer::new(Vec::new());
+        ("abc", 1u16).serialize(&mut serializer).unwrap();
+
+        assert_eq!(serializer.bytes_written(), 8);
+        assert_eq!(serializer.into_inner(), b"abc:0001");
+
+        let mut writer = CountingWriter::new(Vec::new());
+        to_writer(&mut writer, &("abc", 1u16)).unwrap();
+        assert_eq!(writer.count(), 8);
+
+        writer.reset();
+        assert_eq!(writer.count(), 0);
+        assert_eq!(writer.get_ref(), b"abc:0001");
+    }
 }
@@ -0,0 +1,192 @@
+//! Serde implementations for the crate's own key types
+//!
+//! The key types are implemented by hand rather than with serde's derive
+//! macros so that the crate does not require serde's `derive` feature.
+
+use serde::de::{DeserializeSeed, Deserializer, Error, Unexpected, Visitor};
+
+/// Implements `Serialize` and `Deserialize` for a struct with named fields
+/// the same way as serde's derive macros.
+macro_rules! impl_struct_serde {
+    ($name:ident $(<$param:ident>)? { $($field:ident: $type:ty),+ $(,)? }) => {
+        impl$(<$param: serde::Serialize>)? serde::Serialize for $name$(<$param>)? {
+            fn serialize<__S>(&self, serializer: __S) -> Result<__S::Ok, __S::Error>
+            where
+                __S: serde::Serializer,
+            {
+                use serde::ser::SerializeStruct;
+
+                let fields: &[&str] = &[$(stringify!($field)),+];
+                let mut state = serializer.serialize_struct(stringify!($name), fields.len())?;
+                $(state.serialize_field(stringify!($field), &self.$field)?;)+
+                state.end()
+            }
+        }
+
+        impl<'de $(, $param: serde::Deserialize<'de>)?> serde::Deserialize<'de>
+            for $name$(<$param>)?
+        {
+            fn deserialize<__D>(deserializer: __D) -> Result<Self, __D::Error>
+            where
+                __D: serde::Deserializer<'de>,
+            {
+                const FIELDS: &[&str] = &[$(stringify!($field)),+];
+
+                struct StructVisitor<__T>(std::marker::PhantomData<__T>);
+
+                impl<'de $(, $param: serde::Deserialize<'de>)?> serde::de::Visitor<'de>
+                    for StructVisitor<$name$(<$param>)?>
+                {
+                    type Value = $name$(<$param>)?;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        write!(formatter, "struct {}", stringify!($name))
+                    }
+
+                    fn visit_seq<__A>(self, mut seq: __A) -> Result<Self::Value, __A::Error>
+                    where
+                        __A: serde::de::SeqAccess<'de>,
+                    {
+                        $(
+                            let $field = seq.next_element::<$type>()?.ok_or_else(|| {
+                                let index = FIELDS
+                                    .iter()
+                                    .position(|field| *field == stringify!($field))
+                                    .unwrap_or_default();
+
+                                serde::de::Error::invalid_length(index, &self)
+                            })?;
+                        )+
+
+                        Ok($name { $($field),+ })
+                    }
+
+                    fn visit_map<__A>(self, mut map: __A) -> Result<Self::Value, __A::Error>
+                    where
+                        __A: serde::de::MapAccess<'de>,
+                    {
+                        $(let mut $field = None::<$type>;)+
+
+                        while let Some(key) = map.next_key::<String>()? {
+                            $(
+                                if key == stringify!($field) {
+                                    $field = Some(map.next_value()?);
+                                    continue;
+                                }
+                            )+
+
+                            map.next_value::<serde::de::IgnoredAny>()?;
+                        }
+
+                        $(
+                            let $field = $field
+                                .ok_or_else(|| serde::de::Error::missing_field(stringify!($field)))?;
+                        )+
+
+                        Ok($name { $($field),+ })
+                    }
+                }
+
+                deserializer.deserialize_struct(
+                    stringify!($name),
+                    FIELDS,
+                    StructVisitor(std::marker::PhantomData),
+                )
+            }
+        }
+    };
+}
+
+/// Deserializes the index of an enum variant from its name or index.
+pub(crate) struct VariantSeed(pub &'static [&'static str]);
+
+impl<'de> DeserializeSeed<'de> for VariantSeed {
+    type Value = usize;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for VariantSeed {
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("variant identifier")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        if v < self.0.len() as u64 {
+            Ok(v as usize)
+        } else {
+            Err(E::invalid_value(Unexpected::Unsigned(v), &self))
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.0
+            .iter()
+            .position(|variant| *variant == v)
+            .ok_or_else(|| E::unknown_variant(v, self.0))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match std::str::from_utf8(v) {
+            Ok(v) => self.visit_str(v),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::Config, expiry::ExpiryKey, graph::Direction, tree::NodeKey, versioned::VersionKey,
+    };
+
+    #[test]
+    fn test_struct() {
+        let key = VersionKey::new("doc".to_string(), 3);
+        let encoded = crate::to_vec(&key).unwrap();
+
+        assert_eq!(&encoded, b"doc:fffffffffffffffc");
+        assert_eq!(
+            crate::from_slice::<VersionKey<String>>(&encoded).unwrap(),
+            key
+        );
+        assert!(crate::from_slice::<ExpiryKey<u8>>(b"0000000000000001").is_err());
+        assert!(crate::from_slice::<NodeKey>(b"00000001").is_err());
+    }
+
+    #[test]
+    fn test_variant() {
+        assert_eq!(crate::to_vec(&Direction::In).unwrap(), b"in");
+        assert_eq!(
+            crate::from_slice::<Direction>(b"out").unwrap(),
+            Direction::Out
+        );
+        assert!(crate::from_slice::<Direction>(b"sideways").is_err());
+
+        let config = Config::new().with_strict_ordering(true);
+        let encoded = config.to_vec(&Direction::In).unwrap();
+
+        assert_eq!(&encoded, b"00000001");
+        assert_eq!(
+            config.from_slice::<Direction>(&encoded).unwrap(),
+            Direction::In
+        );
+        assert!(config.from_slice::<Direction>(b"00000002").is_err());
+    }
+}
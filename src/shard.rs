@@ -0,0 +1,179 @@
+//! Shard prefixes
+//!
+//! Keys that increase monotonically, such as timestamps, concentrate writes
+//! on a single range of the key space. Prefixing keys with a bucket derived
+//! from a hash of one of its fields spreads them across a fixed number of
+//! ranges. Reading all of the keys then requires a scan of each bucket.
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::shard::Sharded;
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let user_id = 1234u32;
+//! let key = Sharded::<_, 16>::new(&user_id, ("login", user_id))?;
+//! let serialized = strkey::to_vec(&key)?;
+//!
+//! assert_eq!(&serialized, b"0007:login:000004d2");
+//!
+//! let deserialized = strkey::from_slice::<Sharded<(&str, u32), 16>>(&serialized)?;
+//! assert_eq!(deserialized.bucket(), 7);
+//! assert_eq!(deserialized.into_inner(), ("login", 1234));
+//! # Ok(())
+//! # }
+//! ```
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+
+/// Value prefixed with a hash bucket number.
+///
+/// The bucket is encoded as a `u16` component before the value. `BUCKETS`
+/// is the number of buckets and must not be zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sharded<T, const BUCKETS: u16> {
+    bucket: u16,
+    value: T,
+}
+
+impl<T, const BUCKETS: u16> Sharded<T, BUCKETS> {
+    /// Wraps the value using the bucket computed from the given field.
+    ///
+    /// The field is typically a member of the value, such as a user ID.
+    pub fn new<F>(field: &F, value: T) -> Result<Self, Error>
+    where
+        F: ?Sized + Serialize,
+    {
+        Ok(Self {
+            bucket: bucket_of(field, BUCKETS)?,
+            value,
+        })
+    }
+
+    /// Returns the bucket number.
+    pub fn bucket(&self) -> u16 {
+        self.bucket
+    }
+
+    /// Returns a reference to the wrapped value.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Unwrap and return the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Returns the encoded key prefix for each bucket in order.
+    ///
+    /// Each prefix includes the trailing default deliminator. Scanning all
+    /// of the prefixes visits every key.
+    pub fn bucket_prefixes() -> Vec<Vec<u8>> {
+        (0..BUCKETS)
+            .map(|bucket| crate::to_prefix(&bucket).unwrap())
+            .collect()
+    }
+}
+
+impl<T: Serialize, const BUCKETS: u16> Serialize for Sharded<T, BUCKETS> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (self.bucket, &self.value).serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>, const BUCKETS: u16> Deserialize<'de> for Sharded<T, BUCKETS> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (bucket, value) = <(u16, T)>::deserialize(deserializer)?;
+
+        if bucket >= BUCKETS {
+            return Err(serde::de::Error::custom(format!(
+                "bucket {} out of range",
+                bucket
+            )));
+        }
+
+        Ok(Self { bucket, value })
+    }
+}
+
+/// Computes the bucket number of the given field.
+///
+/// The bucket is the FNV-1a hash of the field's default encoding modulo the
+/// number of buckets, so it is stable across platforms and releases.
+///
+/// Panics if `buckets` is zero.
+pub fn bucket_of<F>(field: &F, buckets: u16) -> Result<u16, Error>
+where
+    F: ?Sized + Serialize,
+{
+    assert!(buckets > 0, "number of buckets must not be zero");
+
+    Ok((crate::key::stable_hash64(field)? % buckets as u64) as u16)
+}
+
+pub(crate) fn fnv1a_64(data: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+
+    for &byte in data {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a_64() {
+        assert_eq!(fnv1a_64(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a_64(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a_64(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn test_bucket_of() {
+        for value in 0..100u32 {
+            let bucket = bucket_of(&value, 7).unwrap();
+            assert!(bucket < 7);
+            assert_eq!(bucket, bucket_of(&value, 7).unwrap());
+        }
+
+        assert_eq!(bucket_of(&"anything", 1).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let key = Sharded::<_, 4>::new(&"user1", ("user1", 5u8)).unwrap();
+        let serialized = crate::to_vec(&key).unwrap();
+        let deserialized = crate::from_slice::<Sharded<(String, u8), 4>>(&serialized).unwrap();
+
+        assert_eq!(deserialized.bucket(), key.bucket());
+        assert_eq!(deserialized.get(), &("user1".to_string(), 5));
+    }
+
+    #[test]
+    fn test_bucket_out_of_range() {
+        assert!(crate::from_slice::<Sharded<u8, 4>>(b"0004:01").is_err());
+    }
+
+    #[test]
+    fn test_bucket_prefixes() {
+        let prefixes = Sharded::<(), 3>::bucket_prefixes();
+
+        assert_eq!(
+            prefixes,
+            vec![b"0000:".to_vec(), b"0001:".to_vec(), b"0002:".to_vec()]
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Split points for parallel scans
+//!
+//! Generates keys that divide a range of keys into parts of roughly equal
+//! size by bisecting an integer component. The split keys are aligned to the
+//! component so that every key sharing the component's value is scanned by
+//! the same worker.
+//!
+//! Example:
+//!
+//! ```rust
+//! # fn main() -> Result<(), strkey::Error> {
+//! let splits = strkey::split::split_keys(&"events", 0u32..=99, 4)?;
+//!
+//! assert_eq!(splits.len(), 3);
+//! assert_eq!(&splits[0], b"events:00000019");
+//! assert_eq!(&splits[1], b"events:00000032");
+//! assert_eq!(&splits[2], b"events:0000004b");
+//! # Ok(())
+//! # }
+//! ```
+use std::ops::{Bound, RangeBounds};
+
+use serde::Serialize;
+
+use crate::error::Error;
+
+/// Integer component that can be bisected.
+pub trait Bisect: Copy + Serialize + private::Sealed {
+    /// Smallest value.
+    const MIN: Self;
+
+    /// Largest value.
+    const MAX: Self;
+
+    /// Returns the values that divide the inclusive range into the given
+    /// number of parts.
+    ///
+    /// Each value is the first value of a part after the first. Fewer values
+    /// are returned if the range is too small.
+    fn split_points(start: Self, end: Self, parts: usize) -> Vec<Self>;
+
+    /// Returns the next value, if any.
+    fn checked_successor(self) -> Option<Self>;
+
+    /// Returns the previous value, if any.
+    fn checked_predecessor(self) -> Option<Self>;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! bisect_impl {
+    ($($ty:ty),*) => {
+        $(
+            impl private::Sealed for $ty {}
+
+            impl Bisect for $ty {
+                const MIN: Self = <$ty>::MIN;
+
+                const MAX: Self = <$ty>::MAX;
+
+                fn split_points(start: Self, end: Self, parts: usize) -> Vec<Self> {
+                    let start = i128::from(start);
+                    let end = i128::from(end);
+
+                    if parts <= 1 || start >= end {
+                        return Vec::new();
+                    }
+
+                    let size = end - start + 1;
+                    let parts = (parts as i128).min(size);
+                    let mut points = Vec::with_capacity(parts as usize - 1);
+
+                    for index in 1..parts {
+                        points.push((start + size * index / parts) as $ty);
+                    }
+
+                    points
+                }
+
+                fn checked_successor(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn checked_predecessor(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+            }
+        )*
+    };
+}
+
+bisect_impl!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+/// Returns the values that divide the range into the given number of parts.
+///
+/// Returns an empty vector if the range is empty or `parts` is less than 2.
+pub fn split_values<V, R>(range: R, parts: usize) -> Vec<V>
+where
+    V: Bisect,
+    R: RangeBounds<V>,
+{
+    let start = match range.start_bound() {
+        Bound::Included(value) => Some(*value),
+        Bound::Excluded(value) => value.checked_successor(),
+        Bound::Unbounded => Some(V::MIN),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(value) => Some(*value),
+        Bound::Excluded(value) => value.checked_predecessor(),
+        Bound::Unbounded => Some(V::MAX),
+    };
+
+    match (start, end) {
+        (Some(start), Some(end)) => V::split_points(start, end, parts),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns up to `parts - 1` split keys for scanning keys with the given
+/// leading components and an integer component in the range.
+///
+/// Each split key is the start of a part and the end of the part before it.
+/// Keys with components after the integer component are scanned by the part
+/// that contains the integer component's value.
+pub fn split_keys<P, V, R>(prefix: &P, range: R, parts: usize) -> Result<Vec<Vec<u8>>, Error>
+where
+    P: ?Sized + Serialize,
+    V: Bisect,
+    R: RangeBounds<V>,
+{
+    split_values(range, parts)
+        .into_iter()
+        .map(|value| crate::to_vec(&(prefix, value)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_values() {
+        assert_eq!(split_values(0u8..100, 4), vec![25, 50, 75]);
+        assert_eq!(split_values(0u8..=2, 5), vec![1, 2]);
+        assert_eq!(split_values::<u8, _>(.., 2), vec![128]);
+        assert_eq!(split_values::<i8, _>(.., 2), vec![0]);
+        assert_eq!(
+            split_values::<u64, _>(.., 4),
+            vec![1 << 62, 1 << 63, 3 << 62]
+        );
+        assert!(split_values(5u8..5, 4).is_empty());
+        assert!(split_values(0u8..100, 1).is_empty());
+        assert!(split_values((Bound::Excluded(u8::MAX), Bound::Unbounded), 2).is_empty());
+    }
+
+    #[test]
+    fn test_split_keys() {
+        let splits = split_keys(&"a", 0u8..=255, 2).unwrap();
+        let range = (
+            Bound::Included(crate::to_vec(&("a", 0u8, "x")).unwrap()),
+            Bound::Excluded(splits[0].clone()),
+        );
+
+        assert_eq!(splits, vec![b"a:80".to_vec()]);
+        assert!(range.contains(&crate::to_vec(&("a", 0x7fu8, "z")).unwrap()));
+        assert!(!range.contains(&crate::to_vec(&("a", 0x80u8, "")).unwrap()));
+    }
+}
@@ -0,0 +1,323 @@
+//! Typed tables
+//!
+//! A [`Table`] names a group of keys of the same type. Keys of a table are
+//! laid out as `table_name:key` so each table occupies its own range.
+//! [`TableRegistry`] identifies the table of an arbitrary encoded key and
+//! [`KeyDispatcher`] decodes it with a handler registered for the table.
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::tables::{Table, TableRegistry};
+//!
+//! const ACCOUNTS: Table<u32, String> = Table::new("account");
+//! const SESSIONS: Table<(u32, u64), ()> = Table::new("session");
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let key = ACCOUNTS.encode_key(&1234)?;
+//! assert_eq!(&key, b"account:000004d2");
+//! assert_eq!(ACCOUNTS.decode_key(&key)?, 1234);
+//! assert!(SESSIONS.decode_key(&key).is_err());
+//!
+//! let mut registry = TableRegistry::new();
+//! registry.register(&ACCOUNTS)?;
+//! registry.register(&SESSIONS)?;
+//! assert_eq!(registry.identify(&key), Some("account"));
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    collections::{BTreeSet, HashMap},
+    marker::PhantomData,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{error::Error, range::ByteRange};
+
+/// Table of keys of type `K` with values of type `V`.
+///
+/// The value type is not used for encoding and only documents the table's
+/// contents.
+pub struct Table<K, V> {
+    name: &'static str,
+    _types: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K, V> Table<K, V> {
+    /// Construct a table with the given name.
+    ///
+    /// The name must not contain the deliminator.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _types: PhantomData,
+        }
+    }
+
+    /// Returns the name of the table.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the encoded prefix of the keys of the table.
+    ///
+    /// The prefix includes the trailing default deliminator.
+    pub fn prefix(&self) -> Result<Vec<u8>, Error> {
+        crate::to_prefix(self.name)
+    }
+
+    /// Returns the encoded range of the keys of the table.
+    pub fn prefix_range(&self) -> Result<ByteRange, Error> {
+        Ok(crate::range::prefix_range(&self.prefix()?))
+    }
+}
+
+impl<K: Serialize, V> Table<K, V> {
+    /// Encodes a key of the table.
+    pub fn encode_key(&self, key: &K) -> Result<Vec<u8>, Error> {
+        crate::to_vec(&(self.name, key))
+    }
+}
+
+impl<'de, K: Deserialize<'de>, V> Table<K, V> {
+    /// Decodes a key of the table.
+    ///
+    /// Returns an error if the key belongs to another table.
+    pub fn decode_key(&self, key: &'de [u8]) -> Result<K, Error> {
+        let (name, key) = crate::from_slice::<(&str, K)>(key)?;
+
+        if name != self.name {
+            return Err(Error::Data(name.to_string()));
+        }
+
+        Ok(key)
+    }
+}
+
+impl<K, V> Clone for Table<K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for Table<K, V> {}
+
+impl<K, V> std::fmt::Debug for Table<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Table").field("name", &self.name).finish()
+    }
+}
+
+/// Set of tables with unique names.
+#[derive(Debug, Clone, Default)]
+pub struct TableRegistry {
+    names: BTreeSet<&'static str>,
+}
+
+impl TableRegistry {
+    /// Construct an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a table to the registry.
+    ///
+    /// Returns an error if a table with the same name was already added or
+    /// the name contains the default deliminator.
+    pub fn register<K, V>(&mut self, table: &Table<K, V>) -> Result<(), Error> {
+        if table.name.contains(':') || !self.names.insert(table.name) {
+            return Err(Error::Other(format!("invalid table name {}", table.name)));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the names of the registered tables in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.names.iter().copied()
+    }
+
+    /// Returns the name of the table the encoded key belongs to.
+    pub fn identify(&self, key: &[u8]) -> Option<&'static str> {
+        let name = key.split(|&byte| byte == b':').next()?;
+        let name = std::str::from_utf8(name).ok()?;
+
+        self.names.get(name).copied()
+    }
+}
+
+type Handler<'a, O> = Box<dyn FnMut(&[u8]) -> Result<O, Error> + 'a>;
+
+/// Routes encoded keys to typed handlers by the name in the first component.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::tables::{KeyDispatcher, Table};
+///
+/// const ACCOUNTS: Table<u32, ()> = Table::new("account");
+/// const SESSIONS: Table<(u32, u64), ()> = Table::new("session");
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let mut dispatcher = KeyDispatcher::new();
+/// dispatcher.register_table(&ACCOUNTS, |id| format!("account {}", id))?;
+/// dispatcher.register_table(&SESSIONS, |(id, session)| format!("session {} {}", id, session))?;
+///
+/// let key = ACCOUNTS.encode_key(&1234)?;
+/// assert_eq!(dispatcher.dispatch(&key)?, "account 1234");
+/// # Ok(())
+/// # }
+/// ```
+pub struct KeyDispatcher<'a, O> {
+    handlers: HashMap<&'static str, Handler<'a, O>>,
+}
+
+impl<'a, O> KeyDispatcher<'a, O> {
+    /// Construct a dispatcher without handlers.
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// Adds a handler of keys whose first component is the given name.
+    ///
+    /// The handler receives the rest of the key decoded as `K`. Returns an
+    /// error if a handler for the name was already added or the name
+    /// contains the default deliminator.
+    pub fn register<K, F>(&mut self, name: &'static str, mut handler: F) -> Result<(), Error>
+    where
+        K: DeserializeOwned,
+        F: FnMut(K) -> O + 'a,
+    {
+        if name.contains(':') || self.handlers.contains_key(name) {
+            return Err(Error::Other(format!("invalid handler name {}", name)));
+        }
+
+        self.handlers.insert(
+            name,
+            Box::new(move |key| {
+                let (_name, key) = crate::from_slice::<(String, K)>(key)?;
+                Ok(handler(key))
+            }),
+        );
+
+        Ok(())
+    }
+
+    /// Adds a handler of the keys of the table.
+    pub fn register_table<K, V, F>(&mut self, table: &Table<K, V>, handler: F) -> Result<(), Error>
+    where
+        K: DeserializeOwned,
+        F: FnMut(K) -> O + 'a,
+    {
+        self.register(table.name, handler)
+    }
+
+    /// Returns whether a handler is registered for the given name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+
+    /// Decodes the key and passes it to the handler registered for its
+    /// first component.
+    ///
+    /// Returns [`Error::Data`] if no handler is registered for the name.
+    pub fn dispatch(&mut self, key: &[u8]) -> Result<O, Error> {
+        let name = key.split(|&byte| byte == b':').next().unwrap_or_default();
+        let name = std::str::from_utf8(name)?;
+
+        match self.handlers.get_mut(name) {
+            Some(handler) => handler(key),
+            None => Err(Error::Data(format!("no handler for {}", name))),
+        }
+    }
+}
+
+impl<'a, O> Default for KeyDispatcher<'a, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, O> std::fmt::Debug for KeyDispatcher<'a, O> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names = self.handlers.keys().collect::<Vec<_>>();
+        names.sort_unstable();
+
+        f.debug_struct("KeyDispatcher")
+            .field("handlers", &names)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeBounds;
+
+    use super::*;
+
+    const USERS: Table<(String, u8), ()> = Table::new("user");
+    const USER_EMAILS: Table<String, u32> = Table::new("user_email");
+
+    #[test]
+    fn test_table() {
+        let key = USERS.encode_key(&("a".to_string(), 1)).unwrap();
+
+        assert_eq!(&key, b"user:a:01");
+        assert_eq!(USERS.decode_key(&key).unwrap(), ("a".to_string(), 1));
+        assert!(USER_EMAILS.decode_key(&key).is_err());
+
+        let range = USERS.prefix_range().unwrap();
+        assert!(range.contains(&key));
+        assert!(!range.contains(&USER_EMAILS.encode_key(&"a".to_string()).unwrap()));
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = TableRegistry::new();
+        registry.register(&USERS).unwrap();
+        registry.register(&USER_EMAILS).unwrap();
+
+        assert!(registry.register(&USERS).is_err());
+        assert!(registry.register(&Table::<u8, ()>::new("a:b")).is_err());
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            vec!["user", "user_email"]
+        );
+
+        assert_eq!(registry.identify(b"user_email:a"), Some("user_email"));
+        assert_eq!(registry.identify(b"user:a:01"), Some("user"));
+        assert_eq!(registry.identify(b"other:a"), None);
+    }
+
+    #[test]
+    fn test_dispatcher() {
+        let mut users = Vec::new();
+        let mut emails = Vec::new();
+
+        {
+            let mut dispatcher = KeyDispatcher::new();
+            dispatcher
+                .register_table(&USERS, |key| users.push(key))
+                .unwrap();
+            dispatcher
+                .register_table(&USER_EMAILS, |key| emails.push(key))
+                .unwrap();
+
+            assert!(dispatcher.contains("user"));
+            assert!(dispatcher.register_table(&USERS, |_| ()).is_err());
+
+            dispatcher.dispatch(b"user:a:01").unwrap();
+            dispatcher.dispatch(b"user_email:b").unwrap();
+            dispatcher.dispatch(b"user:c:02").unwrap();
+
+            assert!(dispatcher.dispatch(b"other:a").is_err());
+            assert!(dispatcher.dispatch(b"user:a").is_err());
+        }
+
+        assert_eq!(users, vec![("a".to_string(), 1), ("c".to_string(), 2)]);
+        assert_eq!(emails, vec!["b".to_string()]);
+    }
+}
@@ -0,0 +1,204 @@
+//! Tenant key spaces
+//!
+//! A [`KeySpace`] encodes keys with a leading [`TenantId`] component and
+//! rejects keys of other tenants when decoding, so keys of a tenant cannot be
+//! built or read through another tenant's key space by mistake.
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::tenant::{KeySpace, TenantId};
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let acme = KeySpace::for_tenant(TenantId::parse("acme000000000001")?);
+//! let other = KeySpace::for_tenant(TenantId::parse("other00000000002")?);
+//!
+//! let key = acme.to_vec(&("user", 5u8))?;
+//! assert_eq!(&key, b"acme000000000001:user:05");
+//!
+//! assert_eq!(acme.from_slice::<(&str, u8)>(&key)?, ("user", 5));
+//! assert!(other.from_slice::<(&str, u8)>(&key).is_err());
+//! # Ok(())
+//! # }
+//! ```
+use std::fmt::Display;
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{error::Error, range::ByteRange};
+
+/// Tenant identifier.
+///
+/// A tenant ID is exactly [`TenantId::LENGTH`] characters of lowercase ASCII
+/// letters and digits. The restricted alphabet guarantees the ID cannot
+/// contain a deliminator and the fixed width guarantees no ID is a prefix of
+/// another.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TenantId(String);
+
+impl TenantId {
+    /// Number of characters in a tenant ID.
+    pub const LENGTH: usize = 16;
+
+    /// Validates and constructs a tenant ID.
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        if !Self::is_valid(value) {
+            return Err(Error::Data(format!("invalid tenant ID {:?}", value)));
+        }
+
+        Ok(Self(value.to_string()))
+    }
+
+    /// Constructs a tenant ID from a number formatted as hexadecimal digits.
+    pub fn from_u64(value: u64) -> Self {
+        Self(format!("{:016x}", value))
+    }
+
+    /// Returns whether the value is a valid tenant ID.
+    pub fn is_valid(value: &str) -> bool {
+        value.len() == Self::LENGTH
+            && value
+                .bytes()
+                .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit())
+    }
+
+    /// Returns the ID as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for TenantId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for TenantId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for TenantId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        if !Self::is_valid(&value) {
+            return Err(D::Error::custom("invalid tenant ID"));
+        }
+
+        Ok(Self(value))
+    }
+}
+
+/// Key space of a single tenant.
+///
+/// Keys are encoded as `(tenant, key)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeySpace {
+    tenant: TenantId,
+}
+
+impl KeySpace {
+    /// Construct the key space of the given tenant.
+    pub fn for_tenant(tenant: TenantId) -> Self {
+        Self { tenant }
+    }
+
+    /// Returns the tenant.
+    pub fn tenant(&self) -> &TenantId {
+        &self.tenant
+    }
+
+    /// Serializes a key prefixed with the tenant.
+    pub fn to_vec<T>(&self, key: &T) -> Result<Vec<u8>, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        crate::to_vec(&(&self.tenant, key))
+    }
+
+    /// Deserializes a key prefixed with the tenant.
+    ///
+    /// Returns [`Error::TenantMismatch`] if the key belongs to another tenant.
+    /// The error names only this tenant, so the other tenant's ID is not
+    /// leaked.
+    pub fn from_slice<'a, T>(&self, value: &'a [u8]) -> Result<T, Error>
+    where
+        T: Deserialize<'a>,
+    {
+        let (tenant, key) = crate::from_slice::<(TenantId, T)>(value)?;
+
+        if tenant != self.tenant {
+            return Err(Error::TenantMismatch(self.tenant.to_string()));
+        }
+
+        Ok(key)
+    }
+
+    /// Returns the encoded prefix of the given key prefix.
+    ///
+    /// The prefix includes the trailing default deliminator.
+    pub fn prefix<P>(&self, prefix: &P) -> Result<Vec<u8>, Error>
+    where
+        P: ?Sized + Serialize,
+    {
+        crate::to_prefix(&(&self.tenant, prefix))
+    }
+
+    /// Returns the encoded range of all keys of the tenant.
+    pub fn range(&self) -> Result<ByteRange, Error> {
+        Ok(crate::range::prefix_range(&self.prefix(&())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeBounds;
+
+    use super::*;
+
+    #[test]
+    fn test_tenant_id() {
+        assert!(TenantId::parse("abcdefghij012345").is_ok());
+        assert!(TenantId::parse("abc").is_err());
+        assert!(TenantId::parse("ABCDEFGHIJ012345").is_err());
+        assert!(TenantId::parse("abcdefghij01234:").is_err());
+        assert_eq!(TenantId::from_u64(0xab).as_str(), "00000000000000ab");
+
+        assert!(crate::from_slice::<TenantId>(b"abcdefghij012345").is_ok());
+        assert!(crate::from_slice::<TenantId>(b"abc").is_err());
+    }
+
+    #[test]
+    fn test_key_space() {
+        let space = KeySpace::for_tenant(TenantId::from_u64(1));
+        let other = KeySpace::for_tenant(TenantId::from_u64(2));
+        let key = space.to_vec(&("doc", 3u8)).unwrap();
+
+        assert_eq!(&key, b"0000000000000001:doc:03");
+        assert_eq!(
+            space.from_slice::<(String, u8)>(&key).unwrap(),
+            ("doc".to_string(), 3)
+        );
+        assert_eq!(
+            other
+                .from_slice::<(String, u8)>(&key)
+                .unwrap_err()
+                .to_string(),
+            "Key does not belong to tenant 0000000000000002"
+        );
+        assert!(space.from_slice::<(String, u8)>(b"doc:03").is_err());
+
+        assert_eq!(&space.prefix(&"doc").unwrap(), b"0000000000000001:doc:");
+        assert!(space.range().unwrap().contains(&key));
+        assert!(!other.range().unwrap().contains(&key));
+    }
+}
@@ -0,0 +1,388 @@
+//! Testing helpers
+//!
+//! Helpers for verifying that a key type's encoding preserves its order.
+//!
+//! The [`assert_strkey_order!`](crate::assert_strkey_order) macro generates a
+//! test from the [`BoundaryValues`] of each field of a key type. For finer
+//! control, [`check_order_consistency`] checks a given set of samples.
+//!
+//! Example:
+//!
+//! ```rust
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
+//! struct EventKey {
+//!     day: u16,
+//!     sequence: i32,
+//! }
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let samples = [
+//!     EventKey { day: 1, sequence: -5 },
+//!     EventKey { day: 1, sequence: 3 },
+//!     EventKey { day: 0, sequence: 100 },
+//! ];
+//!
+//! assert!(strkey::testing::check_order_consistency(&samples)?.is_none());
+//! # Ok(())
+//! # }
+//! ```
+use std::{cmp::Ordering, fmt::Debug};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::Error;
+
+/// Pair of values whose order disagrees with the order of their keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderViolation<'a, T> {
+    /// The smaller or equal value.
+    pub left: &'a T,
+    /// The larger or equal value.
+    pub right: &'a T,
+    /// Encoded key of `left`.
+    pub left_key: Vec<u8>,
+    /// Encoded key of `right`.
+    pub right_key: Vec<u8>,
+    /// Order of `left` compared to `right`.
+    pub value_order: Ordering,
+    /// Order of `left_key` compared to `right_key`.
+    pub key_order: Ordering,
+}
+
+impl<'a, T: Debug> std::fmt::Display for OrderViolation<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} is {:?} {:?} but key {:?} is {:?} key {:?}",
+            self.left,
+            self.value_order,
+            self.right,
+            String::from_utf8_lossy(&self.left_key),
+            self.key_order,
+            String::from_utf8_lossy(&self.right_key)
+        )
+    }
+}
+
+/// Checks that the order of the values agrees with the byte order of their
+/// encoded keys.
+///
+/// The samples are sorted by value and each adjacent pair is compared.
+/// Returns the first pair that disagrees, or `None` if all pairs agree.
+/// Returns an error if a value cannot be encoded.
+pub fn check_order_consistency<T>(samples: &[T]) -> Result<Option<OrderViolation<'_, T>>, Error>
+where
+    T: Ord + Serialize,
+{
+    let mut entries = samples
+        .iter()
+        .map(|value| crate::to_vec(value).map(|key| (value, key)))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    for pair in entries.windows(2) {
+        let (left, left_key) = &pair[0];
+        let (right, right_key) = &pair[1];
+        let value_order = left.cmp(right);
+        let key_order = left_key.cmp(right_key);
+
+        if value_order != key_order {
+            return Ok(Some(OrderViolation {
+                left,
+                right,
+                left_key: left_key.clone(),
+                right_key: right_key.clone(),
+                value_order,
+                key_order,
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Panics if the order of the samples disagrees with their keys or a key does
+/// not decode to its value.
+///
+/// This is used by [`assert_strkey_order!`](crate::assert_strkey_order).
+pub fn assert_order_and_round_trip<T>(samples: &[T])
+where
+    T: Ord + Serialize + DeserializeOwned + Debug,
+{
+    if let Some(violation) = check_order_consistency(samples).unwrap() {
+        panic!("order not preserved: {}", violation);
+    }
+
+    for value in samples {
+        let key = crate::to_vec(value).unwrap();
+        let decoded = crate::from_slice::<T>(&key).unwrap();
+
+        assert_eq!(
+            &decoded,
+            value,
+            "round trip failed for key {:?}",
+            String::from_utf8_lossy(&key)
+        );
+    }
+}
+
+/// Values at the edges of a type's range for generating test samples.
+pub trait BoundaryValues: Sized {
+    /// Returns the boundary values.
+    fn boundary_values() -> Vec<Self>;
+}
+
+macro_rules! unsigned_boundary_values {
+    ($($type:ty),*) => {
+        $(
+            impl BoundaryValues for $type {
+                fn boundary_values() -> Vec<Self> {
+                    vec![0, 1, <$type>::MAX / 2, <$type>::MAX - 1, <$type>::MAX]
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! signed_boundary_values {
+    ($($type:ty),*) => {
+        $(
+            impl BoundaryValues for $type {
+                fn boundary_values() -> Vec<Self> {
+                    vec![
+                        <$type>::MIN,
+                        <$type>::MIN + 1,
+                        -1,
+                        0,
+                        1,
+                        <$type>::MAX - 1,
+                        <$type>::MAX,
+                    ]
+                }
+            }
+        )*
+    };
+}
+
+unsigned_boundary_values!(u8, u16, u32, u64, u128);
+signed_boundary_values!(i8, i16, i32, i64, i128);
+
+impl BoundaryValues for bool {
+    fn boundary_values() -> Vec<Self> {
+        vec![false, true]
+    }
+}
+
+impl BoundaryValues for () {
+    fn boundary_values() -> Vec<Self> {
+        vec![()]
+    }
+}
+
+/// Includes strings with characters that sort before the deliminator, such
+/// as digits and punctuation, which break the order of a string that is
+/// followed by another component: `("", 1)` sorts before `("0", 0)` but its
+/// key `:01` sorts after `0:00`.
+impl BoundaryValues for String {
+    fn boundary_values() -> Vec<Self> {
+        vec![
+            String::new(),
+            " ".to_string(),
+            "!".to_string(),
+            "0".to_string(),
+            "9".to_string(),
+            "a".to_string(),
+            "aa".to_string(),
+            "b".to_string(),
+            "z".repeat(256),
+            "\u{10ffff}".to_string(),
+        ]
+    }
+}
+
+impl BoundaryValues for char {
+    fn boundary_values() -> Vec<Self> {
+        vec!['a', 'z', '\u{7f}', '\u{80}', '\u{10ffff}']
+    }
+}
+
+macro_rules! tuple_boundary_values {
+    ($($name:ident)+) => {
+        impl<$($name: BoundaryValues + Clone),+> BoundaryValues for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn boundary_values() -> Vec<Self> {
+                let mut output = Vec::new();
+                $crate::__boundary_product!(output; (); $($name)+);
+                output
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __boundary_product {
+    ($output:ident; ($($bound:ident)*);) => {
+        $output.push(($(::std::clone::Clone::clone(&$bound),)*));
+    };
+    ($output:ident; ($($bound:ident)*); $next:ident $($rest:ident)*) => {
+        for $next in <$next as $crate::testing::BoundaryValues>::boundary_values() {
+            $crate::__boundary_product!($output; ($($bound)* $next); $($rest)*);
+        }
+    };
+}
+
+tuple_boundary_values!(A);
+tuple_boundary_values!(A B);
+tuple_boundary_values!(A B C);
+tuple_boundary_values!(A B C D);
+
+/// Generates a test asserting that a key type preserves order and round
+/// trips.
+///
+/// The samples are every combination of the [`BoundaryValues`] of the fields.
+/// For a struct, list the struct name and its fields. For other types, such
+/// as tuples, give the type which must implement [`BoundaryValues`]. The type
+/// must implement `Ord`, `Serialize`, `DeserializeOwned`, and `Debug`, and
+/// the fields must implement [`BoundaryValues`] and `Clone`.
+///
+/// The test panics with the first pair of samples that is out of order. A
+/// string field that is not the last field is reported because strings
+/// containing characters that sort before the deliminator are out of order;
+/// attributes such as `#[should_panic]` can be given before the test name to
+/// mark a known break as an expected failure.
+///
+/// Example:
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+/// struct EventKey {
+///     day: u16,
+///     sequence: i32,
+///     kind: String,
+/// }
+///
+/// strkey::assert_strkey_order!(test_event_key_order, EventKey { day, sequence, kind });
+/// strkey::assert_strkey_order!(test_tuple_order, (u8, i64));
+/// strkey::assert_strkey_order!(
+///     #[should_panic(expected = "order not preserved")]
+///     test_leading_string_order,
+///     (String, u8)
+/// );
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! assert_strkey_order {
+    ($(#[$attr:meta])* $test:ident, $type:ident { $($field:ident),+ $(,)? }) => {
+        #[test]
+        $(#[$attr])*
+        fn $test() {
+            let mut samples = ::std::vec::Vec::new();
+            $crate::__assert_strkey_order_fields!(samples; $type; (); $($field)+);
+            $crate::testing::assert_order_and_round_trip::<$type>(&samples);
+        }
+    };
+    ($(#[$attr:meta])* $test:ident, $type:ty) => {
+        #[test]
+        $(#[$attr])*
+        fn $test() {
+            let samples = <$type as $crate::testing::BoundaryValues>::boundary_values();
+            $crate::testing::assert_order_and_round_trip::<$type>(&samples);
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_strkey_order_fields {
+    ($samples:ident; $type:ident; ($($bound:ident)*);) => {
+        $samples.push($type {
+            $($bound: ::std::clone::Clone::clone(&$bound)),*
+        });
+    };
+    ($samples:ident; $type:ident; ($($bound:ident)*); $next:ident $($rest:ident)*) => {
+        for $next in $crate::testing::BoundaryValues::boundary_values() {
+            $crate::__assert_strkey_order_fields!($samples; $type; ($($bound)* $next); $($rest)*);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consistent() {
+        let samples = [i32::MAX, -1, 0, i32::MIN, 1, 0];
+
+        assert_eq!(check_order_consistency(&samples).unwrap(), None);
+        assert_eq!(check_order_consistency::<u8>(&[]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_violation() {
+        #[derive(Debug, Serialize, PartialEq, Eq)]
+        struct Key {
+            a: u8,
+            b: u8,
+        }
+
+        impl PartialOrd for Key {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for Key {
+            fn cmp(&self, other: &Self) -> Ordering {
+                (self.b, self.a).cmp(&(other.b, other.a))
+            }
+        }
+
+        let samples = [Key { a: 1, b: 2 }, Key { a: 2, b: 1 }];
+        let violation = check_order_consistency(&samples).unwrap().unwrap();
+
+        assert_eq!(violation.left, &samples[1]);
+        assert_eq!(violation.right, &samples[0]);
+        assert_eq!(violation.value_order, Ordering::Less);
+        assert_eq!(violation.key_order, Ordering::Greater);
+        assert_eq!(
+            violation.to_string(),
+            "Key { a: 2, b: 1 } is Less Key { a: 1, b: 2 } but key \"02:01\" is Greater key \"01:02\""
+        );
+    }
+
+    #[derive(Debug, Serialize, serde::Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+    struct MyKey {
+        flag: bool,
+        value: i16,
+        tail: char,
+        name: String,
+    }
+
+    crate::assert_strkey_order!(
+        test_struct_order,
+        MyKey {
+            flag,
+            value,
+            tail,
+            name
+        }
+    );
+    crate::assert_strkey_order!(test_tuple_order, (u64, i128, (), String));
+    crate::assert_strkey_order!(
+        #[should_panic(expected = "order not preserved")]
+        test_tuple_leading_string_order,
+        (u64, String, i128, ())
+    );
+
+    #[test]
+    #[should_panic(expected = "order not preserved")]
+    fn test_assert_violation() {
+        assert_order_and_round_trip(&[("a!".to_string(), 0u8), ("a".to_string(), 1)]);
+    }
+}
@@ -0,0 +1,292 @@
+//! Time series keys
+//!
+//! Time series keys are laid out as `metric:tags:timestamp` where the tags
+//! are the series' tag pairs sorted by name and joined as `name=value` pairs
+//! separated by commas. The timestamp is a [`ReverseTimestamp`] so scanning
+//! a series returns the newest points first.
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::timeseries::Series;
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let series = Series::new("cpu")
+//!     .with_tag("region", "us")
+//!     .with_tag("host", "a");
+//! let key = strkey::to_vec(&series.key(1000))?;
+//!
+//! assert_eq!(&key, b"cpu:host=a,region=us:fffffffffffffc17");
+//!
+//! let range = series.window_range(0..2000)?.unwrap();
+//! assert!(range.contains(&key));
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    ops::{Range, RangeInclusive},
+    time::Duration,
+};
+
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{error::Error, types::ReverseTimestamp};
+
+/// Metric name and tag pairs that identify a time series.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Series {
+    metric: String,
+    tags: Vec<(String, String)>,
+}
+
+impl Series {
+    /// Construct a series with the given metric name and no tags.
+    ///
+    /// The metric name must not contain the default deliminator or the
+    /// series will fail to serialize.
+    pub fn new<S: Into<String>>(metric: S) -> Self {
+        Self {
+            metric: metric.into(),
+            tags: Vec::new(),
+        }
+    }
+
+    /// Adds a tag, replacing any existing tag with the same name, and returns the series.
+    pub fn with_tag<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        let name = name.into();
+        let value = value.into();
+
+        match self.tags.binary_search_by(|tag| tag.0.cmp(&name)) {
+            Ok(index) => self.tags[index].1 = value,
+            Err(index) => self.tags.insert(index, (name, value)),
+        }
+
+        self
+    }
+
+    /// Returns the metric name.
+    pub fn metric(&self) -> &str {
+        &self.metric
+    }
+
+    /// Returns the tag pairs sorted by name.
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    /// Returns a key for a point at the given timestamp in milliseconds.
+    pub fn key(&self, timestamp: u64) -> SeriesKey {
+        SeriesKey {
+            series: self.clone(),
+            timestamp: ReverseTimestamp::from_millis(timestamp),
+        }
+    }
+
+    /// Returns the encoded prefix of all keys in the series.
+    ///
+    /// The prefix includes the trailing default deliminator.
+    pub fn prefix(&self) -> Result<Vec<u8>, Error> {
+        crate::to_prefix(self)
+    }
+
+    /// Returns the encoded key range of the points in the given time window.
+    ///
+    /// The window is a range of milliseconds where the start is inclusive and
+    /// the end is exclusive. The returned range is inclusive on both ends.
+    /// Returns `None` if the window is empty.
+    pub fn window_range(
+        &self,
+        window: Range<u64>,
+    ) -> Result<Option<RangeInclusive<Vec<u8>>>, Error> {
+        if window.start >= window.end {
+            return Ok(None);
+        }
+
+        let start = crate::to_vec(&self.key(window.end - 1))?;
+        let end = crate::to_vec(&self.key(window.start))?;
+
+        Ok(Some(start..=end))
+    }
+
+    fn encode_tags(&self) -> Option<String> {
+        let mut output = String::new();
+
+        for (index, (name, value)) in self.tags.iter().enumerate() {
+            if !is_valid_tag_part(name) || !is_valid_tag_part(value) {
+                return None;
+            }
+
+            if index != 0 {
+                output.push(',');
+            }
+
+            output.push_str(name);
+            output.push('=');
+            output.push_str(value);
+        }
+
+        Some(output)
+    }
+
+    fn decode_tags(input: &str) -> Option<Vec<(String, String)>> {
+        let mut tags = Vec::new();
+
+        if input.is_empty() {
+            return Some(tags);
+        }
+
+        for pair in input.split(',') {
+            let (name, value) = pair.split_once('=')?;
+
+            tags.push((name.to_string(), value.to_string()));
+        }
+
+        Some(tags)
+    }
+}
+
+fn is_valid_tag_part(value: &str) -> bool {
+    !value.contains([',', '=', ':'])
+}
+
+impl Serialize for Series {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.metric.contains(':') {
+            return Err(S::Error::custom("metric contains reserved character"));
+        }
+
+        let tags = self
+            .encode_tags()
+            .ok_or_else(|| S::Error::custom("tag contains reserved character"))?;
+
+        (&self.metric, tags).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Series {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (metric, tags) = <(String, String)>::deserialize(deserializer)?;
+        let tags = Series::decode_tags(&tags).ok_or_else(|| D::Error::custom("invalid tags"))?;
+
+        Ok(Self { metric, tags })
+    }
+}
+
+/// Key of a point in a time series.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SeriesKey {
+    /// The series of the point.
+    pub series: Series,
+
+    /// The timestamp of the point.
+    pub timestamp: ReverseTimestamp,
+}
+
+impl_struct_serde!(SeriesKey {
+    series: Series,
+    timestamp: ReverseTimestamp,
+});
+
+/// Rounds the timestamp in milliseconds down to a multiple of the granularity.
+///
+/// A granularity shorter than a millisecond leaves the timestamp unchanged.
+pub fn bucket(timestamp: u64, granularity: Duration) -> u64 {
+    let granularity = granularity.as_millis().min(u64::MAX as u128) as u64;
+
+    if granularity == 0 {
+        timestamp
+    } else {
+        timestamp - timestamp % granularity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series_tags() {
+        let series = Series::new("cpu")
+            .with_tag("b", "1")
+            .with_tag("a", "2")
+            .with_tag("b", "3");
+
+        assert_eq!(series.metric(), "cpu");
+        assert_eq!(
+            series.tags(),
+            &[
+                ("a".to_string(), "2".to_string()),
+                ("b".to_string(), "3".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_round_trip() {
+        let key = Series::new("cpu").with_tag("host", "a").key(5);
+        let encoded = crate::to_vec(&key).unwrap();
+
+        assert_eq!(&encoded, b"cpu:host=a:fffffffffffffffa");
+        assert_eq!(crate::from_slice::<SeriesKey>(&encoded).unwrap(), key);
+
+        let key = Series::new("mem").key(5);
+        let encoded = crate::to_vec(&key).unwrap();
+
+        assert_eq!(&encoded, b"mem::fffffffffffffffa");
+        assert_eq!(crate::from_slice::<SeriesKey>(&encoded).unwrap(), key);
+    }
+
+    #[test]
+    fn test_invalid_tags() {
+        assert!(crate::to_vec(&Series::new("cpu").with_tag("a,b", "1")).is_err());
+        assert!(crate::to_vec(&Series::new("cpu").with_tag("a", "1=2")).is_err());
+        assert!(crate::from_slice::<Series>(b"cpu:a").is_err());
+    }
+
+    #[test]
+    fn test_invalid_metric() {
+        assert!(crate::to_vec(&Series::new("cpu:load")).is_err());
+        assert!(crate::to_vec(&Series::new("cpu:load").key(1000)).is_err());
+        assert!(Series::new("cpu:load").prefix().is_err());
+    }
+
+    #[test]
+    fn test_prefix() {
+        let series = Series::new("cpu").with_tag("host", "a");
+        let prefix = series.prefix().unwrap();
+
+        assert_eq!(&prefix, b"cpu:host=a:");
+        assert!(crate::to_vec(&series.key(123))
+            .unwrap()
+            .starts_with(&prefix));
+    }
+
+    #[test]
+    fn test_window_range() {
+        let series = Series::new("cpu");
+        let range = series.window_range(100..200).unwrap().unwrap();
+
+        for timestamp in [100, 150, 199].iter() {
+            assert!(range.contains(&crate::to_vec(&series.key(*timestamp)).unwrap()));
+        }
+
+        for timestamp in [0, 99, 200].iter() {
+            assert!(!range.contains(&crate::to_vec(&series.key(*timestamp)).unwrap()));
+        }
+
+        assert!(series.window_range(5..5).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_bucket() {
+        assert_eq!(bucket(123_456, Duration::from_secs(60)), 120_000);
+        assert_eq!(bucket(60_000, Duration::from_secs(60)), 60_000);
+        assert_eq!(bucket(123, Duration::from_nanos(1)), 123);
+    }
+}
@@ -0,0 +1,297 @@
+//! Hierarchical path keys
+//!
+//! A [`TreePath`] is a sequence of names such as `docs/2021/report`. Trees
+//! can be stored using either of two layouts:
+//!
+//! * Materialized paths, where the path is encoded as a single component
+//!   with names separated by `/`. All descendants of a node can be found
+//!   with a single range scan ([`TreePath::subtree_range`]).
+//! * Node keys, where the path is encoded as `depth:parent_path:name` using
+//!   [`NodeKey`]. The direct children of a node can be found with a single
+//!   range scan ([`TreePath::children_range`]).
+//!
+//! Example:
+//!
+//! ```rust
+//! use std::ops::RangeBounds;
+//! use strkey::tree::TreePath;
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let path = TreePath::parse("docs/2021/report")?;
+//! let parent = path.parent().unwrap();
+//!
+//! assert_eq!(&strkey::to_vec(&("fs", &path))?, b"fs:docs/2021/report");
+//! assert!(parent.subtree_range(&"fs")?.contains(&strkey::to_vec(&("fs", &path))?));
+//!
+//! assert_eq!(&strkey::to_vec(&("fs", path.node_key()))?, b"fs:00000003:docs/2021:report");
+//! assert!(parent.children_range(&"fs")?.contains(&strkey::to_vec(&("fs", path.node_key()))?));
+//! # Ok(())
+//! # }
+//! ```
+use std::fmt::{self, Display};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{error::Error, range::ByteRange};
+
+/// Path of a node in a tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TreePath {
+    names: Vec<String>,
+}
+
+impl TreePath {
+    /// Returns the path of the root node.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    /// Parses a path of names separated by `/`.
+    ///
+    /// An empty string is the root. Returns an error if a name is empty or
+    /// contains the default deliminator.
+    pub fn parse(path: &str) -> Result<Self, Error> {
+        let mut output = Self::root();
+
+        if !path.is_empty() {
+            for name in path.split('/') {
+                output = output.child(name)?;
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Returns the path of the child with the given name.
+    ///
+    /// Returns an error if the name is empty or contains `/` or the default
+    /// deliminator.
+    pub fn child<S: Into<String>>(&self, name: S) -> Result<Self, Error> {
+        let name = name.into();
+
+        if name.is_empty() || name.contains(['/', ':']) {
+            return Err(Error::Data(name));
+        }
+
+        let mut names = self.names.clone();
+        names.push(name);
+
+        Ok(Self { names })
+    }
+
+    /// Returns the path of the parent or `None` if this is the root.
+    pub fn parent(&self) -> Option<Self> {
+        let (_name, names) = self.names.split_last()?;
+
+        Some(Self {
+            names: names.to_vec(),
+        })
+    }
+
+    /// Returns the name of the node or `None` if this is the root.
+    pub fn name(&self) -> Option<&str> {
+        self.names.last().map(|name| name.as_str())
+    }
+
+    /// Returns the names from the root to the node.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Returns the number of names in the path. The root has a depth of zero.
+    pub fn depth(&self) -> u32 {
+        self.names.len() as u32
+    }
+
+    /// Returns whether this is the root.
+    pub fn is_root(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the node key of this path.
+    pub fn node_key(&self) -> NodeKey {
+        NodeKey {
+            depth: self.depth(),
+            parent: self.parent().unwrap_or_default(),
+            name: self.name().unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Returns the encoded key range of node keys of the direct children of this node.
+    ///
+    /// `prefix` is the namespace which precedes each [`NodeKey`]. Use `&()`
+    /// if there is no namespace.
+    pub fn children_range<P>(&self, prefix: &P) -> Result<ByteRange, Error>
+    where
+        P: ?Sized + Serialize,
+    {
+        let prefix = crate::to_prefix(&(prefix, self.depth() + 1, self))?;
+
+        Ok(crate::range::prefix_range(&prefix))
+    }
+
+    /// Returns the encoded key range of materialized paths of all descendants of this node.
+    ///
+    /// The range does not include this node itself. `prefix` is the
+    /// namespace which precedes each path. Use `&()` if there is no
+    /// namespace.
+    pub fn subtree_range<P>(&self, prefix: &P) -> Result<ByteRange, Error>
+    where
+        P: ?Sized + Serialize,
+    {
+        let mut key = crate::to_vec(&(prefix, self))?;
+
+        if !self.is_root() {
+            key.push(b'/');
+        }
+
+        Ok(crate::range::prefix_range(&key))
+    }
+}
+
+impl Display for TreePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.names.join("/"))
+    }
+}
+
+impl Serialize for TreePath {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for TreePath {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let path = String::deserialize(deserializer)?;
+
+        TreePath::parse(&path).map_err(D::Error::custom)
+    }
+}
+
+/// Key of a node laid out as `depth:parent_path:name`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeKey {
+    /// The depth of the node.
+    pub depth: u32,
+
+    /// The path of the parent node.
+    pub parent: TreePath,
+
+    /// The name of the node.
+    pub name: String,
+}
+
+impl_struct_serde!(NodeKey {
+    depth: u32,
+    parent: TreePath,
+    name: String,
+});
+
+impl NodeKey {
+    /// Returns the path of the node.
+    pub fn path(&self) -> Result<TreePath, Error> {
+        if self.depth == 0 {
+            Ok(TreePath::root())
+        } else {
+            self.parent.child(self.name.as_str())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeBounds;
+
+    use super::*;
+
+    #[test]
+    fn test_path() {
+        let path = TreePath::parse("a/b").unwrap();
+
+        assert_eq!(path.depth(), 2);
+        assert_eq!(path.name(), Some("b"));
+        assert_eq!(path.parent().unwrap().to_string(), "a");
+        assert_eq!(path.child("c").unwrap().to_string(), "a/b/c");
+        assert!(TreePath::root().parent().is_none());
+
+        assert!(TreePath::parse("a//b").is_err());
+        assert!(TreePath::parse("a:b").is_err());
+        assert!(path.child("c/d").is_err());
+    }
+
+    #[test]
+    fn test_path_round_trip() {
+        let path = TreePath::parse("a/b").unwrap();
+        let key = crate::to_vec(&path).unwrap();
+
+        assert_eq!(&key, b"a/b");
+        assert_eq!(crate::from_slice::<TreePath>(&key).unwrap(), path);
+        assert!(crate::from_slice::<TreePath>(b"a//b").is_err());
+        assert_eq!(
+            crate::from_reader::<_, TreePath>(key.as_slice()).unwrap(),
+            path
+        );
+    }
+
+    #[test]
+    fn test_node_key_round_trip() {
+        let path = TreePath::parse("a/b").unwrap();
+        let key = crate::to_vec(&path.node_key()).unwrap();
+
+        assert_eq!(&key, b"00000002:a:b");
+
+        let node_key = crate::from_slice::<NodeKey>(&key).unwrap();
+        assert_eq!(node_key.path().unwrap(), path);
+        assert_eq!(
+            crate::from_reader::<_, NodeKey>(key.as_slice()).unwrap(),
+            node_key
+        );
+
+        let key = crate::to_vec(&TreePath::root().node_key()).unwrap();
+        let node_key = crate::from_slice::<NodeKey>(&key).unwrap();
+        assert!(node_key.path().unwrap().is_root());
+    }
+
+    #[test]
+    fn test_children_range() {
+        let parent = TreePath::parse("a").unwrap();
+        let range = parent.children_range(&()).unwrap();
+
+        let node_key =
+            |path: &str| crate::to_vec(&TreePath::parse(path).unwrap().node_key()).unwrap();
+
+        assert!(range.contains(&node_key("a/b")));
+        assert!(range.contains(&node_key("a/c")));
+        assert!(!range.contains(&node_key("a")));
+        assert!(!range.contains(&node_key("a/b/c")));
+        assert!(!range.contains(&node_key("ab/c")));
+
+        let range = TreePath::root().children_range(&()).unwrap();
+        assert!(range.contains(&node_key("a")));
+        assert!(!range.contains(&node_key("a/b")));
+    }
+
+    #[test]
+    fn test_subtree_range() {
+        let parent = TreePath::parse("a").unwrap();
+        let range = parent.subtree_range(&"ns").unwrap();
+
+        let path_key = |path: &str| crate::to_vec(&("ns", TreePath::parse(path).unwrap())).unwrap();
+
+        assert!(range.contains(&path_key("a/b")));
+        assert!(range.contains(&path_key("a/b/c")));
+        assert!(!range.contains(&path_key("a")));
+        assert!(!range.contains(&path_key("ab")));
+        assert!(!range.contains(&path_key("b")));
+
+        let range = TreePath::root().subtree_range(&"ns").unwrap();
+        assert!(range.contains(&path_key("b/c")));
+    }
+}
@@ -0,0 +1,224 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+
+/// Fixed-width ASCII code.
+///
+/// A code is exactly `N` characters of ASCII letters, digits, `-`, or `_`.
+/// The restricted alphabet guarantees the code cannot contain a deliminator
+/// and the fixed width guarantees no code is a prefix of another. The code is
+/// encoded as a string component.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::types::{Alpha2, Currency, FixedAscii};
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let key = strkey::to_vec(&(
+///     Alpha2::parse("NZ")?,
+///     Currency::parse("NZD")?,
+///     FixedAscii::<4>::parse("x-01")?,
+/// ))?;
+/// assert_eq!(&key, b"NZ:NZD:x-01");
+///
+/// assert!(Alpha2::parse("nz").is_err());
+/// assert!(strkey::from_slice::<Currency>(b"NZDX").is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedAscii<const N: usize>([u8; N]);
+
+impl<const N: usize> FixedAscii<N> {
+    /// Number of characters in the code.
+    pub const LENGTH: usize = N;
+
+    /// Validates and constructs a code.
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        if !Self::is_valid(value) {
+            return Err(Error::Data(format!(
+                "invalid {}-character code {:?}",
+                N, value
+            )));
+        }
+
+        let mut code = [0u8; N];
+        code.copy_from_slice(value.as_bytes());
+
+        Ok(Self(code))
+    }
+
+    /// Returns whether the value is a valid code.
+    pub fn is_valid(value: &str) -> bool {
+        value.len() == N
+            && value
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+    }
+
+    /// Returns the code as a string.
+    pub fn as_str(&self) -> &str {
+        // Validated to be ASCII on construction
+        std::str::from_utf8(&self.0).unwrap()
+    }
+}
+
+impl<const N: usize> Display for FixedAscii<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> FromStr for FixedAscii<N> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl<const N: usize> Serialize for FixedAscii<N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for FixedAscii<N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        Self::parse(&value).map_err(D::Error::custom)
+    }
+}
+
+macro_rules! uppercase_code_type {
+    ($(#[$attr:meta])* $name:ident($len:literal, $description:literal);) => {
+        $(#[$attr])*
+        ///
+        #[doc = concat!("The code is exactly ", stringify!($len), " uppercase ASCII letters.")]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(FixedAscii<$len>);
+
+        impl $name {
+            /// Validates and constructs a code.
+            pub fn parse(value: &str) -> Result<Self, Error> {
+                if !Self::is_valid(value) {
+                    return Err(Error::Data(format!(
+                        concat!("invalid ", $description, " {:?}"),
+                        value
+                    )));
+                }
+
+                FixedAscii::parse(value).map(Self)
+            }
+
+            /// Returns whether the value is a valid code.
+            pub fn is_valid(value: &str) -> bool {
+                value.len() == $len && value.bytes().all(|byte| byte.is_ascii_uppercase())
+            }
+
+            /// Returns the code as a string.
+            pub fn as_str(&self) -> &str {
+                self.0.as_str()
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::parse(s)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                self.0.serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let value = String::deserialize(deserializer)?;
+
+                Self::parse(&value).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+uppercase_code_type! {
+    /// ISO 3166-1 alpha-2 country code.
+    Alpha2(2, "alpha-2 code");
+}
+
+uppercase_code_type! {
+    /// ISO 3166-1 alpha-3 country code.
+    Alpha3(3, "alpha-3 code");
+}
+
+uppercase_code_type! {
+    /// ISO 4217 currency code.
+    Currency(3, "currency code");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_ascii() {
+        assert!(FixedAscii::<3>::parse("a_1").is_ok());
+        assert!(FixedAscii::<3>::parse("ab").is_err());
+        assert!(FixedAscii::<3>::parse("a:b").is_err());
+        assert!(FixedAscii::<3>::parse("é1").is_err());
+
+        let code = FixedAscii::<3>::parse("A-z").unwrap();
+        let key = crate::to_vec(&(code, 1u8)).unwrap();
+
+        assert_eq!(&key, b"A-z:01");
+        assert_eq!(
+            crate::from_slice::<(FixedAscii<3>, u8)>(&key).unwrap(),
+            (code, 1)
+        );
+        assert!(crate::from_slice::<FixedAscii<3>>(b"A z").is_err());
+    }
+
+    #[test]
+    fn test_uppercase_codes() {
+        assert_eq!(Alpha2::parse("US").unwrap().as_str(), "US");
+        assert_eq!("USA".parse::<Alpha3>().unwrap().to_string(), "USA");
+        assert!(Alpha2::parse("USA").is_err());
+        assert!(Alpha3::parse("US1").is_err());
+        assert!(Currency::parse("usd").is_err());
+
+        let key = crate::to_vec(&Currency::parse("EUR").unwrap()).unwrap();
+        assert_eq!(&key, b"EUR");
+        assert_eq!(
+            crate::from_slice::<Currency>(&key).unwrap(),
+            Currency::parse("EUR").unwrap()
+        );
+        assert!(crate::from_slice::<Currency>(b"eur").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Integer that sorts in descending order.
+///
+/// The value is encoded with its bits inverted so that larger values sort
+/// first. The `Ord` implementation follows the encoded order.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::types::Desc;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let key1 = strkey::to_vec(&("doc", Desc(1u8)))?;
+/// let key2 = strkey::to_vec(&("doc", Desc(2u8)))?;
+///
+/// assert_eq!(&key2, b"doc:fd");
+/// assert!(key2 < key1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Desc<T>(pub T);
+
+impl<T> Desc<T> {
+    /// Unwrap and return the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: Ord> Ord for Desc<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+impl<T: Ord> PartialOrd for Desc<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Integer types that can be wrapped by [`Desc`].
+pub trait Descending: Copy + private::Sealed {
+    /// Returns the value with its bits inverted.
+    fn invert(self) -> Self;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_descending {
+    ($($ty:ty),*) => {
+        $(
+            impl private::Sealed for $ty {}
+
+            impl Descending for $ty {
+                fn invert(self) -> Self {
+                    !self
+                }
+            }
+        )*
+    };
+}
+
+impl_descending!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+impl<T: Descending + Serialize> Serialize for Desc<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.invert().serialize(serializer)
+    }
+}
+
+impl<'de, T: Descending + Deserialize<'de>> Deserialize<'de> for Desc<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Self(T::deserialize(deserializer)?.invert()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsigned() {
+        assert_eq!(&crate::to_vec(&Desc(0u16)).unwrap(), b"ffff");
+        assert_eq!(&crate::to_vec(&Desc(u16::MAX)).unwrap(), b"0000");
+        assert_eq!(crate::from_slice::<Desc<u16>>(b"fffe").unwrap(), Desc(1));
+    }
+
+    #[test]
+    fn test_signed_order() {
+        let mut values = (i8::MIN..=i8::MAX).map(Desc).collect::<Vec<_>>();
+        values.sort();
+
+        let keys = values
+            .iter()
+            .map(|value| crate::to_vec(value).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(values[0], Desc(i8::MAX));
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+
+        for (value, key) in values.iter().zip(keys.iter()) {
+            assert_eq!(&crate::from_slice::<Desc<i8>>(key).unwrap(), value);
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::{convert::TryFrom, fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+
+/// Fixed-point decimal number.
+///
+/// The number is stored as an `i64` mantissa scaled by `10^SCALE`, so
+/// `FixedPoint::<2>` holds hundredths. The mantissa is encoded as an `i64`
+/// component, so keys sort in numeric order and decode to exactly the same
+/// value. `SCALE` must be at most 18.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::types::FixedPoint;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let price: FixedPoint<2> = "-12.5".parse()?;
+/// assert_eq!(price.mantissa(), -1250);
+/// assert_eq!(price.to_string(), "-12.50");
+///
+/// let cheaper = strkey::to_vec(&("price", price))?;
+/// let dearer = strkey::to_vec(&("price", FixedPoint::<2>::from_integer(3)?))?;
+/// assert!(cheaper < dearer);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct FixedPoint<const SCALE: u32>(i64);
+
+impl<const SCALE: u32> FixedPoint<SCALE> {
+    /// Construct a number from the given mantissa.
+    ///
+    /// The value of the number is `mantissa / 10^SCALE`.
+    pub fn from_mantissa(mantissa: i64) -> Self {
+        Self(mantissa)
+    }
+
+    /// Construct a number from the given integer.
+    ///
+    /// Returns an error if the scaled integer does not fit in the mantissa.
+    pub fn from_integer(value: i64) -> Result<Self, Error> {
+        value
+            .checked_mul(Self::factor()?)
+            .map(Self)
+            .ok_or_else(|| Error::Data(format!("{} out of range", value)))
+    }
+
+    /// Returns the mantissa.
+    pub fn mantissa(&self) -> i64 {
+        self.0
+    }
+
+    /// Returns the number of decimal places.
+    pub fn scale(&self) -> u32 {
+        SCALE
+    }
+
+    fn factor() -> Result<i64, Error> {
+        10i64
+            .checked_pow(SCALE)
+            .ok_or_else(|| Error::Other(format!("scale {} exceeds 18", SCALE)))
+    }
+}
+
+impl<const SCALE: u32> Display for FixedPoint<SCALE> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = i128::from(self.0);
+        let factor = 10i128.pow(SCALE);
+        let sign = if value < 0 { "-" } else { "" };
+        let integer = value.abs() / factor;
+
+        if SCALE == 0 {
+            write!(f, "{}{}", sign, integer)
+        } else {
+            write!(
+                f,
+                "{}{}.{:0width$}",
+                sign,
+                integer,
+                value.abs() % factor,
+                width = SCALE as usize
+            )
+        }
+    }
+}
+
+impl<const SCALE: u32> FromStr for FixedPoint<SCALE> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || Error::Data(s.to_string());
+        let factor = i128::from(Self::factor()?);
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, fraction),
+            None => (digits, ""),
+        };
+
+        if integer.is_empty()
+            || integer.len() > 19
+            || fraction.len() > SCALE as usize
+            || !integer.bytes().all(|byte| byte.is_ascii_digit())
+            || !fraction.bytes().all(|byte| byte.is_ascii_digit())
+            || digits.ends_with('.')
+        {
+            return Err(error());
+        }
+
+        let mut value = integer.parse::<i128>().map_err(|_| error())? * factor;
+
+        if !fraction.is_empty() {
+            let padding = 10i128.pow(SCALE - fraction.len() as u32);
+            value += fraction.parse::<i128>().map_err(|_| error())? * padding;
+        }
+
+        if negative {
+            value = -value;
+        }
+
+        i64::try_from(value).map(Self).map_err(|_| error())
+    }
+}
+
+impl<const SCALE: u32> Serialize for FixedPoint<SCALE> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, const SCALE: u32> Deserialize<'de> for FixedPoint<SCALE> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        i64::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_display() {
+        let value = "12.3".parse::<FixedPoint<2>>().unwrap();
+        assert_eq!(value.mantissa(), 1230);
+        assert_eq!(value.scale(), 2);
+        assert_eq!(value.to_string(), "12.30");
+
+        assert_eq!("-0.05".parse::<FixedPoint<2>>().unwrap().mantissa(), -5);
+        assert_eq!("7".parse::<FixedPoint<0>>().unwrap().to_string(), "7");
+        assert_eq!(FixedPoint::<2>::from_mantissa(-5).to_string(), "-0.05");
+        assert_eq!(
+            FixedPoint::<3>::from_mantissa(i64::MIN).to_string(),
+            "-9223372036854775.808"
+        );
+
+        assert!("1.234".parse::<FixedPoint<2>>().is_err());
+        assert!("1.".parse::<FixedPoint<2>>().is_err());
+        assert!(".5".parse::<FixedPoint<2>>().is_err());
+        assert!("+1".parse::<FixedPoint<2>>().is_err());
+        assert!("1e3".parse::<FixedPoint<2>>().is_err());
+        assert!("92233720368547758.08".parse::<FixedPoint<2>>().is_err());
+        assert!("1".parse::<FixedPoint<19>>().is_err());
+    }
+
+    #[test]
+    fn test_from_integer() {
+        assert_eq!(FixedPoint::<2>::from_integer(-3).unwrap().mantissa(), -300);
+        assert!(FixedPoint::<2>::from_integer(i64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_order() {
+        let values = ["-2.5", "-0.01", "0", "0.01", "10"]
+            .iter()
+            .map(|text| text.parse::<FixedPoint<2>>().unwrap())
+            .collect::<Vec<_>>();
+        let keys = values
+            .iter()
+            .map(|value| crate::to_vec(value).unwrap())
+            .collect::<Vec<_>>();
+
+        let mut sorted = keys.clone();
+        sorted.sort();
+        assert_eq!(sorted, keys);
+
+        for (value, key) in values.iter().zip(&keys) {
+            assert_eq!(&crate::from_slice::<FixedPoint<2>>(key).unwrap(), value);
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use std::fmt::{self, Display};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{error::Error, range::ByteRange};
+
+const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Geohash of a latitude and longitude.
+///
+/// The geohash is encoded as its standard base 32 string. Since the alphabet
+/// is in ASCII order, geohashes sort in Z-order and all cells within a cell
+/// share its geohash as a prefix.
+///
+/// Requires the `geo` feature.
+///
+/// Example:
+///
+/// ```rust
+/// use std::ops::RangeBounds;
+/// use strkey::types::Geohash;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let hash = Geohash::encode(57.64911, 10.40744, 11)?;
+/// assert_eq!(hash.as_str(), "u4pruydqqvj");
+///
+/// let key = strkey::to_vec(&("place", &hash))?;
+/// let area = hash.parent().unwrap().parent().unwrap();
+/// assert!(area.prefix_range(&"place")?.contains(&key));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Geohash(String);
+
+impl Geohash {
+    /// Maximum number of characters.
+    pub const MAX_PRECISION: usize = 12;
+
+    /// Encodes the coordinates using the given number of characters.
+    ///
+    /// Returns an error if the coordinates are out of range or the
+    /// precision is not between 1 and [`Self::MAX_PRECISION`].
+    pub fn encode(latitude: f64, longitude: f64, precision: usize) -> Result<Self, Error> {
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err(Error::Data(format!("{},{}", latitude, longitude)));
+        }
+
+        if !(1..=Self::MAX_PRECISION).contains(&precision) {
+            return Err(Error::Data(format!("precision {}", precision)));
+        }
+
+        let mut latitude_range = (-90.0, 90.0);
+        let mut longitude_range = (-180.0, 180.0);
+        let mut output = String::with_capacity(precision);
+        let mut is_longitude = true;
+
+        for _ in 0..precision {
+            let mut index = 0;
+
+            for _ in 0..5 {
+                let (value, range) = if is_longitude {
+                    (longitude, &mut longitude_range)
+                } else {
+                    (latitude, &mut latitude_range)
+                };
+                let mid = (range.0 + range.1) / 2.0;
+
+                index <<= 1;
+
+                if value >= mid {
+                    index |= 1;
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+
+                is_longitude = !is_longitude;
+            }
+
+            output.push(ALPHABET[index] as char);
+        }
+
+        Ok(Self(output))
+    }
+
+    /// Parses a geohash string.
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        if value.is_empty()
+            || value.len() > Self::MAX_PRECISION
+            || !value.bytes().all(|c| ALPHABET.contains(&c))
+        {
+            return Err(Error::Data(value.to_string()));
+        }
+
+        Ok(Self(value.to_string()))
+    }
+
+    /// Returns the geohash string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the number of characters.
+    pub fn precision(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns the bounds of the cell as `(min_latitude, min_longitude, max_latitude, max_longitude)`.
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        let mut latitude_range = (-90.0, 90.0);
+        let mut longitude_range = (-180.0, 180.0);
+        let mut is_longitude = true;
+
+        for c in self.0.bytes() {
+            let index = ALPHABET.iter().position(|&a| a == c).unwrap_or_default();
+
+            for bit in (0..5).rev() {
+                let range = if is_longitude {
+                    &mut longitude_range
+                } else {
+                    &mut latitude_range
+                };
+                let mid = (range.0 + range.1) / 2.0;
+
+                if index & (1 << bit) != 0 {
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+
+                is_longitude = !is_longitude;
+            }
+        }
+
+        (
+            latitude_range.0,
+            longitude_range.0,
+            latitude_range.1,
+            longitude_range.1,
+        )
+    }
+
+    /// Returns the center of the cell as `(latitude, longitude)`.
+    pub fn decode(&self) -> (f64, f64) {
+        let (min_latitude, min_longitude, max_latitude, max_longitude) = self.bounds();
+
+        (
+            (min_latitude + max_latitude) / 2.0,
+            (min_longitude + max_longitude) / 2.0,
+        )
+    }
+
+    /// Returns the enclosing cell or `None` if the precision is 1.
+    pub fn parent(&self) -> Option<Self> {
+        if self.0.len() > 1 {
+            Some(Self(self.0[..self.0.len() - 1].to_string()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the adjacent cell in the given direction.
+    ///
+    /// `latitude_step` and `longitude_step` are the number of cells to move,
+    /// typically -1, 0, or 1. Longitude wraps around the antimeridian.
+    /// Returns `None` if the cell would be beyond a pole.
+    pub fn neighbor(&self, latitude_step: i32, longitude_step: i32) -> Option<Self> {
+        let (min_latitude, min_longitude, max_latitude, max_longitude) = self.bounds();
+        let (latitude, longitude) = self.decode();
+        let latitude = latitude + (max_latitude - min_latitude) * latitude_step as f64;
+        let mut longitude = longitude + (max_longitude - min_longitude) * longitude_step as f64;
+
+        if !(-90.0..=90.0).contains(&latitude) {
+            return None;
+        }
+
+        while longitude >= 180.0 {
+            longitude -= 360.0;
+        }
+
+        while longitude < -180.0 {
+            longitude += 360.0;
+        }
+
+        Self::encode(latitude, longitude, self.precision()).ok()
+    }
+
+    /// Returns the up to 8 surrounding cells.
+    pub fn neighbors(&self) -> Vec<Self> {
+        let mut output = Vec::with_capacity(8);
+
+        for latitude_step in -1..=1 {
+            for longitude_step in -1..=1 {
+                if latitude_step == 0 && longitude_step == 0 {
+                    continue;
+                }
+
+                if let Some(neighbor) = self.neighbor(latitude_step, longitude_step) {
+                    if !output.contains(&neighbor) && neighbor != *self {
+                        output.push(neighbor);
+                    }
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Returns the encoded key range of geohashes within this cell.
+    ///
+    /// `prefix` is the namespace which precedes the geohash. Use `&()` if
+    /// there is no namespace.
+    pub fn prefix_range<P>(&self, prefix: &P) -> Result<ByteRange, Error>
+    where
+        P: ?Sized + Serialize,
+    {
+        let key = crate::to_vec(&(prefix, self))?;
+
+        Ok(crate::range::prefix_range(&key))
+    }
+}
+
+impl Display for Geohash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for Geohash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Geohash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        Geohash::parse(&value).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeBounds;
+
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(Geohash::encode(42.6, -5.6, 5).unwrap().as_str(), "ezs42");
+        assert_eq!(Geohash::encode(-90.0, -180.0, 3).unwrap().as_str(), "000");
+        assert_eq!(Geohash::encode(90.0, 180.0, 3).unwrap().as_str(), "zzz");
+
+        assert!(Geohash::encode(91.0, 0.0, 5).is_err());
+        assert!(Geohash::encode(0.0, 0.0, 0).is_err());
+        assert!(Geohash::encode(0.0, 0.0, 13).is_err());
+    }
+
+    #[test]
+    fn test_decode() {
+        let (latitude, longitude) = Geohash::parse("ezs42").unwrap().decode();
+
+        assert!((latitude - 42.605).abs() < 0.01);
+        assert!((longitude - -5.603).abs() < 0.01);
+
+        assert!(Geohash::parse("ezs4a").is_err());
+        assert!(Geohash::parse("").is_err());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let hash = Geohash::parse("ezs42").unwrap();
+
+        assert_eq!(hash.neighbor(1, 0).unwrap().as_str(), "ezs48");
+        assert_eq!(hash.neighbor(0, 1).unwrap().as_str(), "ezs43");
+        assert_eq!(hash.neighbors().len(), 8);
+
+        let hash = Geohash::parse("z").unwrap();
+        assert!(hash.neighbor(1, 0).is_none());
+        assert_eq!(hash.neighbor(0, 1).unwrap().as_str(), "b");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let hash = Geohash::parse("u4pru").unwrap();
+        let key = crate::to_vec(&hash).unwrap();
+
+        assert_eq!(&key, b"u4pru");
+        assert_eq!(crate::from_slice::<Geohash>(&key).unwrap(), hash);
+        assert_eq!(
+            crate::from_reader::<_, Geohash>(key.as_slice()).unwrap(),
+            hash
+        );
+        assert!(crate::from_slice::<Geohash>(b"u4pra").is_err());
+    }
+
+    #[test]
+    fn test_prefix_range() {
+        let area = Geohash::parse("u4p").unwrap();
+        let range = area.prefix_range(&()).unwrap();
+
+        assert!(range.contains(&b"u4p".to_vec()));
+        assert!(range.contains(&b"u4pru".to_vec()));
+        assert!(!range.contains(&b"u4q".to_vec()));
+    }
+}
@@ -0,0 +1,157 @@
+use std::{cell::RefCell, collections::HashSet, fmt::Display, ops::Deref, sync::Arc};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+thread_local! {
+    static CACHE: RefCell<HashSet<Arc<str>>> = RefCell::new(HashSet::new());
+}
+
+/// Shared string that is interned when deserialized.
+///
+/// Deserializing looks up the string in a per-thread cache, so equal strings
+/// decoded on the same thread share one allocation. This reduces memory use
+/// when decoding many keys with repeated components such as table names or
+/// tenant IDs. The cache grows with each distinct string until
+/// [`Interned::clear_cache`] is called.
+///
+/// The string is encoded like any other string.
+///
+/// Example:
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use strkey::types::Interned;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let (table1, _) = strkey::from_slice::<(Interned, u8)>(b"users:01")?;
+/// let (table2, _) = strkey::from_slice::<(Interned, u8)>(b"users:02")?;
+///
+/// assert_eq!(&*table1, "users");
+/// assert!(Arc::ptr_eq(table1.as_arc(), table2.as_arc()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interned(Arc<str>);
+
+impl Interned {
+    /// Returns the interned string for the given text, adding it to the
+    /// current thread's cache if needed.
+    pub fn new(text: &str) -> Self {
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+
+            if let Some(value) = cache.get(text) {
+                return Self(value.clone());
+            }
+
+            let value: Arc<str> = Arc::from(text);
+            cache.insert(value.clone());
+
+            Self(value)
+        })
+    }
+
+    /// Returns the string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the shared string.
+    pub fn as_arc(&self) -> &Arc<str> {
+        &self.0
+    }
+
+    /// Removes all strings from the current thread's cache.
+    ///
+    /// Existing values are not affected.
+    pub fn clear_cache() {
+        CACHE.with(|cache| cache.borrow_mut().clear());
+    }
+
+    /// Returns the number of strings in the current thread's cache.
+    pub fn cache_len() -> usize {
+        CACHE.with(|cache| cache.borrow().len())
+    }
+}
+
+impl Deref for Interned {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Interned {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Interned {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for Interned {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Interned {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct InternedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for InternedVisitor {
+            type Value = Interned;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(Interned::new(v))
+            }
+        }
+
+        deserializer.deserialize_str(InternedVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interned() {
+        Interned::clear_cache();
+
+        let keys = [b"a:b".to_vec(), b"a:c".to_vec(), b"a:b".to_vec()];
+        let values = keys
+            .iter()
+            .map(|key| crate::from_reader::<_, (Interned, Interned)>(key.as_slice()).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(Interned::cache_len(), 3);
+        assert!(Arc::ptr_eq(values[0].0.as_arc(), values[1].0.as_arc()));
+        assert!(Arc::ptr_eq(values[0].1.as_arc(), values[2].1.as_arc()));
+        assert_eq!(crate::to_vec(&values[1]).unwrap(), b"a:c");
+
+        Interned::clear_cache();
+        assert_eq!(Interned::cache_len(), 0);
+        assert!(!Arc::ptr_eq(
+            Interned::new("a").as_arc(),
+            values[0].0.as_arc()
+        ));
+    }
+}
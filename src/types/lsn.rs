@@ -0,0 +1,189 @@
+use std::{fmt::Display, ops::Bound};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{error::Error, range::ByteRange};
+
+/// Log sequence number.
+///
+/// The number is encoded as a string component zero-padded to `DIGITS`
+/// digits so the encoded keys sort in numeric order. The digits are
+/// lowercase hexadecimal if `HEX` is true, otherwise decimal. The default is
+/// 16 hexadecimal digits, which is the same width as a `u64` component.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::types::{DecimalLsn, Lsn};
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let lsn = Lsn::<8>::new(0x1f)?;
+/// assert_eq!(&strkey::to_vec(&("wal", lsn))?, b"wal:0000001f");
+///
+/// let lsn = DecimalLsn::<6>::new(42)?;
+/// assert_eq!(&strkey::to_vec(&("wal", lsn.next().unwrap()))?, b"wal:000043");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Lsn<const DIGITS: usize = 16, const HEX: bool = true>(u64);
+
+/// Log sequence number encoded with decimal digits.
+pub type DecimalLsn<const DIGITS: usize = 20> = Lsn<DIGITS, false>;
+
+impl<const DIGITS: usize, const HEX: bool> Lsn<DIGITS, HEX> {
+    /// Construct a sequence number.
+    ///
+    /// Returns an error if the number does not fit in `DIGITS` digits.
+    pub fn new(value: u64) -> Result<Self, Error> {
+        if value > Self::max_value() {
+            return Err(Error::Data(format!("{} exceeds {} digits", value, DIGITS)));
+        }
+
+        Ok(Self(value))
+    }
+
+    /// Returns the largest number that fits in `DIGITS` digits.
+    pub fn max_value() -> u64 {
+        let radix: u64 = if HEX { 16 } else { 10 };
+
+        match radix.checked_pow(DIGITS as u32) {
+            Some(limit) => limit - 1,
+            None => u64::MAX,
+        }
+    }
+
+    /// Returns the number.
+    pub fn get(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the following sequence number or `None` on overflow.
+    pub fn next(&self) -> Option<Self> {
+        self.checked_add(1)
+    }
+
+    /// Returns the number advanced by the given amount or `None` on overflow.
+    pub fn checked_add(&self, amount: u64) -> Option<Self> {
+        let value = self.0.checked_add(amount)?;
+        Self::new(value).ok()
+    }
+
+    /// Returns the encoded range of the keys under the given prefix with a
+    /// sequence number greater than or equal to this number.
+    ///
+    /// The keys are expected to be laid out as `(prefix, lsn)`.
+    pub fn range_from<P>(&self, prefix: &P) -> Result<ByteRange, Error>
+    where
+        P: ?Sized + Serialize,
+    {
+        let start = crate::to_vec(&(prefix, self))?;
+        let end = match crate::range::prefix_successor(&crate::to_prefix(prefix)?) {
+            Some(end) => Bound::Excluded(end),
+            None => Bound::Unbounded,
+        };
+
+        Ok((Bound::Included(start), end))
+    }
+}
+
+impl<const DIGITS: usize, const HEX: bool> Display for Lsn<DIGITS, HEX> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if HEX {
+            write!(f, "{:0width$x}", self.0, width = DIGITS)
+        } else {
+            write!(f, "{:0width$}", self.0, width = DIGITS)
+        }
+    }
+}
+
+impl<const DIGITS: usize, const HEX: bool> Serialize for Lsn<DIGITS, HEX> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, const DIGITS: usize, const HEX: bool> Deserialize<'de> for Lsn<DIGITS, HEX> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        let valid_digits = if HEX {
+            text.bytes()
+                .all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte))
+        } else {
+            text.bytes().all(|byte| byte.is_ascii_digit())
+        };
+
+        if text.len() != DIGITS || !valid_digits {
+            return Err(D::Error::custom("invalid sequence number"));
+        }
+
+        let value = u64::from_str_radix(&text, if HEX { 16 } else { 10 })
+            .map_err(|_| D::Error::custom("invalid sequence number"))?;
+
+        Self::new(value).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeBounds;
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let lsn = Lsn::<16>::new(0xabc).unwrap();
+        let key = crate::to_vec(&lsn).unwrap();
+
+        assert_eq!(&key, b"0000000000000abc");
+        assert_eq!(crate::from_slice::<Lsn>(&key).unwrap(), lsn);
+
+        let lsn = DecimalLsn::<4>::new(12).unwrap();
+        let key = crate::to_vec(&lsn).unwrap();
+
+        assert_eq!(&key, b"0012");
+        assert_eq!(crate::from_slice::<DecimalLsn<4>>(&key).unwrap(), lsn);
+
+        assert!(crate::from_slice::<DecimalLsn<4>>(b"12").is_err());
+        assert!(crate::from_slice::<DecimalLsn<4>>(b"+012").is_err());
+        assert!(crate::from_slice::<Lsn<4>>(b"00AB").is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(DecimalLsn::<2>::max_value(), 99);
+        assert_eq!(Lsn::<2>::max_value(), 0xff);
+        assert_eq!(Lsn::<16>::max_value(), u64::MAX);
+        assert_eq!(DecimalLsn::<20>::max_value(), u64::MAX);
+
+        assert!(DecimalLsn::<2>::new(100).is_err());
+        assert!(DecimalLsn::<2>::new(99).unwrap().next().is_none());
+        assert!(Lsn::<16>::new(u64::MAX).unwrap().next().is_none());
+        assert_eq!(
+            DecimalLsn::<2>::new(98)
+                .unwrap()
+                .checked_add(1)
+                .unwrap()
+                .get(),
+            99
+        );
+    }
+
+    #[test]
+    fn test_range_from() {
+        let lsn = DecimalLsn::<3>::new(5).unwrap();
+        let range = lsn.range_from(&"wal").unwrap();
+        let key = |value| crate::to_vec(&("wal", DecimalLsn::<3>::new(value).unwrap())).unwrap();
+
+        assert!(!range.contains(&key(4)));
+        assert!(range.contains(&key(5)));
+        assert!(range.contains(&key(999)));
+        assert!(!range.contains(&crate::to_vec(&("walx", 0u8)).unwrap()));
+    }
+}
@@ -0,0 +1,187 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+
+macro_rules! hardware_address_type {
+    ($(#[$attr:meta])* $name:ident($len:literal);) => {
+        $(#[$attr])*
+        ///
+        /// The address is encoded as a hexadecimal byte array component,
+        /// which has a fixed width, so keys sort by address.
+        ///
+        /// Parsing is lenient and accepts the digits with or without `:` or
+        /// `-` between bytes, or `.` between groups of 2 bytes, in either
+        /// case. The text form is lowercase and colon-separated.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        pub struct $name(pub [u8; $len]);
+
+        impl $name {
+            /// Number of bytes in the address.
+            pub const LENGTH: usize = $len;
+
+            /// Returns the bytes of the address.
+            pub fn octets(&self) -> [u8; $len] {
+                self.0
+            }
+        }
+
+        impl From<[u8; $len]> for $name {
+            fn from(octets: [u8; $len]) -> Self {
+                Self(octets)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                parse_hardware_address(s, $len)
+                    .map(|bytes| {
+                        let mut octets = [0u8; $len];
+                        octets.copy_from_slice(&bytes);
+                        Self(octets)
+                    })
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                for (index, byte) in self.0.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(":")?;
+                    }
+
+                    write!(f, "{:02x}", byte)?;
+                }
+
+                Ok(())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_bytes(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct AddressVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for AddressVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        write!(formatter, "{} bytes", $len)
+                    }
+
+                    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        let mut octets = [0u8; $len];
+
+                        if v.len() != $len {
+                            return Err(E::invalid_length(v.len(), &self));
+                        }
+
+                        octets.copy_from_slice(v);
+
+                        Ok($name(octets))
+                    }
+                }
+
+                deserializer.deserialize_bytes(AddressVisitor)
+            }
+        }
+    };
+}
+
+hardware_address_type! {
+    /// MAC address (EUI-48).
+    ///
+    /// Example:
+    ///
+    /// ```rust
+    /// use strkey::types::MacAddr;
+    ///
+    /// # fn main() -> Result<(), strkey::Error> {
+    /// let address = "00:1A:2b-3c.4D5e".parse::<MacAddr>()?;
+    /// assert_eq!(&strkey::to_vec(&("device", address))?, b"device:001a2b3c4d5e");
+    /// assert_eq!(address.to_string(), "00:1a:2b:3c:4d:5e");
+    /// # Ok(())
+    /// # }
+    /// ```
+    MacAddr(6);
+}
+
+hardware_address_type! {
+    /// EUI-64 hardware address.
+    Eui64(8);
+}
+
+/// Parses a hardware address of the given number of bytes.
+fn parse_hardware_address(text: &str, len: usize) -> Result<Vec<u8>, Error> {
+    let digits = text
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-' | '.'))
+        .collect::<String>();
+
+    match hex::decode(&digits) {
+        Ok(bytes) if bytes.len() == len => Ok(bytes),
+        _ => Err(Error::Data(format!("invalid hardware address {:?}", text))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let expected = MacAddr([0xaa, 0xbb, 0xcc, 0x01, 0x02, 0x03]);
+
+        for text in [
+            "aa:bb:cc:01:02:03",
+            "AA-BB-CC-01-02-03",
+            "aabb.cc01.0203",
+            "aabbcc010203",
+        ] {
+            assert_eq!(text.parse::<MacAddr>().unwrap(), expected);
+        }
+
+        assert!("aa:bb:cc:01:02".parse::<MacAddr>().is_err());
+        assert!("aa:bb:cc:01:02:0g".parse::<MacAddr>().is_err());
+        assert_eq!(
+            "02:00:5e:10:00:00:00:01"
+                .parse::<Eui64>()
+                .unwrap()
+                .to_string(),
+            "02:00:5e:10:00:00:00:01"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let address = MacAddr([0, 1, 2, 0xfd, 0xfe, 0xff]);
+        let key = crate::to_vec(&address).unwrap();
+
+        assert_eq!(&key, b"000102fdfeff");
+        assert_eq!(crate::from_slice::<MacAddr>(&key).unwrap(), address);
+        assert!(crate::from_slice::<MacAddr>(b"0001").is_err());
+
+        let address = Eui64([1; 8]);
+        let key = crate::to_vec(&address).unwrap();
+
+        assert_eq!(&key, b"0101010101010101");
+        assert_eq!(crate::from_slice::<Eui64>(&key).unwrap(), address);
+    }
+}
@@ -0,0 +1,40 @@
+//! Component types
+//!
+//! Wrapper types with encodings suited for common key components.
+mod code;
+mod desc;
+mod fixed_point;
+#[cfg(feature = "geo")]
+mod geohash;
+mod interned;
+mod lsn;
+mod mac;
+mod morton;
+mod namespaced;
+mod narrow;
+#[cfg(feature = "ipnet")]
+mod network;
+mod path;
+mod raw;
+mod reverse_timestamp;
+mod snowflake;
+mod verbatim;
+
+pub use self::code::{Alpha2, Alpha3, Currency, FixedAscii};
+pub use self::desc::{Desc, Descending};
+pub use self::fixed_point::FixedPoint;
+#[cfg(feature = "geo")]
+pub use self::geohash::Geohash;
+pub use self::interned::Interned;
+pub use self::lsn::{DecimalLsn, Lsn};
+pub use self::mac::{Eui64, MacAddr};
+pub use self::morton::{Morton2D, Morton3D};
+pub use self::namespaced::{Namespace, Namespaced};
+pub use self::narrow::{U24, U40, U48, U56};
+#[cfg(feature = "ipnet")]
+pub use self::network::{Ipv4Network, Ipv6Network};
+pub use self::path::{EscapedPathComponent, PathComponent};
+pub use self::raw::RawComponent;
+pub use self::reverse_timestamp::ReverseTimestamp;
+pub use self::snowflake::SnowflakeId;
+pub use self::verbatim::Verbatim;
@@ -0,0 +1,451 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Two dimensional Z-order (Morton) code.
+///
+/// The bits of the `x` and `y` coordinates are interleaved into a single
+/// `u64` which is encoded as 16 hexadecimal digits. Points that are close
+/// together tend to have codes that are close together, and every square
+/// cell of a power of two size aligned to its size is a contiguous range of
+/// codes.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::types::Morton2D;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let code = Morton2D::new(3, 5);
+/// assert_eq!(&strkey::to_vec(&("tile", code))?, b"tile:0000000000000027");
+///
+/// let ranges = Morton2D::ranges(2, 2, 3, 5, 8);
+/// assert!(ranges.iter().any(|range| range.contains(&code)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Morton2D(u64);
+
+impl Morton2D {
+    /// Construct the code from coordinates.
+    pub fn new(x: u32, y: u32) -> Self {
+        Self(spread_2d(x) | spread_2d(y) << 1)
+    }
+
+    /// Construct from the raw interleaved code.
+    pub fn from_code(code: u64) -> Self {
+        Self(code)
+    }
+
+    /// Returns the raw interleaved code.
+    pub fn code(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the `x` coordinate.
+    pub fn x(&self) -> u32 {
+        compact_2d(self.0)
+    }
+
+    /// Returns the `y` coordinate.
+    pub fn y(&self) -> u32 {
+        compact_2d(self.0 >> 1)
+    }
+
+    /// Returns at most `max_ranges` ranges of codes that cover the given
+    /// bounding box.
+    ///
+    /// The bounds are inclusive. The ranges are sorted and adjacent ranges
+    /// are merged. The number of ranges needed for an exact cover grows with
+    /// the perimeter of the box measured in its smallest misaligned cells, so
+    /// boxes aligned to large powers of two produce few ranges. If an exact
+    /// cover needs more than `max_ranges` ranges, the cells on the edges of
+    /// the box are left coarser and the ranges also contain codes outside of
+    /// the box, which must be filtered out by the caller.
+    ///
+    /// Panics if `max_ranges` is zero.
+    pub fn ranges(
+        min_x: u32,
+        min_y: u32,
+        max_x: u32,
+        max_y: u32,
+        max_ranges: usize,
+    ) -> Vec<std::ops::RangeInclusive<Morton2D>> {
+        assert!(max_ranges > 0, "maximum number of ranges must not be zero");
+
+        if min_x > max_x || min_y > max_y {
+            return Vec::new();
+        }
+
+        cover(
+            &[min_x as u64, min_y as u64],
+            &[max_x as u64, max_y as u64],
+            32,
+            max_ranges,
+            &|point| Self::new(point[0] as u32, point[1] as u32).0,
+        )
+        .into_iter()
+        .map(|(start, end)| Self(start)..=Self(end))
+        .collect()
+    }
+}
+
+/// Three dimensional Z-order (Morton) code.
+///
+/// The lower 21 bits of the `x`, `y`, and `z` coordinates are interleaved
+/// into a single `u64` which is encoded as 16 hexadecimal digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Morton3D(u64);
+
+impl Morton3D {
+    /// Maximum value of a coordinate.
+    pub const MAX_COORDINATE: u32 = (1 << 21) - 1;
+
+    /// Construct the code from coordinates.
+    ///
+    /// Bits above [`Self::MAX_COORDINATE`] are ignored.
+    pub fn new(x: u32, y: u32, z: u32) -> Self {
+        Self(spread_3d(x) | spread_3d(y) << 1 | spread_3d(z) << 2)
+    }
+
+    /// Construct from the raw interleaved code.
+    pub fn from_code(code: u64) -> Self {
+        Self(code)
+    }
+
+    /// Returns the raw interleaved code.
+    pub fn code(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the `x` coordinate.
+    pub fn x(&self) -> u32 {
+        compact_3d(self.0)
+    }
+
+    /// Returns the `y` coordinate.
+    pub fn y(&self) -> u32 {
+        compact_3d(self.0 >> 1)
+    }
+
+    /// Returns the `z` coordinate.
+    pub fn z(&self) -> u32 {
+        compact_3d(self.0 >> 2)
+    }
+
+    /// Returns at most `max_ranges` ranges of codes that cover the given
+    /// bounding box.
+    ///
+    /// The bounds are inclusive. The ranges are sorted and adjacent ranges
+    /// are merged. As with [`Morton2D::ranges`], the ranges contain codes
+    /// outside of the box if an exact cover needs more than `max_ranges`
+    /// ranges.
+    ///
+    /// Panics if `max_ranges` is zero.
+    pub fn ranges(
+        min: [u32; 3],
+        max: [u32; 3],
+        max_ranges: usize,
+    ) -> Vec<std::ops::RangeInclusive<Morton3D>> {
+        assert!(max_ranges > 0, "maximum number of ranges must not be zero");
+
+        let clamp = |value: u32| value.min(Self::MAX_COORDINATE) as u64;
+        let min = [clamp(min[0]), clamp(min[1]), clamp(min[2])];
+        let max = [clamp(max[0]), clamp(max[1]), clamp(max[2])];
+
+        if min.iter().zip(max.iter()).any(|(min, max)| min > max) {
+            return Vec::new();
+        }
+
+        cover(&min, &max, 21, max_ranges, &|point| {
+            Self::new(point[0] as u32, point[1] as u32, point[2] as u32).0
+        })
+        .into_iter()
+        .map(|(start, end)| Self(start)..=Self(end))
+        .collect()
+    }
+}
+
+fn spread_2d(value: u32) -> u64 {
+    let mut x = value as u64;
+    x = (x | x << 16) & 0x0000_ffff_0000_ffff;
+    x = (x | x << 8) & 0x00ff_00ff_00ff_00ff;
+    x = (x | x << 4) & 0x0f0f_0f0f_0f0f_0f0f;
+    x = (x | x << 2) & 0x3333_3333_3333_3333;
+    x = (x | x << 1) & 0x5555_5555_5555_5555;
+    x
+}
+
+fn compact_2d(code: u64) -> u32 {
+    let mut x = code & 0x5555_5555_5555_5555;
+    x = (x | x >> 1) & 0x3333_3333_3333_3333;
+    x = (x | x >> 2) & 0x0f0f_0f0f_0f0f_0f0f;
+    x = (x | x >> 4) & 0x00ff_00ff_00ff_00ff;
+    x = (x | x >> 8) & 0x0000_ffff_0000_ffff;
+    x = (x | x >> 16) & 0x0000_0000_ffff_ffff;
+    x as u32
+}
+
+fn spread_3d(value: u32) -> u64 {
+    let mut x = (value & Morton3D::MAX_COORDINATE) as u64;
+    x = (x | x << 32) & 0x001f_0000_0000_ffff;
+    x = (x | x << 16) & 0x001f_0000_ff00_00ff;
+    x = (x | x << 8) & 0x100f_00f0_0f00_f00f;
+    x = (x | x << 4) & 0x10c3_0c30_c30c_30c3;
+    x = (x | x << 2) & 0x1249_2492_4924_9249;
+    x
+}
+
+fn compact_3d(code: u64) -> u32 {
+    let mut x = code & 0x1249_2492_4924_9249;
+    x = (x | x >> 2) & 0x10c3_0c30_c30c_30c3;
+    x = (x | x >> 4) & 0x100f_00f0_0f00_f00f;
+    x = (x | x >> 8) & 0x001f_0000_ff00_00ff;
+    x = (x | x >> 16) & 0x001f_0000_0000_ffff;
+    x = (x | x >> 32) & 0x0000_0000_001f_ffff;
+    x as u32
+}
+
+/// Covers the box with cells, starting from the cell with sides of
+/// `2^level` containing the whole space.
+///
+/// The cells on the edges of the box are subdivided one level at a time
+/// until every cell is inside the box or the next level would need more than
+/// `max_ranges` ranges.
+fn cover<const N: usize>(
+    min: &[u64; N],
+    max: &[u64; N],
+    mut level: u32,
+    max_ranges: usize,
+    encode: &dyn Fn([u64; N]) -> u64,
+) -> Vec<(u64, u64)> {
+    let cell_range = |origin: [u64; N], level: u32| {
+        let start = encode(origin);
+        (start, start + ((1u128 << (level * N as u32)) - 1) as u64)
+    };
+
+    let mut output = vec![cell_range([0; N], level)];
+    let mut inside = Vec::new();
+    let mut edges = vec![[0; N]];
+
+    while !edges.is_empty() {
+        level -= 1;
+
+        let mut next_inside = inside.clone();
+        let mut next_edges = Vec::new();
+
+        for origin in &edges {
+            for child in 0..(1 << N) {
+                let mut child_origin = *origin;
+
+                for (axis, value) in child_origin.iter_mut().enumerate() {
+                    if child & (1 << axis) != 0 {
+                        *value += 1 << level;
+                    }
+                }
+
+                match classify(min, max, &child_origin, level) {
+                    Some(true) => next_inside.push(cell_range(child_origin, level)),
+                    Some(false) => next_edges.push(child_origin),
+                    None => {}
+                }
+            }
+        }
+
+        let next_inside = merge(next_inside);
+        let candidate = merge(
+            next_inside
+                .iter()
+                .copied()
+                .chain(next_edges.iter().map(|origin| cell_range(*origin, level)))
+                .collect(),
+        );
+
+        if candidate.len() > max_ranges {
+            break;
+        }
+
+        output = candidate;
+        inside = next_inside;
+        edges = next_edges;
+    }
+
+    output
+}
+
+/// Returns whether the cell with sides of `2^level` is inside the box, on
+/// its edge, or `None` if outside.
+fn classify<const N: usize>(
+    min: &[u64; N],
+    max: &[u64; N],
+    origin: &[u64; N],
+    level: u32,
+) -> Option<bool> {
+    let mut inside = true;
+
+    for axis in 0..N {
+        let cell_max = origin[axis] + (1 << level) - 1;
+
+        if cell_max < min[axis] || origin[axis] > max[axis] {
+            return None;
+        }
+
+        if origin[axis] < min[axis] || cell_max > max[axis] {
+            inside = false;
+        }
+    }
+
+    Some(inside)
+}
+
+fn merge(mut ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    ranges.sort_unstable();
+
+    let mut output: Vec<(u64, u64)> = Vec::new();
+
+    for (start, end) in ranges {
+        match output.last_mut() {
+            Some(last) if last.1.checked_add(1) == Some(start) => last.1 = end,
+            _ => output.push((start, end)),
+        }
+    }
+
+    output
+}
+
+macro_rules! impl_serde {
+    ($($ty:ident),*) => {
+        $(
+            impl Serialize for $ty {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    self.0.serialize(serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $ty {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    Ok(Self(u64::deserialize(deserializer)?))
+                }
+            }
+        )*
+    };
+}
+
+impl_serde!(Morton2D, Morton3D);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_2d() {
+        let code = Morton2D::new(0b101, 0b011);
+        assert_eq!(code.code(), 0b011011);
+        assert_eq!(code.x(), 0b101);
+        assert_eq!(code.y(), 0b011);
+
+        let code = Morton2D::new(u32::MAX, 0);
+        assert_eq!(code.code(), 0x5555_5555_5555_5555);
+        assert_eq!(code.x(), u32::MAX);
+    }
+
+    #[test]
+    fn test_3d() {
+        let code = Morton3D::new(1, 2, 4);
+        assert_eq!(code.code(), 0b100_010_001);
+        assert_eq!((code.x(), code.y(), code.z()), (1, 2, 4));
+
+        let max = Morton3D::MAX_COORDINATE;
+        let code = Morton3D::new(max, 0, max);
+        assert_eq!((code.x(), code.y(), code.z()), (max, 0, max));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let code = Morton2D::new(12, 34);
+        let key = crate::to_vec(&code).unwrap();
+        assert_eq!(crate::from_slice::<Morton2D>(&key).unwrap(), code);
+
+        let code = Morton3D::new(12, 34, 56);
+        let key = crate::to_vec(&code).unwrap();
+        assert_eq!(crate::from_slice::<Morton3D>(&key).unwrap(), code);
+    }
+
+    #[test]
+    fn test_2d_ranges() {
+        let ranges = Morton2D::ranges(2, 1, 5, 6, usize::MAX);
+
+        for x in 0..8 {
+            for y in 0..8 {
+                let inside = (2..=5).contains(&x) && (1..=6).contains(&y);
+                let code = Morton2D::new(x, y);
+
+                assert_eq!(ranges.iter().any(|range| range.contains(&code)), inside);
+            }
+        }
+
+        assert!(ranges.windows(2).all(|w| w[0].end() < w[1].start()));
+
+        let ranges = Morton2D::ranges(0, 0, 3, 3, 1);
+        assert_eq!(ranges, vec![Morton2D::new(0, 0)..=Morton2D::new(3, 3)]);
+
+        assert!(Morton2D::ranges(5, 0, 4, 0, 1).is_empty());
+    }
+
+    #[test]
+    fn test_2d_ranges_max_ranges() {
+        let exact = Morton2D::ranges(2, 1, 5, 6, usize::MAX);
+
+        for max_ranges in 1..=exact.len() {
+            let ranges = Morton2D::ranges(2, 1, 5, 6, max_ranges);
+
+            assert!(ranges.len() <= max_ranges);
+            assert!(ranges.windows(2).all(|w| w[0].end() < w[1].start()));
+
+            for x in 2..=5 {
+                for y in 1..=6 {
+                    let code = Morton2D::new(x, y);
+                    assert!(ranges.iter().any(|range| range.contains(&code)));
+                }
+            }
+        }
+
+        assert_eq!(Morton2D::ranges(2, 1, 5, 6, exact.len()), exact);
+
+        let ranges = Morton2D::ranges(1, 0, 1, u32::MAX, 16);
+        assert!(ranges.len() <= 16);
+
+        for y in (0..=u32::MAX).step_by(0x0100_0001) {
+            let code = Morton2D::new(1, y);
+            assert!(ranges.iter().any(|range| range.contains(&code)));
+        }
+    }
+
+    #[test]
+    fn test_3d_ranges() {
+        let ranges = Morton3D::ranges([1, 0, 2], [2, 3, 3], usize::MAX);
+
+        for x in 0..4 {
+            for y in 0..4 {
+                for z in 0..4 {
+                    let inside =
+                        (1..=2).contains(&x) && (0..=3).contains(&y) && (2..=3).contains(&z);
+                    let code = Morton3D::new(x, y, z);
+
+                    assert_eq!(ranges.iter().any(|range| range.contains(&code)), inside);
+                }
+            }
+        }
+
+        let ranges = Morton3D::ranges([1, 0, 2], [2, 3, 3], 2);
+        assert!(ranges.len() <= 2);
+
+        let code = Morton3D::new(2, 3, 3);
+        assert!(ranges.iter().any(|range| range.contains(&code)));
+    }
+}
@@ -0,0 +1,218 @@
+use std::{fmt, marker::PhantomData};
+
+use serde::{
+    de::{DeserializeSeed, Error as _, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// Marker type naming the namespace of a [`Namespaced`] key.
+///
+/// The name should not contain the deliminator.
+pub trait Namespace {
+    /// Name written as the first component.
+    const NAME: &'static str;
+}
+
+/// Key prefixed with the name of a namespace.
+///
+/// The name of `NS` is written as the first component and checked on
+/// decode, so decoding a key of one namespace as a key of another namespace
+/// returns an error instead of a wrong value.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::types::{Namespace, Namespaced};
+///
+/// struct Users;
+///
+/// impl Namespace for Users {
+///     const NAME: &'static str = "users";
+/// }
+///
+/// struct Orders;
+///
+/// impl Namespace for Orders {
+///     const NAME: &'static str = "orders";
+/// }
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let key = strkey::to_vec(&Namespaced::<Users, _>::new(1234u32))?;
+/// assert_eq!(&key, b"users:000004d2");
+///
+/// assert_eq!(strkey::from_slice::<Namespaced<Users, u32>>(&key)?.into_inner(), 1234);
+/// assert!(strkey::from_slice::<Namespaced<Orders, u32>>(&key).is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Namespaced<NS, T> {
+    value: T,
+    _namespace: PhantomData<fn() -> NS>,
+}
+
+impl<NS: Namespace, T> Namespaced<NS, T> {
+    /// Wrap the value.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            _namespace: PhantomData,
+        }
+    }
+
+    /// Returns the name of the namespace.
+    pub fn name(&self) -> &'static str {
+        NS::NAME
+    }
+
+    /// Returns a reference to the wrapped value.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Unwrap and return the wrapped value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<NS, T: Clone> Clone for Namespaced<NS, T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+            _namespace: PhantomData,
+        }
+    }
+}
+
+impl<NS: Namespace, T: fmt::Debug> fmt::Debug for Namespaced<NS, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Namespaced")
+            .field(&NS::NAME)
+            .field(&self.value)
+            .finish()
+    }
+}
+
+impl<NS, T: PartialEq> PartialEq for Namespaced<NS, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<NS, T: Eq> Eq for Namespaced<NS, T> {}
+
+impl<NS: Namespace, T: Serialize> Serialize for Namespaced<NS, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(NS::NAME)?;
+        tuple.serialize_element(&self.value)?;
+        tuple.end()
+    }
+}
+
+impl<'de, NS: Namespace, T: Deserialize<'de>> Deserialize<'de> for Namespaced<NS, T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(2, NamespacedVisitor(PhantomData))
+    }
+}
+
+struct NamespacedVisitor<NS, T>(PhantomData<fn() -> (NS, T)>);
+
+impl<'de, NS: Namespace, T: Deserialize<'de>> Visitor<'de> for NamespacedVisitor<NS, T> {
+    type Value = Namespaced<NS, T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "key in namespace {}", NS::NAME)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        seq.next_element_seed(NameSeed(NS::NAME))?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let value = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+
+        Ok(Namespaced::new(value))
+    }
+}
+
+struct NameSeed(&'static str);
+
+impl<'de> DeserializeSeed<'de> for NameSeed {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for NameSeed {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "namespace {}", self.0)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if v == self.0 {
+            Ok(())
+        } else {
+            Err(E::custom(format!(
+                "expected namespace {}, got {}",
+                self.0, v
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Users;
+
+    impl Namespace for Users {
+        const NAME: &'static str = "users";
+    }
+
+    struct UsersV2;
+
+    impl Namespace for UsersV2 {
+        const NAME: &'static str = "users_v2";
+    }
+
+    #[test]
+    fn test_namespaced() {
+        let key = crate::to_vec(&Namespaced::<Users, _>::new(("alice", 1u8))).unwrap();
+        assert_eq!(&key, b"users:alice:01");
+
+        let value = crate::from_slice::<Namespaced<Users, (&str, u8)>>(&key).unwrap();
+        assert_eq!(value.get(), &("alice", 1));
+        assert_eq!(value.name(), "users");
+
+        let error = crate::from_slice::<Namespaced<UsersV2, (&str, u8)>>(&key).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Other error: expected namespace users_v2, got users"
+        );
+
+        let value = crate::from_reader::<_, Namespaced<Users, (String, u8)>>(&key[..]).unwrap();
+        assert_eq!(value.into_inner(), ("alice".to_string(), 1));
+    }
+}
@@ -0,0 +1,198 @@
+use std::{convert::TryFrom, fmt::Display};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+
+macro_rules! narrow_uint_type {
+    ($(#[$attr:meta])* $name:ident($bits:literal);) => {
+        $(#[$attr])*
+        ///
+        /// The value is encoded as a big-endian hexadecimal byte array
+        /// component, which has a fixed width, so keys sort by value.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        pub struct $name(u64);
+
+        impl $name {
+            /// Number of bits in the value.
+            pub const BITS: u32 = $bits;
+
+            /// Number of bytes in the encoded value.
+            pub const LENGTH: usize = $bits / 8;
+
+            /// Smallest value.
+            pub const MIN: Self = Self(0);
+
+            /// Largest value.
+            pub const MAX: Self = Self((1 << $bits) - 1);
+
+            /// Construct from a value.
+            ///
+            /// Returns an error if the value does not fit in the width.
+            pub fn new(value: u64) -> Result<Self, Error> {
+                if value > Self::MAX.0 {
+                    return Err(Error::Data(format!(
+                        "{} exceeds {} bits",
+                        value, $bits
+                    )));
+                }
+
+                Ok(Self(value))
+            }
+
+            /// Construct from a value, keeping only the lowest bits.
+            pub fn wrapping_new(value: u64) -> Self {
+                Self(value & Self::MAX.0)
+            }
+
+            /// Returns the value.
+            pub fn get(&self) -> u64 {
+                self.0
+            }
+        }
+
+        impl TryFrom<u64> for $name {
+            type Error = Error;
+
+            fn try_from(value: u64) -> Result<Self, Self::Error> {
+                Self::new(value)
+            }
+        }
+
+        impl From<$name> for u64 {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_bytes(&self.0.to_be_bytes()[8 - Self::LENGTH..])
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct NarrowVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for NarrowVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        write!(formatter, "{} bytes", $name::LENGTH)
+                    }
+
+                    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        let mut bytes = [0u8; 8];
+
+                        if v.len() != $name::LENGTH {
+                            return Err(E::invalid_length(v.len(), &self));
+                        }
+
+                        bytes[8 - $name::LENGTH..].copy_from_slice(v);
+
+                        Ok($name(u64::from_be_bytes(bytes)))
+                    }
+                }
+
+                deserializer.deserialize_bytes(NarrowVisitor)
+            }
+        }
+    };
+}
+
+narrow_uint_type! {
+    /// 24-bit unsigned integer encoded as 6 hexadecimal digits.
+    U24(24);
+}
+
+narrow_uint_type! {
+    /// 40-bit unsigned integer encoded as 10 hexadecimal digits.
+    U40(40);
+}
+
+narrow_uint_type! {
+    /// 48-bit unsigned integer encoded as 12 hexadecimal digits.
+    ///
+    /// A timestamp in milliseconds since the Unix epoch fits until the year
+    /// 10889.
+    ///
+    /// Example:
+    ///
+    /// ```rust
+    /// use strkey::types::U48;
+    ///
+    /// # fn main() -> Result<(), strkey::Error> {
+    /// let millis = U48::new(1618561155448)?;
+    /// assert_eq!(&strkey::to_vec(&("event", millis))?, b"event:0178d9c34178");
+    /// assert!(U48::new(1 << 48).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    U48(48);
+}
+
+narrow_uint_type! {
+    /// 56-bit unsigned integer encoded as 14 hexadecimal digits.
+    U56(56);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range() {
+        assert_eq!(U24::MAX.get(), 0xff_ffff);
+        assert!(U24::new(0xff_ffff).is_ok());
+        assert!(U24::new(0x100_0000).is_err());
+        assert!(U40::new(1 << 40).is_err());
+        assert!(U56::try_from(u64::MAX).is_err());
+        assert_eq!(U24::wrapping_new(0x123_4567).get(), 0x23_4567);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let value = U24::new(0x01_02ff).unwrap();
+        let key = crate::to_vec(&value).unwrap();
+
+        assert_eq!(&key, b"0102ff");
+        assert_eq!(crate::from_slice::<U24>(&key).unwrap(), value);
+
+        let key = crate::to_vec(&U40::MAX).unwrap();
+        assert_eq!(&key, b"ffffffffff");
+        assert_eq!(crate::from_slice::<U40>(&key).unwrap(), U40::MAX);
+
+        let key = crate::to_vec(&U56::new(1).unwrap()).unwrap();
+        assert_eq!(&key, b"00000000000001");
+
+        assert!(crate::from_slice::<U48>(b"0001").is_err());
+        assert!(crate::from_slice::<U48>(b"00000000000000").is_err());
+    }
+
+    #[test]
+    fn test_order() {
+        let values = [0, 1, 0xff, 0x100, 0xffff_ffff, 0xffff_ffff_ffff];
+        let keys = values
+            .iter()
+            .map(|value| crate::to_vec(&U48::new(*value).unwrap()).unwrap())
+            .collect::<Vec<_>>();
+
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
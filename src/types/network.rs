@@ -0,0 +1,199 @@
+use std::{
+    net::{Ipv4Addr, Ipv6Addr},
+    ops::Bound,
+};
+
+use ipnet::{Ipv4Net, Ipv6Net};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{error::Error, range::ByteRange};
+
+macro_rules! network_type {
+    ($(#[$attr:meta])* $name:ident($net:ident, $addr:ident, $int:ty);) => {
+        $(#[$attr])*
+        ///
+        /// The network is encoded as the network address followed by the
+        /// prefix length. A network sorts immediately before the subnets and
+        /// addresses inside it when addresses are stored as host networks,
+        /// so [`Self::range`] can find everything a network contains.
+        ///
+        /// Host bits are cleared on construction, and keys with host bits set
+        /// return an error on deserialization.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        pub struct $name($net);
+
+        impl $name {
+            /// Construct a network with the host bits cleared.
+            pub fn new(network: $net) -> Self {
+                Self(network.trunc())
+            }
+
+            /// Construct a network containing only the given address.
+            pub fn host(address: $addr) -> Self {
+                Self(address.into())
+            }
+
+            /// Returns the network.
+            pub fn get(&self) -> $net {
+                self.0
+            }
+
+            /// Returns the encoded range of the keys under the given prefix
+            /// with a network inside this network, including this network.
+            ///
+            /// The keys are expected to be laid out as `(prefix, network)`.
+            pub fn range<P>(&self, prefix: &P) -> Result<ByteRange, Error>
+            where
+                P: ?Sized + Serialize,
+            {
+                let start = crate::to_vec(&(prefix, self))?;
+                let last = crate::to_vec(&(
+                    prefix,
+                    <$int>::from(self.0.broadcast()),
+                    self.0.max_prefix_len(),
+                ))?;
+                let end = match crate::range::prefix_successor(&last) {
+                    Some(end) => Bound::Excluded(end),
+                    None => Bound::Unbounded,
+                };
+
+                Ok((Bound::Included(start), end))
+            }
+        }
+
+        impl From<$net> for $name {
+            fn from(network: $net) -> Self {
+                Self::new(network)
+            }
+        }
+
+        impl From<$addr> for $name {
+            fn from(address: $addr) -> Self {
+                Self::host(address)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                (<$int>::from(self.0.network()), self.0.prefix_len()).serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let (address, prefix_len) = <($int, u8)>::deserialize(deserializer)?;
+                let network =
+                    $net::new($addr::from(address), prefix_len).map_err(D::Error::custom)?;
+
+                if network != network.trunc() {
+                    return Err(D::Error::custom("network has host bits set"));
+                }
+
+                Ok(Self(network))
+            }
+        }
+    };
+}
+
+network_type! {
+    /// IPv4 network.
+    ///
+    /// Example:
+    ///
+    /// ```rust
+    /// use std::ops::RangeBounds;
+    ///
+    /// use strkey::types::Ipv4Network;
+    ///
+    /// # fn main() -> Result<(), strkey::Error> {
+    /// let network = Ipv4Network::new("10.1.0.0/16".parse().unwrap());
+    /// assert_eq!(&strkey::to_vec(&("route", network))?, b"route:0a010000:10");
+    ///
+    /// let host = Ipv4Network::host("10.1.2.3".parse().unwrap());
+    /// let range = network.range("route")?;
+    /// assert!(range.contains(&strkey::to_vec(&("route", host))?));
+    /// # Ok(())
+    /// # }
+    /// ```
+    Ipv4Network(Ipv4Net, Ipv4Addr, u32);
+}
+
+network_type! {
+    /// IPv6 network.
+    Ipv6Network(Ipv6Net, Ipv6Addr, u128);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::RangeBounds;
+
+    use super::*;
+
+    fn v4(text: &str) -> Ipv4Network {
+        Ipv4Network::new(text.parse().unwrap())
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let network = v4("192.168.1.7/24");
+        let key = crate::to_vec(&network).unwrap();
+
+        assert_eq!(&key, b"c0a80100:18");
+        assert_eq!(crate::from_slice::<Ipv4Network>(&key).unwrap(), network);
+        assert!(crate::from_slice::<Ipv4Network>(b"c0a80101:18").is_err());
+        assert!(crate::from_slice::<Ipv4Network>(b"c0a80100:21").is_err());
+
+        let network = Ipv6Network::new("2001:db8::/32".parse().unwrap());
+        let key = crate::to_vec(&network).unwrap();
+
+        assert_eq!(&key, b"20010db8000000000000000000000000:20");
+        assert_eq!(crate::from_slice::<Ipv6Network>(&key).unwrap(), network);
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut keys = [
+            "10.0.0.0/8",
+            "10.0.0.0/16",
+            "10.0.0.1/32",
+            "10.255.255.255/32",
+            "9.0.0.0/8",
+            "10.0.0.0/7",
+            "11.0.0.0/8",
+        ]
+        .iter()
+        .map(|text| crate::to_vec(&("a", v4(text), "x")).unwrap())
+        .collect::<Vec<_>>();
+        keys.sort();
+
+        let range = v4("10.0.0.0/8").range("a").unwrap();
+        let inside = keys
+            .iter()
+            .filter(|key| range.contains(*key))
+            .map(|key| {
+                crate::from_slice::<(&str, Ipv4Network, &str)>(key)
+                    .unwrap()
+                    .1
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            inside,
+            vec![
+                v4("10.0.0.0/8"),
+                v4("10.0.0.0/16"),
+                v4("10.0.0.1/32"),
+                v4("10.255.255.255/32")
+            ]
+        );
+
+        let range = v4("0.0.0.0/0").range(&()).unwrap();
+        assert!(range.contains(&crate::to_vec(&v4("255.255.255.255/32")).unwrap()));
+    }
+}
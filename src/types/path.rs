@@ -0,0 +1,227 @@
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{error::Error, ser::VERBATIM_NAME};
+
+/// Filesystem path.
+///
+/// The path is encoded as a string component with `%` and `:` escaped as
+/// `%25` and `%3a` so the path can contain the default deliminator. Paths
+/// that are not valid UTF-8 return an error unless `ESCAPE` is true, in which
+/// case the invalid bytes are escaped as `%` followed by two hexadecimal
+/// digits. Escaping invalid bytes requires a Unix platform; on other
+/// platforms these paths always return an error.
+///
+/// Unlike a lossy conversion, the encoding is reversible, so distinct paths
+/// never produce the same key. Note that escaped characters do not sort in
+/// their original order.
+///
+/// When serialized by [`crate::Serializer`], the escaped text is written as
+/// is, and an error is returned if it contains the deliminator.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::types::PathComponent;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let path: PathComponent = PathComponent::new("C:/100%.txt");
+/// let key = strkey::to_vec(&("file", path))?;
+/// assert_eq!(&key, b"file:C%3a/100%25.txt");
+///
+/// let (_, path) = strkey::from_slice::<(&str, PathComponent)>(&key)?;
+/// assert_eq!(path.as_path().to_str(), Some("C:/100%.txt"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct PathComponent<const ESCAPE: bool = false>(PathBuf);
+
+/// Filesystem path with bytes that are not valid UTF-8 escaped.
+pub type EscapedPathComponent = PathComponent<true>;
+
+impl<const ESCAPE: bool> PathComponent<ESCAPE> {
+    /// Construct a path component.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self(path.into())
+    }
+
+    /// Returns the path.
+    pub fn as_path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Unwrap and return the path.
+    pub fn into_inner(self) -> PathBuf {
+        self.0
+    }
+
+    fn escape(&self) -> Result<String, Error> {
+        let mut output = String::new();
+
+        if let Some(text) = self.0.to_str() {
+            escape_str(text, &mut output);
+        } else if ESCAPE {
+            escape_bytes(os_bytes(&self.0)?, &mut output);
+        } else {
+            return Err(Error::Data(format!("path {:?} is not valid UTF-8", self.0)));
+        }
+
+        Ok(output)
+    }
+
+    fn unescape(text: &str) -> Result<PathBuf, Error> {
+        let mut bytes = Vec::with_capacity(text.len());
+        let mut input = text.as_bytes();
+
+        while let Some((&byte, rest)) = input.split_first() {
+            if byte == b'%' {
+                let digits = rest
+                    .get(..2)
+                    .and_then(|digits| std::str::from_utf8(digits).ok())
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+                    .ok_or_else(|| Error::Data(format!("invalid escape in path {:?}", text)))?;
+
+                bytes.push(digits);
+                input = &rest[2..];
+            } else {
+                bytes.push(byte);
+                input = rest;
+            }
+        }
+
+        match String::from_utf8(bytes) {
+            Ok(text) => Ok(PathBuf::from(text)),
+            Err(error) if ESCAPE => path_from_bytes(error.into_bytes()),
+            Err(error) => Err(error.utf8_error().into()),
+        }
+    }
+}
+
+impl<const ESCAPE: bool> From<PathBuf> for PathComponent<ESCAPE> {
+    fn from(path: PathBuf) -> Self {
+        Self(path)
+    }
+}
+
+fn escape_str(text: &str, output: &mut String) {
+    for c in text.chars() {
+        match c {
+            '%' => output.push_str("%25"),
+            ':' => output.push_str("%3a"),
+            _ => output.push(c),
+        }
+    }
+}
+
+fn escape_bytes(mut bytes: &[u8], output: &mut String) {
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => {
+                escape_str(text, output);
+                break;
+            }
+            Err(error) => {
+                let (valid, rest) = bytes.split_at(error.valid_up_to());
+                let invalid_len = error.error_len().unwrap_or(rest.len());
+
+                escape_str(std::str::from_utf8(valid).unwrap(), output);
+
+                for byte in &rest[..invalid_len] {
+                    write!(output, "%{:02x}", byte).unwrap();
+                }
+
+                bytes = &rest[invalid_len..];
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn os_bytes(path: &Path) -> Result<&[u8], Error> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Ok(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+fn os_bytes(path: &Path) -> Result<&[u8], Error> {
+    Err(Error::Data(format!("path {:?} is not valid UTF-8", path)))
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> Result<PathBuf, Error> {
+    use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+    Ok(PathBuf::from(OsString::from_vec(bytes)))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> Result<PathBuf, Error> {
+    Err(Error::Data(format!("path {:?} is not valid UTF-8", bytes)))
+}
+
+impl<const ESCAPE: bool> Serialize for PathComponent<ESCAPE> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let text = self.escape().map_err(serde::ser::Error::custom)?;
+
+        serializer.serialize_newtype_struct(VERBATIM_NAME, &text)
+    }
+}
+
+impl<'de, const ESCAPE: bool> Deserialize<'de> for PathComponent<ESCAPE> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+
+        Self::unescape(&text).map(Self).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let path: PathComponent = PathComponent::new("/a:b/%3a/c d");
+        let key = crate::to_vec(&(&path, 1u8)).unwrap();
+
+        assert_eq!(&key, b"/a%3ab/%253a/c d:01");
+        assert_eq!(
+            crate::from_slice::<(PathComponent, u8)>(&key).unwrap(),
+            (path, 1)
+        );
+        assert!(crate::from_slice::<PathComponent>(b"a%3").is_err());
+        assert!(crate::from_slice::<PathComponent>(b"a%zz").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let path = PathBuf::from(OsStr::from_bytes(b"a\xffb\xc3\xa9:"));
+
+        assert!(crate::to_vec(&PathComponent::<false>::new(path.clone())).is_err());
+
+        let key = crate::to_vec(&EscapedPathComponent::new(path.clone())).unwrap();
+        assert_eq!(key, "a%ffbé%3a".as_bytes());
+        assert_eq!(
+            crate::from_slice::<EscapedPathComponent>(&key)
+                .unwrap()
+                .into_inner(),
+            path
+        );
+        assert!(crate::from_slice::<PathComponent>(&key).is_err());
+    }
+}
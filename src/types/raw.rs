@@ -0,0 +1,105 @@
+use serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::ser::VERBATIM_NAME;
+
+/// Component that is already encoded.
+///
+/// When serialized by [`crate::Serializer`], the bytes are written as is as
+/// a single component. An error is returned if the bytes are not UTF-8 or
+/// contain the deliminator. When deserialized, the component's text is
+/// returned unchanged. Deserializing requires borrowing from the input, so
+/// it is not supported by [`crate::from_reader`].
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::types::RawComponent;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let key = strkey::to_vec(&("blob", RawComponent(b"sha256-AbC"), 1u8))?;
+/// assert_eq!(&key, b"blob:sha256-AbC:01");
+///
+/// let (_, raw, _) = strkey::from_slice::<(&str, RawComponent, u8)>(&key)?;
+/// assert_eq!(raw.as_bytes(), b"sha256-AbC");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RawComponent<'a>(pub &'a [u8]);
+
+impl<'a> RawComponent<'a> {
+    /// Returns the encoded bytes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Serialize for Bytes<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+impl<'a> Serialize for RawComponent<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(VERBATIM_NAME, &Bytes(self.0))
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for RawComponent<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct RawVisitor;
+
+        impl<'de> Visitor<'de> for RawVisitor {
+            type Value = RawComponent<'de>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a borrowed string")
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E> {
+                Ok(RawComponent(v.as_bytes()))
+            }
+        }
+
+        deserializer.deserialize_str(RawVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Serializer;
+
+    #[test]
+    fn test_round_trip() {
+        let key = crate::to_vec(&RawComponent("é%20".as_bytes())).unwrap();
+
+        assert_eq!(key, "é%20".as_bytes());
+        assert_eq!(
+            crate::from_slice::<RawComponent>(&key).unwrap(),
+            RawComponent("é%20".as_bytes())
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(crate::to_vec(&RawComponent(b"a:b")).is_err());
+        assert!(crate::to_vec(&RawComponent(b"\xff")).is_err());
+
+        let mut serializer = Serializer::new(Vec::new()).with_deliminator("/");
+        assert!(RawComponent(b"a:b").serialize(&mut serializer).is_ok());
+        assert!(RawComponent(b"a/b").serialize(&mut serializer).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+use std::{
+    cmp::Ordering,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Timestamp that sorts newest first.
+///
+/// The timestamp is stored as milliseconds since the Unix epoch and encoded
+/// as a `u64` of `u64::MAX` minus the milliseconds. As a result, iterating
+/// keys in ascending order visits the newest timestamps first. The `Ord`
+/// implementation follows the encoded order.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::types::ReverseTimestamp;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let older = strkey::to_vec(&("event", ReverseTimestamp::from_millis(1000)))?;
+/// let newer = strkey::to_vec(&("event", ReverseTimestamp::from_millis(2000)))?;
+///
+/// assert_eq!(&newer, b"event:fffffffffffff82f");
+/// assert!(newer < older);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReverseTimestamp(u64);
+
+impl ReverseTimestamp {
+    /// Construct from milliseconds since the Unix epoch.
+    pub fn from_millis(millis: u64) -> Self {
+        Self(millis)
+    }
+
+    /// Construct from a system time.
+    ///
+    /// Times before the Unix epoch are clamped to the epoch.
+    pub fn from_system_time(time: SystemTime) -> Self {
+        let millis = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+
+        Self(millis.min(u64::MAX as u128) as u64)
+    }
+
+    /// Construct from the current system time.
+    pub fn now() -> Self {
+        Self::from_system_time(SystemTime::now())
+    }
+
+    /// Returns the milliseconds since the Unix epoch.
+    pub fn as_millis(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the timestamp as a system time.
+    pub fn to_system_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.0)
+    }
+}
+
+impl Ord for ReverseTimestamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+impl PartialOrd for ReverseTimestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<SystemTime> for ReverseTimestamp {
+    fn from(time: SystemTime) -> Self {
+        Self::from_system_time(time)
+    }
+}
+
+impl Serialize for ReverseTimestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (u64::MAX - self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ReverseTimestamp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Self(u64::MAX - u64::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding() {
+        let key = crate::to_vec(&ReverseTimestamp::from_millis(0)).unwrap();
+        assert_eq!(&key, b"ffffffffffffffff");
+
+        let key = crate::to_vec(&ReverseTimestamp::from_millis(u64::MAX)).unwrap();
+        assert_eq!(&key, b"0000000000000000");
+
+        let value = crate::from_slice::<ReverseTimestamp>(b"fffffffffffffffe").unwrap();
+        assert_eq!(value.as_millis(), 1);
+    }
+
+    #[test]
+    fn test_order() {
+        let mut values = [
+            ReverseTimestamp::from_millis(5),
+            ReverseTimestamp::from_millis(1_620_000_000_000),
+            ReverseTimestamp::from_millis(0),
+            ReverseTimestamp::from_millis(300),
+        ];
+        values.sort();
+
+        let keys = values
+            .iter()
+            .map(|value| crate::to_vec(value).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(values[0].as_millis(), 1_620_000_000_000);
+        assert!(keys.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_system_time() {
+        let time = UNIX_EPOCH + Duration::from_millis(1234);
+        let value = ReverseTimestamp::from(time);
+
+        assert_eq!(value.as_millis(), 1234);
+        assert_eq!(value.to_system_time(), time);
+
+        let value = ReverseTimestamp::from_system_time(UNIX_EPOCH - Duration::from_secs(1));
+        assert_eq!(value.as_millis(), 0);
+    }
+}
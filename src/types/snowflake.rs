@@ -0,0 +1,170 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Error;
+
+/// Snowflake-style ID.
+///
+/// The ID is a `u64` consisting of, from the most significant bits, an
+/// unused sign bit, a 41-bit timestamp in milliseconds since an epoch, a
+/// 10-bit worker ID, and a 12-bit sequence number. This is the layout used by
+/// Twitter and many other systems. The ID is encoded as a `u64` component so
+/// IDs sort by time.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::types::SnowflakeId;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let id = SnowflakeId::from_u64(1382971839000211456);
+///
+/// assert_eq!(id.unix_millis(SnowflakeId::TWITTER_EPOCH), 1618561155448);
+/// assert_eq!(&strkey::to_vec(&id)?, b"13314d8f6df9f000");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SnowflakeId(u64);
+
+impl SnowflakeId {
+    /// Epoch used by Twitter in milliseconds since the Unix epoch.
+    pub const TWITTER_EPOCH: u64 = 1288834974657;
+
+    /// Maximum value of the timestamp.
+    pub const MAX_TIMESTAMP: u64 = (1 << 41) - 1;
+
+    /// Maximum value of the worker ID.
+    pub const MAX_WORKER: u16 = (1 << 10) - 1;
+
+    /// Maximum value of the sequence number.
+    pub const MAX_SEQUENCE: u16 = (1 << 12) - 1;
+
+    /// Construct an ID from its fields.
+    ///
+    /// The timestamp is in milliseconds since the epoch chosen by the
+    /// system issuing the IDs. Returns an error if a field is out of range.
+    pub fn from_parts(timestamp: u64, worker: u16, sequence: u16) -> Result<Self, Error> {
+        if timestamp > Self::MAX_TIMESTAMP
+            || worker > Self::MAX_WORKER
+            || sequence > Self::MAX_SEQUENCE
+        {
+            return Err(Error::Data(format!(
+                "{}:{}:{}",
+                timestamp, worker, sequence
+            )));
+        }
+
+        Ok(Self(
+            timestamp << 22 | (worker as u64) << 12 | sequence as u64,
+        ))
+    }
+
+    /// Construct from the raw value.
+    pub fn from_u64(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the raw value.
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the timestamp in milliseconds since the issuing system's epoch.
+    pub fn timestamp(&self) -> u64 {
+        (self.0 >> 22) & Self::MAX_TIMESTAMP
+    }
+
+    /// Returns the timestamp in milliseconds since the Unix epoch given the
+    /// issuing system's epoch.
+    pub fn unix_millis(&self, epoch: u64) -> u64 {
+        self.timestamp() + epoch
+    }
+
+    /// Returns the worker ID.
+    pub fn worker(&self) -> u16 {
+        ((self.0 >> 12) as u16) & Self::MAX_WORKER
+    }
+
+    /// Returns the sequence number.
+    pub fn sequence(&self) -> u16 {
+        (self.0 as u16) & Self::MAX_SEQUENCE
+    }
+
+    /// Returns the smallest ID with the given timestamp.
+    ///
+    /// Timestamps beyond [`Self::MAX_TIMESTAMP`] are clamped.
+    pub fn min_for_timestamp(timestamp: u64) -> Self {
+        Self(timestamp.min(Self::MAX_TIMESTAMP) << 22)
+    }
+
+    /// Returns the largest ID with the given timestamp.
+    ///
+    /// Timestamps beyond [`Self::MAX_TIMESTAMP`] are clamped.
+    pub fn max_for_timestamp(timestamp: u64) -> Self {
+        Self(timestamp.min(Self::MAX_TIMESTAMP) << 22 | ((1 << 22) - 1))
+    }
+}
+
+impl Serialize for SnowflakeId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SnowflakeId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u64::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parts() {
+        let id = SnowflakeId::from_parts(1000, 5, 7).unwrap();
+
+        assert_eq!(id.timestamp(), 1000);
+        assert_eq!(id.worker(), 5);
+        assert_eq!(id.sequence(), 7);
+        assert_eq!(id.unix_millis(10), 1010);
+
+        let id = SnowflakeId::from_parts(
+            SnowflakeId::MAX_TIMESTAMP,
+            SnowflakeId::MAX_WORKER,
+            SnowflakeId::MAX_SEQUENCE,
+        )
+        .unwrap();
+        assert_eq!(id.as_u64(), i64::MAX as u64);
+
+        assert!(SnowflakeId::from_parts(SnowflakeId::MAX_TIMESTAMP + 1, 0, 0).is_err());
+        assert!(SnowflakeId::from_parts(0, SnowflakeId::MAX_WORKER + 1, 0).is_err());
+        assert!(SnowflakeId::from_parts(0, 0, SnowflakeId::MAX_SEQUENCE + 1).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let id = SnowflakeId::from_parts(1000, 5, 7).unwrap();
+        let key = crate::to_vec(&id).unwrap();
+
+        assert_eq!(&key, b"00000000fa005007");
+        assert_eq!(crate::from_slice::<SnowflakeId>(&key).unwrap(), id);
+    }
+
+    #[test]
+    fn test_timestamp_bounds() {
+        let id = SnowflakeId::from_parts(1000, 5, 7).unwrap();
+
+        assert!(SnowflakeId::min_for_timestamp(1000) <= id);
+        assert!(id <= SnowflakeId::max_for_timestamp(1000));
+        assert!(SnowflakeId::max_for_timestamp(999) < id);
+        assert!(id < SnowflakeId::min_for_timestamp(1001));
+    }
+}
@@ -0,0 +1,111 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::ser::VERBATIM_NAME;
+
+/// Value encoded as its text form.
+///
+/// The value is written using its [`Display`] implementation and read using
+/// its [`FromStr`] implementation instead of the usual encoding. This allows
+/// reading and writing keys produced by other systems, such as keys with
+/// plain decimal numbers. Note that the text form might not sort in the
+/// value's order.
+///
+/// When serialized by [`crate::Serializer`], the text is written as is, even
+/// if percent-encoding is enabled, and an error is returned if the text
+/// contains the deliminator.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::types::Verbatim;
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let key = strkey::to_vec(&("order", Verbatim(1234u32)))?;
+/// assert_eq!(&key, b"order:1234");
+///
+/// let (_, id) = strkey::from_slice::<(&str, Verbatim<u32>)>(&key)?;
+/// assert_eq!(id.into_inner(), 1234);
+///
+/// assert!(strkey::to_vec(&Verbatim("a:b")).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Verbatim<T>(pub T);
+
+impl<T> Verbatim<T> {
+    /// Unwrap and return the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+struct DisplayStr<'a, T>(&'a T);
+
+impl<'a, T: Display> Serialize for DisplayStr<'a, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self.0)
+    }
+}
+
+impl<T: Display> Serialize for Verbatim<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(VERBATIM_NAME, &DisplayStr(&self.0))
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Verbatim<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+
+        text.parse().map(Verbatim).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Serializer;
+
+    #[test]
+    fn test_round_trip() {
+        let key = crate::to_vec(&(Verbatim(-12i64), Verbatim(1.5f32))).unwrap();
+
+        assert_eq!(&key, b"-12:1.5");
+        assert_eq!(
+            crate::from_slice::<(Verbatim<i64>, Verbatim<f32>)>(&key).unwrap(),
+            (Verbatim(-12), Verbatim(1.5))
+        );
+        assert!(crate::from_slice::<Verbatim<u8>>(b"x").is_err());
+    }
+
+    #[test]
+    fn test_deliminator() {
+        let mut buffer = Vec::new();
+        let mut serializer = Serializer::new(&mut buffer)
+            .with_deliminator("/")
+            .with_percent_encoding(true);
+
+        (Verbatim("a:b"), "c d").serialize(&mut serializer).unwrap();
+        assert_eq!(&buffer, b"a:b/c%20d");
+
+        let mut serializer = Serializer::new(Vec::new()).with_deliminator("/");
+        assert!(Verbatim("a/b").serialize(&mut serializer).is_err());
+        assert!(crate::to_vec(&Verbatim("a:b")).is_err());
+    }
+}
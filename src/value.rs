@@ -0,0 +1,1464 @@
+//! Dynamic values
+//!
+//! Keys can be decoded and encoded without compile-time types by describing
+//! the components with a [`Schema`]. This is useful for generic tooling such
+//! as command line programs and administration interfaces.
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::{FieldType, Schema, Value};
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let schema = Schema::new(vec![FieldType::Str, FieldType::U32]);
+//! let values = strkey::decode_with_schema(b"account:000004d2", &schema)?;
+//!
+//! assert_eq!(values, vec![Value::Str("account".to_string()), Value::U64(1234)]);
+//! assert_eq!(strkey::encode_with_schema(&values, &schema)?, b"account:000004d2");
+//! # Ok(())
+//! # }
+//! ```
+use std::{collections::BTreeMap, convert::TryFrom, fmt::Display, str::FromStr};
+
+use serde::{
+    de::{DeserializeOwned, DeserializeSeed, IntoDeserializer, SeqAccess, Visitor},
+    ser::{Error as _, SerializeTuple},
+    Deserialize, Serialize, Serializer,
+};
+
+use crate::{
+    config::{Encoding, EncodingOverrides},
+    de::{Deserializer, SliceReader},
+    error::Error,
+    types::{Eui64, MacAddr},
+};
+
+/// Decoded component value.
+///
+/// Integers of all widths are widened to 64 bits and `f32` to `f64`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// String or char.
+    Str(String),
+    /// Unsigned integer.
+    U64(u64),
+    /// Signed integer.
+    I64(i64),
+    /// Floating point number.
+    F64(f64),
+    /// Byte array.
+    Bytes(Vec<u8>),
+    /// Boolean.
+    Bool(bool),
+    /// Unit, which has no component.
+    Unit,
+}
+
+impl Value {
+    /// Parses the text form of a value of the given type.
+    ///
+    /// Integers and floats are decimal, byte arrays are hexadecimal, booleans
+    /// are `true` or `false`, and units are empty. This is the inverse of the
+    /// [`Display`] implementation. Hardware addresses also accept separators
+    /// as described in [`MacAddr`].
+    pub fn parse(text: &str, field: FieldType) -> Result<Self, Error> {
+        fn number<T: FromStr>(text: &str) -> Result<T, Error> {
+            text.parse().map_err(|_| Error::Data(text.to_string()))
+        }
+
+        Ok(match field {
+            FieldType::U8 => Value::U64(number::<u8>(text)?.into()),
+            FieldType::U16 => Value::U64(number::<u16>(text)?.into()),
+            FieldType::U32 => Value::U64(number::<u32>(text)?.into()),
+            FieldType::U64 => Value::U64(number(text)?),
+            FieldType::I8 => Value::I64(number::<i8>(text)?.into()),
+            FieldType::I16 => Value::I64(number::<i16>(text)?.into()),
+            FieldType::I32 => Value::I64(number::<i32>(text)?.into()),
+            FieldType::I64 => Value::I64(number(text)?),
+            FieldType::F32 | FieldType::F64 => Value::F64(number(text)?),
+            FieldType::Str => Value::Str(text.to_string()),
+            FieldType::Bytes => {
+                Value::Bytes(hex::decode(text).map_err(|_| Error::Data(text.to_string()))?)
+            }
+            FieldType::Bool => Value::Bool(number(text)?),
+            FieldType::Unit if text.is_empty() => Value::Unit,
+            FieldType::Unit => return Err(Error::Data(text.to_string())),
+            FieldType::MacAddr => Value::Bytes(MacAddr::from_str(text)?.0.to_vec()),
+            FieldType::Eui64 => Value::Bytes(Eui64::from_str(text)?.0.to_vec()),
+        })
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Str(value) => f.write_str(value),
+            Value::U64(value) => write!(f, "{}", value),
+            Value::I64(value) => write!(f, "{}", value),
+            Value::F64(value) => write!(f, "{}", value),
+            Value::Bytes(value) => f.write_str(&hex::encode(value)),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Unit => Ok(()),
+        }
+    }
+}
+
+/// Type of a component in a schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldType {
+    /// `u8`
+    U8,
+    /// `u16`
+    U16,
+    /// `u32`
+    U32,
+    /// `u64`
+    U64,
+    /// `i8`
+    I8,
+    /// `i16`
+    I16,
+    /// `i32`
+    I32,
+    /// `i64`
+    I64,
+    /// `f32`
+    F32,
+    /// `f64`
+    F64,
+    /// String
+    Str,
+    /// Byte array
+    Bytes,
+    /// `bool`
+    Bool,
+    /// `()`
+    Unit,
+    /// [`MacAddr`](crate::types::MacAddr), decoded as bytes.
+    MacAddr,
+    /// [`Eui64`](crate::types::Eui64), decoded as bytes.
+    Eui64,
+}
+
+impl FieldType {
+    /// Returns the lowercase name of the type, such as `u32` or `str`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FieldType::U8 => "u8",
+            FieldType::U16 => "u16",
+            FieldType::U32 => "u32",
+            FieldType::U64 => "u64",
+            FieldType::I8 => "i8",
+            FieldType::I16 => "i16",
+            FieldType::I32 => "i32",
+            FieldType::I64 => "i64",
+            FieldType::F32 => "f32",
+            FieldType::F64 => "f64",
+            FieldType::Str => "str",
+            FieldType::Bytes => "bytes",
+            FieldType::Bool => "bool",
+            FieldType::Unit => "unit",
+            FieldType::MacAddr => "mac",
+            FieldType::Eui64 => "eui64",
+        }
+    }
+}
+
+impl Display for FieldType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FieldType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "u8" => FieldType::U8,
+            "u16" => FieldType::U16,
+            "u32" => FieldType::U32,
+            "u64" => FieldType::U64,
+            "i8" => FieldType::I8,
+            "i16" => FieldType::I16,
+            "i32" => FieldType::I32,
+            "i64" => FieldType::I64,
+            "f32" => FieldType::F32,
+            "f64" => FieldType::F64,
+            "str" => FieldType::Str,
+            "bytes" => FieldType::Bytes,
+            "bool" => FieldType::Bool,
+            "unit" => FieldType::Unit,
+            "mac" => FieldType::MacAddr,
+            "eui64" => FieldType::Eui64,
+            _ => return Err(Error::Data(s.to_string())),
+        })
+    }
+}
+
+/// Sequence of component types describing the layout of a key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Schema {
+    fields: Vec<FieldType>,
+}
+
+impl Schema {
+    /// Construct a schema with the given component types.
+    pub fn new(fields: Vec<FieldType>) -> Self {
+        Self { fields }
+    }
+
+    /// Appends a component type.
+    pub fn push(&mut self, field: FieldType) {
+        self.fields.push(field);
+    }
+
+    /// Returns the component types.
+    pub fn fields(&self) -> &[FieldType] {
+        &self.fields
+    }
+
+    /// Returns the changes from the old schema that prevent keys encoded
+    /// with it from being decoded or sorted correctly with this schema.
+    ///
+    /// Adding fields after the last field is compatible if missing trailing
+    /// components are allowed when decoding. Unit fields are ignored as they
+    /// are encoded as nothing.
+    ///
+    /// Example:
+    ///
+    /// ```rust
+    /// use strkey::{value::Incompatibility, FieldType, Schema};
+    ///
+    /// let old = Schema::new(vec![FieldType::Str, FieldType::U32]);
+    /// let new = Schema::new(vec![FieldType::Str, FieldType::U32, FieldType::Bool]);
+    /// assert!(new.compatible_with(&old).is_empty());
+    ///
+    /// let new = Schema::new(vec![FieldType::Str, FieldType::U64]);
+    /// assert_eq!(
+    ///     new.compatible_with(&old),
+    ///     vec![Incompatibility::TypeChanged(1, FieldType::U32, FieldType::U64)]
+    /// );
+    /// ```
+    pub fn compatible_with(&self, old: &Schema) -> Vec<Incompatibility> {
+        let old = old.components();
+        let new = self.components();
+
+        if new.starts_with(&old) {
+            return Vec::new();
+        }
+
+        if new.len() > old.len() {
+            if let Some(added) = inserted_indexes(&old, &new) {
+                return added
+                    .into_iter()
+                    .map(|index| Incompatibility::Added(index, new[index]))
+                    .collect();
+            }
+        }
+
+        if new.len() == old.len() {
+            let mut old_sorted = old.iter().map(FieldType::as_str).collect::<Vec<_>>();
+            let mut new_sorted = new.iter().map(FieldType::as_str).collect::<Vec<_>>();
+            old_sorted.sort_unstable();
+            new_sorted.sort_unstable();
+
+            if old_sorted == new_sorted {
+                return vec![Incompatibility::Reordered];
+            }
+        }
+
+        let mut issues = old
+            .iter()
+            .zip(&new)
+            .enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(index, (old, new))| Incompatibility::TypeChanged(index, *old, *new))
+            .collect::<Vec<_>>();
+
+        issues.extend(
+            old.iter()
+                .enumerate()
+                .skip(new.len())
+                .map(|(index, field)| Incompatibility::Removed(index, *field)),
+        );
+        issues
+    }
+
+    fn components(&self) -> Vec<FieldType> {
+        self.fields
+            .iter()
+            .copied()
+            .filter(|field| *field != FieldType::Unit)
+            .collect()
+    }
+}
+
+/// Returns the indexes of the fields inserted before the last old field if
+/// the old fields are a subsequence of the new fields.
+fn inserted_indexes(old: &[FieldType], new: &[FieldType]) -> Option<Vec<usize>> {
+    let mut added = Vec::new();
+    let mut matched = 0;
+
+    for (index, field) in new.iter().enumerate() {
+        if matched == old.len() {
+            break;
+        } else if old[matched] == *field {
+            matched += 1;
+        } else {
+            added.push(index);
+        }
+    }
+
+    if matched == old.len() {
+        Some(added)
+    } else {
+        None
+    }
+}
+
+/// Change between schemas that breaks compatibility of keys.
+///
+/// Indexes are of components and do not count unit fields.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Incompatibility {
+    /// The type of the component at the index changed from the old type to
+    /// the new type, such as a change of integer width.
+    TypeChanged(usize, FieldType, FieldType),
+
+    /// A component was added at the index before existing components.
+    Added(usize, FieldType),
+
+    /// The component at the index was removed.
+    Removed(usize, FieldType),
+
+    /// The components have the same types in a different order.
+    Reordered,
+}
+
+impl Display for Incompatibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Incompatibility::TypeChanged(index, old, new) => {
+                write!(f, "component {} changed from {} to {}", index, old, new)
+            }
+            Incompatibility::Added(index, field) => {
+                write!(f, "component {} of type {} was added", index, field)
+            }
+            Incompatibility::Removed(index, field) => {
+                write!(f, "component {} of type {} was removed", index, field)
+            }
+            Incompatibility::Reordered => f.write_str("components were reordered"),
+        }
+    }
+}
+
+/// Worst-case encoded key length of a schema.
+///
+/// Strings and byte arrays have no fixed width, so their maximum length in
+/// bytes must be given by the index of the field in the schema. The other
+/// types have a fixed maximum width. This is intended for checking layouts
+/// against a store's key size limit at design time.
+///
+/// Example:
+///
+/// ```rust
+/// use strkey::{value::LengthBudget, FieldType, Schema};
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let schema = Schema::new(vec![FieldType::Str, FieldType::U64, FieldType::Bytes]);
+/// let budget = LengthBudget::new(256).with_max_len(0, 32).with_max_len(2, 16);
+///
+/// assert_eq!(budget.max_encoded_len(&schema)?, 32 + 1 + 16 + 1 + 32);
+/// assert!(budget.check(&schema).is_ok());
+/// assert!(LengthBudget::new(64).with_max_len(0, 32).with_max_len(2, 16).check(&schema).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LengthBudget {
+    limit: usize,
+    max_lengths: BTreeMap<usize, usize>,
+    deliminator_len: usize,
+    percent_encoding: bool,
+}
+
+impl LengthBudget {
+    /// Construct a budget with the given maximum key length in bytes.
+    ///
+    /// The deliminator is assumed to be 1 byte long.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            max_lengths: BTreeMap::new(),
+            deliminator_len: 1,
+            percent_encoding: false,
+        }
+    }
+
+    /// Returns the maximum key length in bytes.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Sets the maximum length in bytes of the string or byte array field at the index.
+    pub fn set_max_len(&mut self, index: usize, len: usize) {
+        self.max_lengths.insert(index, len);
+    }
+
+    /// Sets the maximum length in bytes of the string or byte array field at
+    /// the index and returns the budget.
+    pub fn with_max_len(mut self, index: usize, len: usize) -> Self {
+        self.set_max_len(index, len);
+        self
+    }
+
+    /// Sets the length of the deliminator in bytes.
+    pub fn set_deliminator_len(&mut self, len: usize) {
+        self.deliminator_len = len;
+    }
+
+    /// Sets the length of the deliminator in bytes and returns the budget.
+    pub fn with_deliminator_len(mut self, len: usize) -> Self {
+        self.set_deliminator_len(len);
+        self
+    }
+
+    /// Sets whether strings are percent-encoded, which can triple their length.
+    pub fn set_percent_encoding(&mut self, value: bool) {
+        self.percent_encoding = value;
+    }
+
+    /// Sets whether strings are percent-encoded and returns the budget.
+    pub fn with_percent_encoding(mut self, value: bool) -> Self {
+        self.set_percent_encoding(value);
+        self
+    }
+
+    /// Returns the worst-case encoded length of keys of the schema.
+    ///
+    /// Returns [`Error::Other`] if a string or byte array field has no
+    /// maximum length.
+    pub fn max_encoded_len(&self, schema: &Schema) -> Result<usize, Error> {
+        let mut total = 0;
+        let mut components = 0usize;
+
+        for (index, field) in schema.fields.iter().enumerate() {
+            let width = match field {
+                FieldType::U8 | FieldType::I8 => 2,
+                FieldType::U16 | FieldType::I16 => 4,
+                FieldType::U32 | FieldType::I32 | FieldType::F32 => 8,
+                FieldType::U64 | FieldType::I64 | FieldType::F64 => 16,
+                FieldType::Bool => 5,
+                FieldType::Unit => continue,
+                FieldType::MacAddr => 12,
+                FieldType::Eui64 => 16,
+                FieldType::Str | FieldType::Bytes => {
+                    let len = *self.max_lengths.get(&index).ok_or_else(|| {
+                        Error::Other(format!("component {} has no maximum length", index))
+                    })?;
+
+                    match field {
+                        FieldType::Bytes => len * 2,
+                        _ if self.percent_encoding => len * 3,
+                        _ => len,
+                    }
+                }
+            };
+
+            total += width;
+            components += 1;
+        }
+
+        Ok(total + components.saturating_sub(1) * self.deliminator_len)
+    }
+
+    /// Checks that the worst-case encoded length of keys of the schema is
+    /// within the limit and returns the length.
+    ///
+    /// Returns [`Error::KeyTooLong`] if the length exceeds the limit.
+    pub fn check(&self, schema: &Schema) -> Result<usize, Error> {
+        let len = self.max_encoded_len(schema)?;
+
+        if len > self.limit {
+            Err(Error::KeyTooLong(len, self.limit))
+        } else {
+            Ok(len)
+        }
+    }
+}
+
+impl From<Vec<FieldType>> for Schema {
+    fn from(fields: Vec<FieldType>) -> Self {
+        Self::new(fields)
+    }
+}
+
+/// Decodes a key into values using the given schema.
+pub fn decode_with_schema(key: &[u8], schema: &Schema) -> Result<Vec<Value>, Error> {
+    let mut deserializer = Deserializer::from_slice(key);
+    let mut values = Vec::with_capacity(schema.fields.len());
+
+    for field in &schema.fields {
+        let de = &mut deserializer;
+        let value = match field {
+            FieldType::U8 => Value::U64(u8::deserialize(de)?.into()),
+            FieldType::U16 => Value::U64(u16::deserialize(de)?.into()),
+            FieldType::U32 => Value::U64(u32::deserialize(de)?.into()),
+            FieldType::U64 => Value::U64(u64::deserialize(de)?),
+            FieldType::I8 => Value::I64(i8::deserialize(de)?.into()),
+            FieldType::I16 => Value::I64(i16::deserialize(de)?.into()),
+            FieldType::I32 => Value::I64(i32::deserialize(de)?.into()),
+            FieldType::I64 => Value::I64(i64::deserialize(de)?),
+            FieldType::F32 => Value::F64(f32::deserialize(de)?.into()),
+            FieldType::F64 => Value::F64(f64::deserialize(de)?),
+            FieldType::Str => Value::Str(String::deserialize(de)?),
+            FieldType::Bytes => Value::Bytes(ByteBuf::deserialize(de)?.0),
+            FieldType::Bool => Value::Bool(bool::deserialize(de)?),
+            FieldType::Unit => {
+                <()>::deserialize(de)?;
+                Value::Unit
+            }
+            FieldType::MacAddr => Value::Bytes(MacAddr::deserialize(de)?.0.to_vec()),
+            FieldType::Eui64 => Value::Bytes(Eui64::deserialize(de)?.0.to_vec()),
+        };
+
+        values.push(value);
+    }
+
+    deserializer.end()?;
+
+    Ok(values)
+}
+
+/// Replaces the component at the given index of a key laid out as `T`.
+///
+/// Only the new value is encoded, with the encoding override of the
+/// component at the index. The components before the index are decoded to
+/// find the component, the new component is decoded as the component of
+/// `T` so a value of the wrong type is rejected, and the components after
+/// it are copied as is without being decoded. Unit fields have no
+/// component and are not counted.
+///
+/// This uses the default configuration. See
+/// [`crate::config::Config::replace_component`] for other deliminators.
+///
+/// Returns [`Error::Other`] if the key has no component at the index,
+/// [`Error::UnsupportedType`] if the layout of `T` cannot be followed up to
+/// the index, [`Error::Data`] if the new value is not encoded as exactly
+/// one component without the deliminator, and the decoding error if a
+/// component before the index or the new component is not valid.
+///
+/// Example:
+///
+/// ```rust
+/// # fn main() -> Result<(), strkey::Error> {
+/// let key = strkey::to_vec(&("tenant-a", 42u32, "name"))?;
+/// let key = strkey::replace_component::<(String, u32, String)>(&key, 0, "tenant-b")?;
+///
+/// assert_eq!(&key, b"tenant-b:0000002a:name");
+/// assert!(strkey::replace_component::<(String, u32, String)>(&key, 1, &7u8).is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub fn replace_component<T>(
+    key: &[u8],
+    index: usize,
+    value: &(impl Serialize + ?Sized),
+) -> Result<Vec<u8>, Error>
+where
+    T: DeserializeOwned,
+{
+    crate::config::Config::new().replace_component::<T>(key, index, value)
+}
+
+/// Decodes a key into the values of the fields of the struct `T`.
+///
+/// The key is decoded as `T` and the value of each named field is recorded
+/// instead of returning the struct. Fields of nested structs are recorded
+/// by their own names. Returns an error if a field consists of more than
+/// one component, such as a tuple, and an empty map if `T` is not a struct.
+///
+/// Example:
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use strkey::Value;
+///
+/// #[derive(Deserialize)]
+/// struct AccountKey {
+///     tenant: String,
+///     id: u32,
+/// }
+///
+/// # fn main() -> Result<(), strkey::Error> {
+/// let fields = strkey::value::decode_fields::<AccountKey>(b"acme:000004d2")?;
+///
+/// assert_eq!(fields["tenant"], Value::Str("acme".to_string()));
+/// assert_eq!(fields["id"], Value::U64(1234));
+/// # Ok(())
+/// # }
+/// ```
+pub fn decode_fields<'a, T>(key: &'a [u8]) -> Result<BTreeMap<&'static str, Value>, Error>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_slice(key);
+    deserializer.record_fields();
+    T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+
+    Ok(deserializer.take_fields())
+}
+
+/// Encodes values into a key using the given schema.
+///
+/// Returns an error if the number of values does not match the schema or a
+/// value does not fit the component type.
+pub fn encode_with_schema(values: &[Value], schema: &Schema) -> Result<Vec<u8>, Error> {
+    if values.len() != schema.fields.len() {
+        return Err(Error::Data(format!(
+            "expected {} values, got {}",
+            schema.fields.len(),
+            values.len()
+        )));
+    }
+
+    crate::to_vec(&SchemaValues { values, schema })
+}
+
+struct SchemaValues<'a> {
+    values: &'a [Value],
+    schema: &'a Schema,
+}
+
+impl<'a> Serialize for SchemaValues<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tuple = serializer.serialize_tuple(self.values.len())?;
+
+        for (value, field) in self.values.iter().zip(&self.schema.fields) {
+            tuple.serialize_element(&SchemaValue(value, *field))?;
+        }
+
+        tuple.end()
+    }
+}
+
+struct SchemaValue<'a>(&'a Value, FieldType);
+
+impl<'a> Serialize for SchemaValue<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        fn convert<T: TryFrom<U>, U: Copy + std::fmt::Display, E: serde::ser::Error>(
+            value: U,
+        ) -> Result<T, E> {
+            T::try_from(value).map_err(|_| E::custom(format!("{} out of range", value)))
+        }
+
+        match (self.0, self.1) {
+            (Value::U64(v), FieldType::U8) => serializer.serialize_u8(convert(*v)?),
+            (Value::U64(v), FieldType::U16) => serializer.serialize_u16(convert(*v)?),
+            (Value::U64(v), FieldType::U32) => serializer.serialize_u32(convert(*v)?),
+            (Value::U64(v), FieldType::U64) => serializer.serialize_u64(*v),
+            (Value::I64(v), FieldType::I8) => serializer.serialize_i8(convert(*v)?),
+            (Value::I64(v), FieldType::I16) => serializer.serialize_i16(convert(*v)?),
+            (Value::I64(v), FieldType::I32) => serializer.serialize_i32(convert(*v)?),
+            (Value::I64(v), FieldType::I64) => serializer.serialize_i64(*v),
+            (Value::F64(v), FieldType::F32) => serializer.serialize_f32(*v as f32),
+            (Value::F64(v), FieldType::F64) => serializer.serialize_f64(*v),
+            (Value::Str(v), FieldType::Str) => serializer.serialize_str(v),
+            (Value::Bytes(v), FieldType::Bytes) => serializer.serialize_bytes(v),
+            (Value::Bool(v), FieldType::Bool) => serializer.serialize_bool(*v),
+            (Value::Unit, FieldType::Unit) => serializer.serialize_unit(),
+            (Value::Bytes(v), FieldType::MacAddr) if v.len() == MacAddr::LENGTH => {
+                serializer.serialize_bytes(v)
+            }
+            (Value::Bytes(v), FieldType::Eui64) if v.len() == Eui64::LENGTH => {
+                serializer.serialize_bytes(v)
+            }
+            (value, field) => Err(S::Error::custom(format!(
+                "{:?} does not match {:?}",
+                value, field
+            ))),
+        }
+    }
+}
+
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct ByteBufVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("bytes")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+/// Returns the component types of `T` by driving its `Deserialize`
+/// implementation with placeholder values.
+///
+/// Probing stops at the first component the placeholder cannot satisfy,
+/// such as a sequence or an enum with data, so the returned layout may be
+/// only a prefix of the full layout.
+pub(crate) fn probe_layout<'de, T>() -> Vec<FieldType>
+where
+    T: Deserialize<'de>,
+{
+    probe_components::<T>(None)
+        .into_iter()
+        .map(|(field, _)| field)
+        .collect()
+}
+
+/// Returns the component types of `T` along with the encoding the given
+/// overrides select for each component.
+///
+/// See [`probe_layout`].
+pub(crate) fn probe_components<'de, T>(
+    overrides: Option<&EncodingOverrides>,
+) -> Vec<(FieldType, Option<Encoding>)>
+where
+    T: Deserialize<'de>,
+{
+    let mut state = ProbeState::new(None, None);
+    let _ = T::deserialize(LayoutProbe {
+        state: &mut state,
+        overrides,
+        encoding: None,
+        top: true,
+    });
+
+    state.fields
+}
+
+/// Returns the component types of each field of `T` if it is a struct.
+///
+/// Fields starting from the first one whose layout cannot be probed are
+/// omitted, so the returned list may be shorter than the number of fields.
+pub(crate) fn probe_fields<'de, T>() -> Option<Vec<Vec<FieldType>>>
+where
+    T: Deserialize<'de>,
+{
+    let mut state = ProbeState::new(None, None);
+    state.field_ends = Some(Vec::new());
+    let _ = T::deserialize(LayoutProbe {
+        state: &mut state,
+        overrides: None,
+        encoding: None,
+        top: true,
+    });
+
+    let layout = state.fields;
+    let mut start = 0;
+    let fields = state
+        .field_ends?
+        .into_iter()
+        .map(|end| {
+            let components = layout[start..end]
+                .iter()
+                .map(|(field, _)| *field)
+                .filter(|field| *field != FieldType::Unit)
+                .collect();
+            start = end;
+            components
+        })
+        .collect();
+
+    Some(fields)
+}
+
+/// Returns the encoding the given overrides select for the component of
+/// `T` at the given index.
+///
+/// The components before the index are decoded from the deserializer of
+/// the key instead of given placeholder values, so types that validate
+/// their values can be probed. Returns the decoding error if one of those
+/// components is invalid, and [`Error::UnsupportedType`] if the layout
+/// cannot be probed up to the index.
+pub(crate) fn locate_component<'de, T>(
+    key: &mut Deserializer<'de, SliceReader<'de>>,
+    index: usize,
+    overrides: Option<&EncodingOverrides>,
+) -> Result<Option<Encoding>, Error>
+where
+    T: Deserialize<'de>,
+{
+    let mut state = ProbeState::new(Some(key), Some((index, None)));
+    let result = T::deserialize(LayoutProbe {
+        state: &mut state,
+        overrides,
+        encoding: None,
+        top: true,
+    });
+
+    match (state.located, result) {
+        (Some(encoding), _) => Ok(encoding),
+        (None, Err(error)) if state.key_failed => Err(error),
+        _ => Err(Error::UnsupportedType),
+    }
+}
+
+/// Checks that the next component of the deserializer is a valid value of
+/// the component of `T` at the given index.
+///
+/// The components before the index are decoded from the deserializer of
+/// the key as in [`locate_component`], and the components after it are not
+/// decoded. Returns [`Error::UnsupportedType`] if the layout cannot be
+/// probed up to the index.
+pub(crate) fn check_component<'de, T>(
+    key: &mut Deserializer<'de, SliceReader<'de>>,
+    component: &mut Deserializer<'de, SliceReader<'de>>,
+    index: usize,
+    overrides: Option<&EncodingOverrides>,
+) -> Result<(), Error>
+where
+    T: Deserialize<'de>,
+{
+    let mut state = ProbeState::new(Some(key), Some((index, Some(component))));
+    let result = T::deserialize(LayoutProbe {
+        state: &mut state,
+        overrides,
+        encoding: None,
+        top: true,
+    });
+
+    match (state.passed, result) {
+        (Some(true), _) => Ok(()),
+        (_, Err(error)) if state.located.is_some() || state.key_failed => Err(error),
+        _ => Err(Error::UnsupportedType),
+    }
+}
+
+type SliceDeserializer<'de> = Deserializer<'de, SliceReader<'de>>;
+
+struct ProbeState<'t, 'de> {
+    fields: Vec<(FieldType, Option<Encoding>)>,
+    key: Option<&'t mut SliceDeserializer<'de>>,
+    target: Option<(usize, Option<&'t mut SliceDeserializer<'de>>)>,
+    located: Option<Option<Encoding>>,
+    passed: Option<bool>,
+    key_failed: bool,
+    field_ends: Option<Vec<usize>>,
+}
+
+impl<'t, 'de> ProbeState<'t, 'de> {
+    fn new(
+        key: Option<&'t mut SliceDeserializer<'de>>,
+        target: Option<(usize, Option<&'t mut SliceDeserializer<'de>>)>,
+    ) -> Self {
+        ProbeState {
+            fields: Vec::new(),
+            key,
+            target,
+            located: None,
+            passed: None,
+            key_failed: false,
+            field_ends: None,
+        }
+    }
+}
+
+/// Where the value of a probed component comes from.
+enum Source<'b, 'de> {
+    Placeholder,
+    Decode(&'b mut SliceDeserializer<'de>),
+    Stop,
+}
+
+struct LayoutProbe<'a, 't, 'de> {
+    state: &'a mut ProbeState<'t, 'de>,
+    overrides: Option<&'a EncodingOverrides>,
+    encoding: Option<Encoding>,
+    top: bool,
+}
+
+impl<'a, 't, 'de> LayoutProbe<'a, 't, 'de> {
+    fn nested(&mut self, encoding: Option<Encoding>) -> LayoutProbe<'_, 't, 'de> {
+        LayoutProbe {
+            state: &mut *self.state,
+            overrides: self.overrides,
+            encoding: encoding.or(self.encoding),
+            top: false,
+        }
+    }
+
+    /// Records a component and returns where its value comes from.
+    fn component(&mut self, field: FieldType) -> Source<'_, 'de> {
+        let index = self
+            .state
+            .fields
+            .iter()
+            .filter(|(field, _)| *field != FieldType::Unit)
+            .count();
+        self.state.fields.push((field, self.encoding));
+
+        let state = &mut *self.state;
+        let deserializer = match &mut state.target {
+            Some((target, component)) if *target == index => {
+                state.located = Some(self.encoding);
+
+                match component {
+                    Some(component) => &mut **component,
+                    None => return Source::Stop,
+                }
+            }
+            Some((target, _)) if *target < index => return Source::Placeholder,
+            _ => match &mut state.key {
+                Some(key) => &mut **key,
+                None => return Source::Placeholder,
+            },
+        };
+
+        deserializer.begin_component(index, self.encoding);
+        Source::Decode(deserializer)
+    }
+
+    fn finish<T>(&mut self, result: Result<T, Error>) -> Result<T, Error> {
+        if self.state.located.is_some() {
+            if self.state.passed.is_none() {
+                self.state.passed = Some(result.is_ok());
+            }
+        } else if result.is_err() {
+            self.state.key_failed = true;
+        }
+
+        result
+    }
+}
+
+macro_rules! probe_primitive {
+    ($method:ident, $visit:ident, $field:expr, $value:expr) => {
+        fn $method<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
+            match self.component($field) {
+                Source::Placeholder => visitor.$visit($value),
+                Source::Decode(deserializer) => {
+                    let result = deserializer.$method(visitor);
+                    self.finish(result)
+                }
+                Source::Stop => Err(Error::UnsupportedType),
+            }
+        }
+    };
+}
+
+impl<'a, 't, 'de> serde::Deserializer<'de> for LayoutProbe<'a, 't, 'de> {
+    type Error = Error;
+
+    probe_primitive!(deserialize_bool, visit_bool, FieldType::Bool, false);
+    probe_primitive!(deserialize_i8, visit_i8, FieldType::I8, 0);
+    probe_primitive!(deserialize_i16, visit_i16, FieldType::I16, 0);
+    probe_primitive!(deserialize_i32, visit_i32, FieldType::I32, 0);
+    probe_primitive!(deserialize_i64, visit_i64, FieldType::I64, 0);
+    probe_primitive!(deserialize_u8, visit_u8, FieldType::U8, 0);
+    probe_primitive!(deserialize_u16, visit_u16, FieldType::U16, 0);
+    probe_primitive!(deserialize_u32, visit_u32, FieldType::U32, 0);
+    probe_primitive!(deserialize_u64, visit_u64, FieldType::U64, 0);
+    // There is no 128-bit field type, so these are recorded as bytes.
+    probe_primitive!(deserialize_i128, visit_i128, FieldType::Bytes, 0);
+    probe_primitive!(deserialize_u128, visit_u128, FieldType::Bytes, 0);
+    probe_primitive!(deserialize_f32, visit_f32, FieldType::F32, 0.0);
+    probe_primitive!(deserialize_f64, visit_f64, FieldType::F64, 0.0);
+    probe_primitive!(deserialize_char, visit_char, FieldType::Str, '0');
+    probe_primitive!(deserialize_str, visit_borrowed_str, FieldType::Str, "");
+    probe_primitive!(deserialize_string, visit_borrowed_str, FieldType::Str, "");
+    probe_primitive!(
+        deserialize_bytes,
+        visit_borrowed_bytes,
+        FieldType::Bytes,
+        &[]
+    );
+    probe_primitive!(
+        deserialize_byte_buf,
+        visit_borrowed_bytes,
+        FieldType::Bytes,
+        &[]
+    );
+    probe_primitive!(
+        deserialize_identifier,
+        visit_borrowed_str,
+        FieldType::Str,
+        ""
+    );
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(Error::UnsupportedType)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.state.fields.push((FieldType::Unit, self.encoding));
+        visitor.visit_unit()
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(Error::UnsupportedType)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        mut self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let encoding = self.overrides.and_then(|overrides| overrides.newtype(name));
+        visitor.visit_newtype_struct(self.nested(encoding))
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(Error::UnsupportedType)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(self)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(self)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(Error::UnsupportedType)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(StructProbe {
+            probe: self,
+            fields: fields.iter(),
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.component(FieldType::Str) {
+            Source::Placeholder => {
+                let variant = variants.first().ok_or(Error::UnsupportedType)?;
+                visitor.visit_enum(IntoDeserializer::<Error>::into_deserializer(*variant))
+            }
+            Source::Decode(deserializer) => {
+                let result = deserializer.deserialize_enum(name, variants, visitor);
+                self.finish(result)
+            }
+            Source::Stop => Err(Error::UnsupportedType),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(Error::UnsupportedType)
+    }
+}
+
+impl<'a, 't, 'de> SeqAccess<'de> for LayoutProbe<'a, 't, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.nested(None)).map(Some)
+    }
+}
+
+struct StructProbe<'a, 't, 'de> {
+    probe: LayoutProbe<'a, 't, 'de>,
+    fields: std::slice::Iter<'static, &'static str>,
+}
+
+impl<'a, 't, 'de> SeqAccess<'de> for StructProbe<'a, 't, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        let encoding = match (self.probe.overrides, self.fields.next()) {
+            (Some(overrides), Some(field)) => overrides.field(field),
+            _ => None,
+        };
+
+        let value = seed.deserialize(self.probe.nested(encoding))?;
+
+        if self.probe.top {
+            let len = self.probe.state.fields.len();
+
+            if let Some(ends) = &mut self.probe.state.field_ends {
+                ends.push(len);
+            }
+        }
+
+        Ok(Some(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            FieldType::Str,
+            FieldType::U16,
+            FieldType::I8,
+            FieldType::F64,
+            FieldType::Bytes,
+            FieldType::Bool,
+            FieldType::Unit,
+        ])
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let key = crate::to_vec(&(
+            "abc",
+            5u16,
+            -1i8,
+            1.5f64,
+            serde_bytes::Bytes::new(b"\x01"),
+            true,
+            (),
+        ))
+        .unwrap();
+        let values = decode_with_schema(&key, &schema()).unwrap();
+
+        assert_eq!(
+            values,
+            vec![
+                Value::Str("abc".to_string()),
+                Value::U64(5),
+                Value::I64(-1),
+                Value::F64(1.5),
+                Value::Bytes(vec![1]),
+                Value::Bool(true),
+                Value::Unit,
+            ]
+        );
+        assert_eq!(encode_with_schema(&values, &schema()).unwrap(), key);
+    }
+
+    #[test]
+    fn test_text() {
+        let values = [
+            (Value::Str("a b".to_string()), FieldType::Str, "a b"),
+            (Value::U64(300), FieldType::U16, "300"),
+            (Value::I64(-5), FieldType::I8, "-5"),
+            (Value::F64(0.5), FieldType::F32, "0.5"),
+            (Value::Bytes(vec![0xab]), FieldType::Bytes, "ab"),
+            (Value::Bool(false), FieldType::Bool, "false"),
+            (Value::Unit, FieldType::Unit, ""),
+        ];
+
+        for (value, field, text) in values.iter() {
+            assert_eq!(&value.to_string(), text);
+            assert_eq!(&Value::parse(text, *field).unwrap(), value);
+            assert_eq!(&field.to_string().parse::<FieldType>().unwrap(), field);
+        }
+
+        assert!("u128".parse::<FieldType>().is_err());
+
+        assert!(Value::parse("256", FieldType::U8).is_err());
+        assert!(Value::parse("x", FieldType::Bytes).is_err());
+        assert!(Value::parse("x", FieldType::Unit).is_err());
+
+        assert_eq!(
+            Value::parse("AA:BB:CC:00:11:22", FieldType::MacAddr).unwrap(),
+            Value::Bytes(vec![0xaa, 0xbb, 0xcc, 0x00, 0x11, 0x22])
+        );
+        assert!(Value::parse("aa:bb", FieldType::MacAddr).is_err());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let schema = Schema::new(vec![FieldType::Str, FieldType::U16]);
+
+        assert!(decode_with_schema(b"abc", &schema).is_err());
+        assert!(decode_with_schema(b"abc:0001:02", &schema).is_err());
+        assert!(decode_with_schema(b"abc:01", &schema).is_err());
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        let schema = Schema::new(vec![FieldType::U8]);
+
+        assert!(encode_with_schema(&[], &schema).is_err());
+        assert!(encode_with_schema(&[Value::U64(256)], &schema).is_err());
+        assert!(encode_with_schema(&[Value::I64(1)], &schema).is_err());
+        assert!(encode_with_schema(
+            &[Value::Bytes(vec![1])],
+            &Schema::new(vec![FieldType::MacAddr])
+        )
+        .is_err());
+        assert_eq!(
+            encode_with_schema(&[Value::U64(255)], &schema).unwrap(),
+            b"ff"
+        );
+    }
+
+    #[test]
+    fn test_replace_component() {
+        type Layout = ((), String, i16, serde_bytes::ByteBuf);
+
+        let key = crate::to_vec(&((), "a", -1i16, serde_bytes::Bytes::new(b"\x01"))).unwrap();
+
+        assert_eq!(&key, b"a:7fff:01");
+        assert_eq!(
+            replace_component::<Layout>(&key, 0, "tenant").unwrap(),
+            b"tenant:7fff:01"
+        );
+        assert_eq!(
+            replace_component::<Layout>(&key, 1, &2i16).unwrap(),
+            b"a:8002:01"
+        );
+        assert_eq!(
+            replace_component::<Layout>(&key, 2, serde_bytes::Bytes::new(b"\xab\xcd")).unwrap(),
+            b"a:7fff:abcd"
+        );
+        assert!(matches!(
+            replace_component::<Layout>(&key, 1, &2u8),
+            Err(Error::WrongWidth(..))
+        ));
+        assert!(matches!(
+            replace_component::<Layout>(&key, 0, "a:b"),
+            Err(Error::Data(_))
+        ));
+        assert!(matches!(
+            replace_component::<Layout>(&key, 1, &(1i16, 2i16)),
+            Err(Error::Data(_))
+        ));
+        assert_eq!(
+            replace_component::<Layout>(&key, 2, serde_bytes::Bytes::new(b"")).unwrap(),
+            b"a:7fff:"
+        );
+        assert_eq!(
+            replace_component::<Layout>(b"a:7fff:xx", 1, &2i16).unwrap(),
+            b"a:8002:xx"
+        );
+        assert!(replace_component::<Layout>(b"a:xx:01", 2, serde_bytes::Bytes::new(b"")).is_err());
+        assert!(matches!(
+            replace_component::<Layout>(&key, 3, "x"),
+            Err(Error::Other(_))
+        ));
+        assert!(matches!(
+            replace_component::<Layout>(b"a", 1, &2i16),
+            Err(Error::Other(_))
+        ));
+    }
+
+    #[test]
+    fn test_replace_component_types() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum Kind {
+            User,
+            Group,
+        }
+
+        let key = crate::to_vec(&(Kind::User, 5u32)).unwrap();
+
+        assert_eq!(
+            replace_component::<(Kind, u32)>(&key, 0, &Kind::Group).unwrap(),
+            b"Group:00000005"
+        );
+        assert!(replace_component::<(Kind, u32)>(&key, 0, "Bogus").is_err());
+
+        let key = crate::to_vec(&('a', 5u32)).unwrap();
+        assert!(replace_component::<(char, u32)>(&key, 0, "abc").is_err());
+        assert_eq!(
+            replace_component::<(char, u32)>(&key, 0, &'b').unwrap(),
+            b"b:00000005"
+        );
+
+        let key = crate::to_vec(&(1u128, 5u32)).unwrap();
+        let key = replace_component::<(u128, u32)>(&key, 1, &6u32).unwrap();
+        assert_eq!(crate::from_slice::<(u128, u32)>(&key).unwrap(), (1, 6));
+
+        let code = "US".parse::<crate::types::Alpha2>().unwrap();
+        let key = crate::to_vec(&(code, 5u32)).unwrap();
+        let key = replace_component::<(crate::types::Alpha2, u32)>(&key, 1, &6u32).unwrap();
+        assert_eq!(&key, b"US:00000006");
+    }
+
+    #[test]
+    fn test_replace_component_config() {
+        let config = crate::config::Config::new()
+            .with_deliminator("/")
+            .with_sort_spec(crate::config::SortSpec::new().with_descending(1));
+        let key = config.to_vec(&("a:b", 1u8, 2u8)).unwrap();
+
+        assert_eq!(&key, b"a:b/fe/02");
+
+        let key = config
+            .replace_component::<(String, u8, u8)>(&key, 1, &3u8)
+            .unwrap();
+        assert_eq!(&key, b"a:b/fc/02");
+        assert_eq!(
+            config.from_slice::<(String, u8, u8)>(&key).unwrap(),
+            ("a:b".to_string(), 3, 2)
+        );
+    }
+
+    #[test]
+    fn test_decode_fields() {
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Inner {
+            flag: bool,
+            data: serde_bytes::ByteBuf,
+        }
+
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Outer {
+            name: String,
+            inner: Inner,
+            score: f32,
+        }
+
+        let key = b"a:true:abcd:bff00000";
+        let fields = decode_fields::<Outer>(key).unwrap();
+
+        assert_eq!(
+            fields.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("data", Value::Bytes(vec![0xab, 0xcd])),
+                ("flag", Value::Bool(true)),
+                ("name", Value::Str("a".to_string())),
+                ("score", Value::F64(1.875)),
+            ]
+        );
+
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct Pair {
+            pair: (u8, u8),
+        }
+
+        assert!(decode_fields::<Pair>(b"01:02").is_err());
+        assert!(decode_fields::<(u8, u8)>(b"01:02").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_length_budget() {
+        let schema = Schema::new(vec![
+            FieldType::Str,
+            FieldType::Unit,
+            FieldType::I32,
+            FieldType::Bool,
+            FieldType::MacAddr,
+        ]);
+        let budget = LengthBudget::new(40)
+            .with_max_len(0, 10)
+            .with_deliminator_len(2);
+
+        assert_eq!(budget.limit(), 40);
+        assert_eq!(
+            budget.max_encoded_len(&schema).unwrap(),
+            10 + 8 + 5 + 12 + 3 * 2
+        );
+        assert!(matches!(
+            budget.check(&schema),
+            Err(Error::KeyTooLong(41, 40))
+        ));
+        assert_eq!(
+            budget
+                .clone()
+                .with_deliminator_len(1)
+                .check(&schema)
+                .unwrap(),
+            38
+        );
+        assert_eq!(
+            budget
+                .with_percent_encoding(true)
+                .max_encoded_len(&schema)
+                .unwrap(),
+            30 + 8 + 5 + 12 + 3 * 2
+        );
+
+        assert!(matches!(
+            LengthBudget::new(40).max_encoded_len(&schema),
+            Err(Error::Other(_))
+        ));
+        assert_eq!(
+            LengthBudget::new(0)
+                .max_encoded_len(&Schema::default())
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_compatible_with() {
+        use FieldType::*;
+
+        let old = Schema::new(vec![Str, U32, Bool]);
+        let check = |fields: Vec<FieldType>| Schema::new(fields).compatible_with(&old);
+
+        assert!(check(vec![Str, U32, Bool]).is_empty());
+        assert!(check(vec![Str, Unit, U32, Bool, I8]).is_empty());
+        assert_eq!(
+            check(vec![Str, I8, U32, Bool]),
+            vec![Incompatibility::Added(1, I8)]
+        );
+        assert_eq!(
+            check(vec![U32, Str, Bool]),
+            vec![Incompatibility::Reordered]
+        );
+        assert_eq!(
+            check(vec![Str, U64, Bool]),
+            vec![Incompatibility::TypeChanged(1, U32, U64)]
+        );
+        assert_eq!(
+            check(vec![Str, U32]),
+            vec![Incompatibility::Removed(2, Bool)]
+        );
+        assert_eq!(
+            Incompatibility::TypeChanged(1, U32, U64).to_string(),
+            "component 1 changed from u32 to u64"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Versioned document keys
+//!
+//! Multiple versions of a document are stored under keys laid out as
+//! `doc_id:version` where the version is a [`Desc<u64>`]. Since newer
+//! versions sort first, the latest version of a document is the first key
+//! of a prefix scan.
+//!
+//! Example:
+//!
+//! ```rust
+//! use strkey::versioned::{self, VersionKey};
+//!
+//! # fn main() -> Result<(), strkey::Error> {
+//! let key = strkey::to_vec(&VersionKey::new("doc1", 3))?;
+//! assert_eq!(&key, b"doc1:fffffffffffffffc");
+//!
+//! let prefix = versioned::latest_prefix(&"doc1")?;
+//! assert!(key.starts_with(&prefix));
+//!
+//! let range = versioned::versions_range(&"doc1", 2..=5)?.unwrap();
+//! assert!(range.contains(&key));
+//! # Ok(())
+//! # }
+//! ```
+use std::ops::RangeInclusive;
+
+use serde::Serialize;
+
+use crate::{error::Error, types::Desc};
+
+/// Key of a version of a document.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VersionKey<D> {
+    /// The document ID.
+    pub doc_id: D,
+
+    /// The version number.
+    pub version: Desc<u64>,
+}
+
+impl_struct_serde!(VersionKey<D> {
+    doc_id: D,
+    version: Desc<u64>,
+});
+
+impl<D> VersionKey<D> {
+    /// Construct a key for the given version of a document.
+    pub fn new(doc_id: D, version: u64) -> Self {
+        Self {
+            doc_id,
+            version: Desc(version),
+        }
+    }
+
+    /// Returns the version number.
+    pub fn version(&self) -> u64 {
+        self.version.0
+    }
+}
+
+/// Returns the encoded prefix of all versions of the document.
+///
+/// Keys matching the prefix are ordered from the latest version to the
+/// oldest, so the first key of a prefix scan is the latest version.
+pub fn latest_prefix<D>(doc_id: &D) -> Result<Vec<u8>, Error>
+where
+    D: ?Sized + Serialize,
+{
+    crate::to_prefix(doc_id)
+}
+
+/// Returns the encoded key range of the given versions of the document.
+///
+/// The returned range is inclusive on both ends and begins with the newest
+/// version. Returns `None` if the range of versions is empty.
+///
+/// To find the latest version as of a version `v`, scan `0..=v` and take the
+/// first key.
+pub fn versions_range<D>(
+    doc_id: &D,
+    versions: RangeInclusive<u64>,
+) -> Result<Option<RangeInclusive<Vec<u8>>>, Error>
+where
+    D: ?Sized + Serialize,
+{
+    if versions.is_empty() {
+        return Ok(None);
+    }
+
+    let start = crate::to_vec(&VersionKey::new(doc_id, *versions.end()))?;
+    let end = crate::to_vec(&VersionKey::new(doc_id, *versions.start()))?;
+
+    Ok(Some(start..=end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let key = VersionKey::new(7u32, 2);
+        let encoded = crate::to_vec(&key).unwrap();
+
+        assert_eq!(&encoded, b"00000007:fffffffffffffffd");
+
+        let decoded = crate::from_slice::<VersionKey<u32>>(&encoded).unwrap();
+        assert_eq!(decoded, key);
+        assert_eq!(decoded.version(), 2);
+    }
+
+    #[test]
+    fn test_latest_first() {
+        let mut keys = (0..5)
+            .map(|version| crate::to_vec(&VersionKey::new("doc", version)).unwrap())
+            .collect::<Vec<_>>();
+        keys.sort();
+
+        let latest = crate::from_slice::<VersionKey<&str>>(&keys[0]).unwrap();
+        assert_eq!(latest.version(), 4);
+
+        let prefix = latest_prefix("doc").unwrap();
+        assert!(keys.iter().all(|key| key.starts_with(&prefix)));
+    }
+
+    #[test]
+    fn test_versions_range() {
+        let range = versions_range("doc", 2..=4).unwrap().unwrap();
+
+        for version in 0..7 {
+            let key = crate::to_vec(&VersionKey::new("doc", version)).unwrap();
+            assert_eq!(range.contains(&key), (2..=4).contains(&version));
+        }
+
+        let key = crate::to_vec(&VersionKey::new("doc2", 3)).unwrap();
+        assert!(!range.contains(&key));
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = versions_range("doc", 4..=2).unwrap();
+        assert!(empty.is_none());
+    }
+}
@@ -0,0 +1,22 @@
+//! WebAssembly bindings
+//!
+//! Functions taking and returning JSON strings so they can be wrapped with
+//! `wasm-bindgen` without exposing Rust types. This lets browser-side tools
+//! generate the same keys as the backend. The schema and values use the
+//! format described in the [`crate::json`] module.
+//!
+//! Example:
+//!
+//! ```rust
+//! # fn main() -> Result<(), strkey::Error> {
+//! let schema = r#"["str", "u32"]"#;
+//!
+//! let key = strkey::wasm::encode_json_with_schema(r#"["account", 1234]"#, schema)?;
+//! assert_eq!(key, "account:000004d2");
+//!
+//! let values = strkey::wasm::decode_to_json(&key, schema)?;
+//! assert_eq!(values, r#"["account",1234]"#);
+//! # Ok(())
+//! # }
+//! ```
+pub use crate::json::{decode_to_json, encode_json_with_schema};